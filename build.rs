@@ -6,8 +6,10 @@ const LANGUAGE_DIR: &str = "src/book/language";
 fn main() {
     let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
 
+    let (grammar, terminals) = collect_grammar();
     let grammar_path = out_dir.join("parser.rustylr");
-    fs::write(&grammar_path, collect_grammar()).unwrap();
+    fs::write(&grammar_path, grammar).unwrap();
+    fs::write(out_dir.join("terminals.rs"), terminals).unwrap();
 
     let parser_path = out_dir.join("parser.rs");
     rusty_lr::build::Builder::new()
@@ -19,7 +21,9 @@ fn main() {
         .build(parser_path.to_str().unwrap());
 }
 
-fn collect_grammar() -> String {
+/// The rusty_lr grammar, and the list of terminals that `parse_program` uses to tell which tokens
+/// it expected.
+fn collect_grammar() -> (String, String) {
     println!("cargo:rerun-if-changed={LANGUAGE_DIR}");
 
     let mut grammar = grammar::Grammar::default();
@@ -39,7 +43,8 @@ fn collect_grammar() -> String {
 
     let declarations = lexer.rustylr_declarations().unwrap();
     let productions = grammar::rustylr::render_rustylr(&grammar, &lexer).unwrap();
-    format!("use crate::language::*;\n\n%%\n\n{declarations}\n{productions}")
+    let grammar = format!("use crate::language::*;\n\n%%\n\n{declarations}\n{productions}");
+    (grammar, lexer.rust_terminals().unwrap())
 }
 
 fn literate_markdown(content: &str) -> String {
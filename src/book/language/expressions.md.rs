@@ -10,11 +10,11 @@ use crate::language::*; //#
 //@
 //@ ExpressionWithoutBlock -> Expression:
 //@     attrs=OuterAttribute* kind=ExpressionWithoutBlockNoAttrs
-//@     => Expression { attrs, kind }
+//@     => Expression { attrs, kind, span, origin: Origin::new() }
 //@
 //@ ExpressionWithBlock -> Expression:
 //@     attrs=OuterAttribute* kind=ExpressionWithBlockNoAttrs
-//@     => Expression { attrs, kind }
+//@     => Expression { attrs, kind, span, origin: Origin::new() }
 //@
 //@ ExpressionWithoutBlockNoAttrs -> ExpressionKind:
 //@     | literal=LiteralExpression => ExpressionKind::Literal(literal),
@@ -23,17 +23,31 @@ use crate::language::*; //#
 //@     | grouped=GroupedExpression => ExpressionKind::Grouped(grouped),
 //@     | tuple=TupleExpression => ExpressionKind::Tuple(tuple),
 //@     | tuple_indexing=TupleIndexingExpression => ExpressionKind::TupleIndexing(tuple_indexing),
+//@     | adt=StructExpression => ExpressionKind::Struct(adt),
+//@     | path=VariantPath => ExpressionKind::VariantPath(path),
+//@     | variant=EnumVariantExpression => ExpressionKind::EnumVariant(variant),
+//@     | field=FieldExpression => ExpressionKind::Field(field),
+//@     | discriminant=DiscriminantExpression => ExpressionKind::Discriminant(discriminant),
+//@     | path=DiscriminantOfExpression => ExpressionKind::DiscriminantOf(path),
 //@     | call=CallExpression => ExpressionKind::Call(call),
+//@     | expr=BreakExpression => ExpressionKind::Break(expr),
+//@     | expr=ContinueExpression => ExpressionKind::Continue(expr),
 //@
 //@ ExpressionWithBlockNoAttrs -> ExpressionKind:
 //@     | expr=LabelBlockExpression => ExpressionKind::Block(expr),
 //@     | expr=IfExpression => ExpressionKind::If(expr),
+//@     | expr=LoopExpression => ExpressionKind::Loop(expr),
+//@     | expr=MatchExpression => ExpressionKind::Match(expr),
 //@ ```
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct Expression {
     pub attrs: Vec<OuterAttribute>,
     pub kind: ExpressionKind,
+    #[serde(default)] //#
+    pub span: Span,
+    #[serde(default = "Origin::new")] //#
+    pub origin: Origin,
 }
 
 impl Expression {
@@ -41,12 +55,24 @@ impl Expression {
         Self {
             attrs: vec![],
             kind,
+            span: Span::dummy(),
+            origin: Origin::new(),
+        }
+    }
+
+    /// A new expression made from `source`. It points at the same source code.
+    pub fn derived_from(source: &Expression, kind: ExpressionKind) -> Self {
+        Self {
+            attrs: vec![],
+            kind,
+            span: source.span,
+            origin: source.origin.derive(),
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub enum ExpressionKind {
     Literal(LiteralExpression),
     Path(PathExpression),
@@ -54,8 +80,22 @@ pub enum ExpressionKind {
     Grouped(Box<Expression>),
     Block(BlockExpression),
     If(IfExpression),
+    /// A `let` condition, which may only appear in the condition of an `if` or `while`.
+    Let(LetExpression),
+    Loop(LoopExpression),
+    Match(MatchExpression),
+    Break(BreakExpression),
+    Continue(ContinueExpression),
     Tuple(Vec<Expression>),
     TupleIndexing(TupleIndexingExpression),
+    Struct(StructExpression),
+    /// A path to an enum variant, e.g. a unit variant or a tuple variant constructor.
+    VariantPath(VariantPath),
+    EnumVariant(EnumVariantExpression),
+    Field(FieldExpression),
+    EnumProjection(EnumProjectionExpression),
+    Discriminant(DiscriminantExpression),
+    DiscriminantOf(VariantPath),
     Call(CallExpression),
     /// Expressions that don't exist in the surface language, that we invented for the purpose of
     /// enabling some desugarings.
@@ -67,16 +107,26 @@ pub enum ExpressionKind {
 pub mod block_expressions;
 #[path = "expressions/call-exprs.md.rs"]
 pub mod call_expressions;
+#[path = "expressions/enum-exprs.md.rs"]
+pub mod enum_expressions;
+#[path = "expressions/field-exprs.md.rs"]
+pub mod field_expressions;
 #[path = "expressions/grouped-exprs.md.rs"]
 pub mod grouped_expressions;
 #[path = "expressions/if-exprs.md.rs"]
 pub mod if_expressions;
 #[path = "expressions/literal-exprs.md.rs"]
 pub mod literal_expressions;
+#[path = "expressions/loop-exprs.md.rs"]
+pub mod loop_expressions;
+#[path = "expressions/match-exprs.md.rs"]
+pub mod match_expressions;
 #[path = "expressions/operator-exprs.md.rs"]
 pub mod operator_expressions;
 #[path = "expressions/path-exprs.md.rs"]
 pub mod path_expressions;
+#[path = "expressions/struct-exprs.md.rs"]
+pub mod struct_expressions;
 #[path = "expressions/tuple-exprs.md.rs"]
 pub mod tuple_expressions;
 #[path = "expressions/virtual-exprs.md.rs"]
@@ -84,9 +134,14 @@ pub mod virtual_expressions;
 
 pub use block_expressions::*;
 pub use call_expressions::*;
+pub use enum_expressions::*;
+pub use field_expressions::*;
 pub use if_expressions::*;
 pub use literal_expressions::*;
+pub use loop_expressions::*;
+pub use match_expressions::*;
 pub use operator_expressions::*;
 pub use path_expressions::*;
+pub use struct_expressions::*;
 pub use tuple_expressions::*;
 pub use virtual_expressions::*;
@@ -10,14 +10,14 @@ use crate::language::*; //#
 //@         statements=Statement*
 //@         tail=ExpressionWithoutBlock?
 //@     `}`
-//@     => BlockExpression { label: None, inner_attrs, statements, tail: tail.map(Box::new) }
+//@     => BlockExpression { label: None, inner_attrs, statements, tail: tail.map(Box::new), origin: Origin::new() }
 //@
 //@ BlockExpressionNoInnerAttributes -> BlockExpression:
 //@     `{`
 //@         statements=Statement*
 //@         tail=ExpressionWithoutBlock?
 //@     `}`
-//@     => BlockExpression { label: None, inner_attrs: vec![], statements, tail: tail.map(Box::new) }
+//@     => BlockExpression { label: None, inner_attrs: vec![], statements, tail: tail.map(Box::new), origin: Origin::new() }
 //@
 //@ LabelBlockExpression -> BlockExpression:
 //@     label=BlockLabel? block=BlockExpression
@@ -26,15 +26,32 @@ use crate::language::*; //#
 //@ BlockLabel -> String: label=LIFETIME `:` => label
 //@ ```
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct BlockExpression {
     pub label: Option<String>,
     pub inner_attrs: Vec<InnerAttribute>,
     pub statements: Vec<Statement>,
     pub tail: Option<Box<Expression>>,
+    #[serde(default = "Origin::new")] //#
+    pub origin: Origin,
 }
 
 impl BlockExpression {
+    /// A new unlabeled block made from the node with origin `source`.
+    pub fn derived_from(
+        source: &Origin,
+        statements: Vec<Statement>,
+        tail: Option<Expression>,
+    ) -> Self {
+        BlockExpression {
+            label: None,
+            inner_attrs: vec![],
+            statements,
+            tail: tail.map(Box::new),
+            origin: source.derive(),
+        }
+    }
+
     pub fn with_label(mut self, label: Option<String>) -> Self {
         self.label = label;
         self
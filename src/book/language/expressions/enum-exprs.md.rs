@@ -0,0 +1,131 @@
+use crate::language::*; //#
+use std::collections::{HashMap, HashSet}; //#
+//@ # Enum Expressions
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+//@ A variant of an [enum](../items/enums.md.rs) is named by a path `Enum::Variant`. Like for
+//@ structs, a variant value is created with a struct expression, e.g. `Enum::Variant { a: true }`
+//@ [ref:expr.struct.intro]. Tuple variant constructors `Enum::Variant(true)` and unit variants
+//@ `Enum::Variant` are parsed as call and path expressions respectively; they get turned into
+//@ struct expressions in [Misc Expression Desugarings](../../pipeline/misc-expr-desugarings.md.rs).
+//@
+//@ ```grammar
+//@ VariantPath:
+//@     enum_name=IDENTIFIER `::` variant=IDENTIFIER
+//@     => VariantPath { enum_name, variant }
+//@
+//@ EnumVariantExpression:
+//@     path=VariantPath `{` fields=StructExprFields? `}`
+//@     => EnumVariantExpression { path, fields: fields.unwrap_or_default() }
+//@ ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct VariantPath {
+    pub enum_name: Identifier,
+    pub variant: Identifier,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct EnumVariantExpression {
+    pub path: VariantPath,
+    pub fields: Vec<StructExprField>,
+}
+
+//@ ## Enum projections
+//@
+//@ To desugar enum patterns we need to talk about the places inside an enum variant. We use the
+//@ [Enum Projections](../../features/enum-projections.md) feature for that: if `$place` has an enum
+//@ type, `$place.$Variant.$field` denotes the field `$field` of the `$Variant` variant stored at
+//@ `$place`. Using it is UB if the enum doesn't currently have that variant. The projection
+//@ records the path of the variant, since different enums may have variants of the same name.
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct EnumProjectionExpression {
+    pub expression: Box<Expression>,
+    pub variant: VariantPath,
+    pub field: FieldName,
+}
+
+//@ An enum projection looks just like two nested field accesses, so our grammar can't tell them
+//@ apart. Instead the parser reads them as field accesses. Once [Type
+//@ Checking](../../pipeline/type-checking.md.rs) knows the type of `$place`, it turns
+//@ `$place.$name.$field` into an enum projection whenever that type is an enum with a `$name`
+//@ variant, seeing through references like field accesses do.
+impl Program {
+    /// Turn the nested field accesses that are enum projections into enum projections. `types` are
+    /// the types of the nodes of the program, by node id.
+    pub fn resolve_enum_projections(&mut self, types: &HashMap<NodeId, Type>) {
+        let mut variants = HashSet::new();
+        self.visit_all_infallible(|adt: &Enum| {
+            for variant in &adt.variants {
+                variants.insert(VariantPath {
+                    enum_name: adt.name.clone(),
+                    variant: variant.name.clone(),
+                });
+            }
+        });
+        self.visit_all_mut_infallible(|expression: &mut Expression| {
+            let (base, field) = match &mut expression.kind {
+                ExpressionKind::Field(FieldExpression { expression, field }) => {
+                    (expression, FieldName::Named(field.clone()))
+                }
+                ExpressionKind::TupleIndexing(TupleIndexingExpression { expression, index }) => {
+                    (expression, FieldName::Index(*index))
+                }
+                _ => return,
+            };
+            let ExpressionKind::Field(variant_access) = &mut base.kind else {
+                return;
+            };
+            let Some(mut ty) = types.get(&variant_access.expression.origin.id) else {
+                return;
+            };
+            while let TypeKind::Ref(_, _, pointee) = &ty.kind {
+                ty = pointee;
+            }
+            let TypeKind::Path(enum_name, _) = &ty.kind else {
+                return;
+            };
+            let variant = VariantPath {
+                enum_name: enum_name.clone(),
+                variant: variant_access.field.clone(),
+            };
+            if !base.attrs.is_empty() || !variants.contains(&variant) {
+                return;
+            }
+            let place = std::mem::replace(
+                &mut variant_access.expression,
+                Box::new(Expression::new(ExpressionKind::Tuple(vec![]))),
+            );
+            expression.kind = ExpressionKind::EnumProjection(EnumProjectionExpression {
+                expression: place,
+                variant,
+                field,
+            });
+        });
+    }
+}
+
+//@ ## Discriminant access
+//@
+//@ Following [Enum Discriminant Access](../../features/enum-discriminant.md),
+//@ `$place.enum#discriminant` is a place expression that denotes the discriminant of the enum
+//@ stored at `$place`, and `discriminant_of!(Enum, Variant)` is the discriminant value of the given
+//@ variant.
+//@
+//@ ```grammar
+//@ DiscriminantExpression:
+//@     expression=Expression `.` `enum#discriminant` #[prec = `.`]
+//@     => DiscriminantExpression { expression: Box::new(expression) }
+//@
+//@ DiscriminantOfExpression -> VariantPath:
+//@     `discriminant_of!` `(` enum_name=IDENTIFIER `,` variant=IDENTIFIER `)`
+//@     => VariantPath { enum_name, variant }
+//@ ```
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct DiscriminantExpression {
+    pub expression: Box<Expression>,
+}
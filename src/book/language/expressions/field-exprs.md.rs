@@ -0,0 +1,20 @@
+use crate::language::*; //#
+//@ # Field Access Expressions
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+//@ A field expression is a place expression that denotes a named field of a struct
+//@ [ref:expr.field.intro]. Fields of tuple structs are accessed with
+//@ [tuple indexing](tuple-exprs.md.rs).
+//@
+//@ ```grammar
+//@ FieldExpression:
+//@     expression=Expression `.` field=IDENTIFIER #[prec = `.`]
+//@     => FieldExpression { expression: Box::new(expression), field }
+//@ ```
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct FieldExpression {
+    pub expression: Box<Expression>,
+    pub field: Identifier,
+}
@@ -11,16 +11,53 @@ use crate::language::*; //#
 //@       => IfExpression { condition: Box::new(condition), then_branch: Box::new(then_branch.into()), else_branch: Some(Box::new(else_branch)) },
 //@
 //@ Conditions -> Expression:
-//@     condition=Expression => condition
+//@     | condition=Expression => condition,
+//@     | chain=LetChain => chain,
 //@
 //@ IfExpressionElse -> Expression:
-//@     | block=BlockExpression => block.into(),
-//@     | if_expression=IfExpression => Expression::new(ExpressionKind::If(if_expression)),
+//@     | block=BlockExpression => Expression { span, ..block.into() },
+//@     | if_expression=IfExpression
+//@       => Expression { attrs: vec![], kind: ExpressionKind::If(if_expression), span, origin: Origin::new() },
 //@ ```
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct IfExpression {
     pub condition: Box<Expression>,
     pub then_branch: Box<Expression>,
     pub else_branch: Option<Box<Expression>>,
 }
+
+//@ The condition of an `if` can also be a chain of `let` conditions and boolean conditions,
+//@ separated by `&&` [ref:expr.if.chains]. The `if` branch is taken if every `let` pattern matches
+//@ and every boolean condition is `true`, evaluated from left to right. We only accept chains that
+//@ contain at least one `let`; the others are plain boolean expressions.
+//@
+//@ ```grammar
+//@ LetChain -> Expression:
+//@     | condition=LetCondition => condition,
+//@     | chain=LetChain `&&` condition=LetCondition => LetExpression::and(chain, condition),
+//@     | chain=LetChain `&&` condition=Expression => LetExpression::and(chain, condition),
+//@
+//@ LetCondition -> Expression:
+//@     attrs=OuterAttribute* `let` pattern=PatternNoTopAlt `=` scrutinee=Expression #[prec = LET_SCRUTINEE]
+//@     => Expression { attrs, kind: ExpressionKind::Let(LetExpression { pattern, scrutinee: Box::new(scrutinee) }), span, origin: Origin::new() }
+//@ ```
+//@
+//@ A scrutinee can't be a lazy boolean expression: in `if let p = a && b`, the `&& b` continues
+//@ the chain [ref:expr.if.chains]. The made-up `LET_SCRUTINEE` precedence, between `&&`
+//@ and the comparison operators, makes the parser end the scrutinee there.
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct LetExpression {
+    pub pattern: Pattern,
+    pub scrutinee: Box<Expression>,
+}
+
+impl LetExpression {
+    /// Chain two conditions with `&&`.
+    pub fn and(left: Expression, right: Expression) -> Expression {
+        Expression::new(ExpressionKind::Operator(Box::new(
+            OperatorExpression::LazyAnd(left, right),
+        )))
+    }
+}
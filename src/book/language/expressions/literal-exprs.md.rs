@@ -11,9 +11,26 @@ use crate::language::*; //#
 //@     | `false` => LiteralExpression::Bool(false),
 //@ ```
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub enum LiteralExpression {
     String(String),
-    Integer(u128),
+    Integer(IntegerLiteral),
     Bool(bool),
 }
+
+//@ ## Integer literals
+//@
+//@ Integer literals may be written in decimal, hexadecimal (`0x`), octal (`0o`) or binary (`0b`),
+//@ with `_` separators, and may end with a suffix that gives their type, e.g. `0xff_u8`
+//@ [ref:lex.token.literal.int]. We record the value and the suffix; the original spelling is not
+//@ preserved.
+//@
+//@ Without a suffix the type of the literal is inferred from context. Until we have type
+//@ inference, backends pick the expected type where they know it and default to `usize`
+//@ otherwise.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct IntegerLiteral {
+    pub value: u128,
+    pub suffix: Option<IntType>,
+}
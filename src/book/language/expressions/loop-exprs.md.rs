@@ -0,0 +1,64 @@
+use crate::language::*; //#
+//@ # Loop Expressions
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+//@ ```grammar
+//@ LoopExpression:
+//@     label=BlockLabel? kind=LoopKind
+//@     => LoopExpression { label, kind }
+//@
+//@ LoopKind:
+//@     | `loop` body=BlockExpression => LoopKind::Infinite(body),
+//@     | `while` condition=Conditions body=BlockExpression
+//@       => LoopKind::While { condition: Box::new(condition), body },
+//@ ```
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct LoopExpression {
+    pub label: Option<String>,
+    pub kind: LoopKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub enum LoopKind {
+    /// `loop { .. }` repeats its body until a `break` exits it [ref:expr.loop.infinite].
+    Infinite(BlockExpression),
+    /// `while $condition { .. }` repeats its body as long as the condition is `true`
+    /// [ref:expr.loop.while].
+    While {
+        condition: Box<Expression>,
+        body: BlockExpression,
+    },
+}
+
+//@ `break` exits the innermost enclosing loop, or the loop or block with the given label
+//@ [ref:expr.loop.break]. A `break` out of a `loop` may carry a value, which becomes the value of
+//@ that loop [ref:expr.loop.break-value].
+//@
+//@ ```grammar
+//@ BreakExpression:
+//@     `break` label=LIFETIME? value=Expression? #[prec = `break`]
+//@     => BreakExpression { label, value: value.map(Box::new) }
+//@ ```
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct BreakExpression {
+    pub label: Option<String>,
+    pub value: Option<Box<Expression>>,
+}
+
+//@ `continue` jumps back to the start of the innermost enclosing loop, or of the loop with the
+//@ given label [ref:expr.loop.continue].
+//@
+//@ ```grammar
+//@ ContinueExpression:
+//@     `continue` label=LIFETIME?
+//@     => ContinueExpression { label }
+//@ ```
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct ContinueExpression {
+    pub label: Option<String>,
+}
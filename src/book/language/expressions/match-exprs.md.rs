@@ -0,0 +1,48 @@
+use crate::language::*; //#
+//@ # Match Expressions
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+//@ A `match` expression branches on a pattern: the scrutinee is matched against the pattern of
+//@ each arm in turn, and the first arm whose pattern matches and whose guard, if any, evaluates to
+//@ `true` is executed [ref:expr.match.intro]. The scrutinee is a place expression context
+//@ [ref:expr.match.scrutinee].
+//@
+//@ Arms whose body is an expression without a block must be followed by a comma, except for the last
+//@ one [ref:expr.match.syntax].
+//@
+//@ ```grammar
+//@ MatchExpression:
+//@     `match` scrutinee=Expression `{` arms=MatchArm* last=LastMatchArm? `}`
+//@     => MatchExpression { scrutinee: Box::new(scrutinee), arms: arms.into_iter().chain(last).collect() }
+//@
+//@ MatchArm:
+//@     | attrs=OuterAttribute* pattern=PatternNoTopAlt guard=MatchArmGuard? `=>` body=ExpressionWithoutBlock `,`
+//@       => MatchArm { attrs, pattern, guard: guard.map(Box::new), body: Box::new(body) },
+//@     | attrs=OuterAttribute* pattern=PatternNoTopAlt guard=MatchArmGuard? `=>` body=ExpressionWithBlock `,`?
+//@       => MatchArm { attrs, pattern, guard: guard.map(Box::new), body: Box::new(body) },
+//@
+//@ LastMatchArm -> MatchArm:
+//@     attrs=OuterAttribute* pattern=PatternNoTopAlt guard=MatchArmGuard? `=>` body=ExpressionWithoutBlock
+//@     => MatchArm { attrs, pattern, guard: guard.map(Box::new), body: Box::new(body) }
+//@
+//@ MatchArmGuard -> Expression:
+//@     `if` guard=Expression => guard
+//@ ```
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct MatchExpression {
+    pub scrutinee: Box<Expression>,
+    pub arms: Vec<MatchArm>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct MatchArm {
+    pub attrs: Vec<OuterAttribute>,
+    pub pattern: Pattern,
+    /// An extra condition, evaluated after the pattern matched and with its bindings in scope
+    /// [ref:expr.match.guard].
+    pub guard: Option<Box<Expression>>,
+    pub body: Box<Expression>,
+}
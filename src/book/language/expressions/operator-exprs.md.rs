@@ -7,44 +7,228 @@ use crate::language::*; //#
 //@ OperatorExpression:
 //@     | e=BorrowExpression => OperatorExpression::Borrow(e),
 //@     | e=DereferenceExpression => OperatorExpression::Dereference(e),
+//@     | e=NegationExpression => e,
 //@     | e=ArithmeticOrLogicalExpression => e,
+//@     | e=ComparisonExpression => e,
+//@     | e=LazyBooleanExpression => e,
 //@     | e=AssignmentExpression => e,
+//@     | e=CompoundAssignmentExpression => e,
 //@
 //@ BorrowExpression:
-//@     `&` mutability=Mutability expression=Expression #[prec = `&`]
-//@     => BorrowExpression { mutability, expression: Box::new(expression) }
+//@     | `&` mutability=Mutability expression=Expression #[prec = PREFIX]
+//@       => BorrowExpression { mutability, expression: Box::new(expression) },
+//@     | `&&` mutability=Mutability expression=Expression #[prec = PREFIX]
+//@       => BorrowExpression::double(mutability, expression),
 //@
 //@ DereferenceExpression:
-//@     `*` expression=Expression #[prec = `*`]
+//@     `*` expression=Expression #[prec = PREFIX]
 //@     => DereferenceExpression { expression: Box::new(expression) }
 //@
+//@ NegationExpression -> OperatorExpression:
+//@     | `-` e=Expression #[prec = PREFIX] => OperatorExpression::Negation(NegationOperator::Neg, e),
+//@     | `!` e=Expression #[prec = PREFIX] => OperatorExpression::Negation(NegationOperator::Not, e),
+//@
 //@ ArithmeticOrLogicalExpression -> OperatorExpression:
-//@     | a=Expression `+` b=Expression => OperatorExpression::Add(a, b),
+//@     | a=Expression `+` b=Expression => OperatorExpression::Binary(BinaryOperator::Add, a, b),
+//@     | a=Expression `-` b=Expression => OperatorExpression::Binary(BinaryOperator::Sub, a, b),
+//@     | a=Expression `*` b=Expression => OperatorExpression::Binary(BinaryOperator::Mul, a, b),
+//@     | a=Expression `/` b=Expression => OperatorExpression::Binary(BinaryOperator::Div, a, b),
+//@     | a=Expression `%` b=Expression => OperatorExpression::Binary(BinaryOperator::Rem, a, b),
+//@     | a=Expression `&` b=Expression => OperatorExpression::Binary(BinaryOperator::BitAnd, a, b),
+//@     | a=Expression `|` b=Expression => OperatorExpression::Binary(BinaryOperator::BitOr, a, b),
+//@     | a=Expression `^` b=Expression => OperatorExpression::Binary(BinaryOperator::BitXor, a, b),
+//@     | a=Expression `<<` b=Expression => OperatorExpression::Binary(BinaryOperator::Shl, a, b),
+//@     | a=Expression `>>` b=Expression => OperatorExpression::Binary(BinaryOperator::Shr, a, b),
+//@
+//@ ComparisonExpression -> OperatorExpression:
+//@     | a=Expression `==` b=Expression => OperatorExpression::Comparison(ComparisonOperator::Eq, a, b),
+//@     | a=Expression `!=` b=Expression => OperatorExpression::Comparison(ComparisonOperator::Ne, a, b),
+//@     | a=Expression `<` b=Expression => OperatorExpression::Comparison(ComparisonOperator::Lt, a, b),
+//@     | a=Expression `>` b=Expression => OperatorExpression::Comparison(ComparisonOperator::Gt, a, b),
+//@     | a=Expression `<=` b=Expression => OperatorExpression::Comparison(ComparisonOperator::Le, a, b),
+//@     | a=Expression `>=` b=Expression => OperatorExpression::Comparison(ComparisonOperator::Ge, a, b),
+//@
+//@ LazyBooleanExpression -> OperatorExpression:
+//@     | a=Expression `||` b=Expression => OperatorExpression::LazyOr(a, b),
+//@     | a=Expression `&&` b=Expression => OperatorExpression::LazyAnd(a, b),
 //@
 //@ AssignmentExpression -> OperatorExpression: lhs=Expression `=` rhs=Expression => OperatorExpression::Assignment(lhs, rhs)
+//@
+//@ CompoundAssignmentExpression -> OperatorExpression:
+//@     | a=Expression `+=` b=Expression => OperatorExpression::CompoundAssignment(BinaryOperator::Add, a, b),
+//@     | a=Expression `-=` b=Expression => OperatorExpression::CompoundAssignment(BinaryOperator::Sub, a, b),
+//@     | a=Expression `*=` b=Expression => OperatorExpression::CompoundAssignment(BinaryOperator::Mul, a, b),
+//@     | a=Expression `/=` b=Expression => OperatorExpression::CompoundAssignment(BinaryOperator::Div, a, b),
+//@     | a=Expression `%=` b=Expression => OperatorExpression::CompoundAssignment(BinaryOperator::Rem, a, b),
+//@     | a=Expression `&=` b=Expression => OperatorExpression::CompoundAssignment(BinaryOperator::BitAnd, a, b),
+//@     | a=Expression `|=` b=Expression => OperatorExpression::CompoundAssignment(BinaryOperator::BitOr, a, b),
+//@     | a=Expression `^=` b=Expression => OperatorExpression::CompoundAssignment(BinaryOperator::BitXor, a, b),
+//@     | a=Expression `<<=` b=Expression => OperatorExpression::CompoundAssignment(BinaryOperator::Shl, a, b),
+//@     | a=Expression `>>=` b=Expression => OperatorExpression::CompoundAssignment(BinaryOperator::Shr, a, b),
 //@ ```
+//@
+//@ Precedence and associativity follow [ref:expr.precedence]; they are declared in the lexer
+//@ block. Prefix operators all share the made-up `PREFIX` precedence since `-`, `*` and `&` are
+//@ also binary operators with a lower precedence.
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub enum OperatorExpression {
     Borrow(BorrowExpression),
     Dereference(DereferenceExpression),
-    Add(Expression, Expression),
+    /// `-a` and `!a`.
+    Negation(NegationOperator, Expression),
+    /// The arithmetic, bitwise and shift operators.
+    Binary(BinaryOperator, Expression, Expression),
+    Comparison(ComparisonOperator, Expression, Expression),
     Assignment(Expression, Expression),
+    /// `a += b` and the like.
+    CompoundAssignment(BinaryOperator, Expression, Expression),
+    /// `a && b`. This is also how `let` conditions are chained together in `if` conditions.
+    LazyAnd(Expression, Expression),
+    /// `a || b`.
+    LazyOr(Expression, Expression),
 }
 
 //@ The `&` (shared borrow) and `&mut` (mutable borrow) operators are unary prefix operators.
 //@ [ref:expr.operator.borrow.intro].
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct BorrowExpression {
     pub mutability: Mutability,
     pub expression: Box<Expression>,
 }
 
+//@ `&&` is lexed as a single token, so `&&x` is parsed as two nested borrows
+//@ [ref:expr.operator.borrow.and-and-syntax].
+impl BorrowExpression {
+    pub fn double(mutability: Mutability, expression: Expression) -> Self {
+        let inner = BorrowExpression {
+            mutability,
+            expression: Box::new(expression),
+        };
+        BorrowExpression {
+            mutability: Mutability::Immutable,
+            expression: Box::new(Expression::new(ExpressionKind::Operator(Box::new(
+                OperatorExpression::Borrow(inner),
+            )))),
+        }
+    }
+}
+
 //@ The `*` dereference operator is applied to a pointer and denotes the pointed-to location.
 //@ [ref:expr.deref.result].
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct DereferenceExpression {
     pub expression: Box<Expression>,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub enum NegationOperator {
+    /// `-`
+    Neg,
+    /// `!`, which is logical negation on `bool` and bitwise negation on integers.
+    Not,
+}
+
+//@ The arithmetic and logical binary operators [ref:expr.arith-logic.syntax]. On `bool`, `&`, `|`
+//@ and `^` are the non-lazy logical operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub enum BinaryOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+}
+
+//@ Comparison operators [ref:expr.cmp.syntax] require parentheses to be chained, e.g.
+//@ `a == b == c` is an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub enum ComparisonOperator {
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+impl OperatorExpression {
+    /// How tightly this operator binds, higher is tighter. This is used to put parentheses back
+    /// when printing.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            OperatorExpression::Assignment(..) | OperatorExpression::CompoundAssignment(..) => 1,
+            OperatorExpression::LazyOr(..) => 2,
+            OperatorExpression::LazyAnd(..) => 3,
+            OperatorExpression::Comparison(..) => 4,
+            OperatorExpression::Binary(operator, ..) => operator.precedence(),
+            OperatorExpression::Borrow(_)
+            | OperatorExpression::Dereference(_)
+            | OperatorExpression::Negation(..) => 11,
+        }
+    }
+}
+
+impl BinaryOperator {
+    pub fn precedence(self) -> u8 {
+        match self {
+            BinaryOperator::BitOr => 5,
+            BinaryOperator::BitXor => 6,
+            BinaryOperator::BitAnd => 7,
+            BinaryOperator::Shl | BinaryOperator::Shr => 8,
+            BinaryOperator::Add | BinaryOperator::Sub => 9,
+            BinaryOperator::Mul | BinaryOperator::Div | BinaryOperator::Rem => 10,
+        }
+    }
+
+    pub fn is_shift(self) -> bool {
+        matches!(self, BinaryOperator::Shl | BinaryOperator::Shr)
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Sub => "-",
+            BinaryOperator::Mul => "*",
+            BinaryOperator::Div => "/",
+            BinaryOperator::Rem => "%",
+            BinaryOperator::BitAnd => "&",
+            BinaryOperator::BitOr => "|",
+            BinaryOperator::BitXor => "^",
+            BinaryOperator::Shl => "<<",
+            BinaryOperator::Shr => ">>",
+        }
+    }
+}
+
+impl ComparisonOperator {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            ComparisonOperator::Eq => "==",
+            ComparisonOperator::Ne => "!=",
+            ComparisonOperator::Lt => "<",
+            ComparisonOperator::Gt => ">",
+            ComparisonOperator::Le => "<=",
+            ComparisonOperator::Ge => ">=",
+        }
+    }
+}
+
+impl NegationOperator {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            NegationOperator::Neg => "-",
+            NegationOperator::Not => "!",
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::language::*; //#
+//@ # Struct Expressions
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+//@ A struct expression creates a struct value. Fields may be named or positional, e.g.
+//@ `Foo { a: true, b: () }` or `Foo { 0: true }`. The shorthand `Foo { a }` stands for
+//@ `Foo { a: a }` [ref:expr.struct.field.named]; we expand it while parsing.
+//@
+//@ Tuple-struct constructors `Foo(true)` and unit-struct values `Foo` are parsed as call and
+//@ path expressions respectively; they get turned into struct expressions in
+//@ [Misc Expression Desugarings](../../pipeline/misc-expr-desugarings.md.rs).
+//@
+//@ ```grammar
+//@ StructExpression:
+//@     path=PathExpression `{` fields=StructExprFields? `}`
+//@     => StructExpression { path, fields: fields.unwrap_or_default() }
+//@
+//@ StructExprFields -> Vec<StructExprField>:
+//@     first=StructExprField rest=(`,` StructExprField)* `,`?
+//@     => [first].into_iter().chain(rest).collect()
+//@
+//@ StructExprField:
+//@     | attrs=OuterAttribute* name=IDENTIFIER
+//@     => StructExprField {
+//@         attrs,
+//@         name: FieldName::Named(name.clone()),
+//@         value: Expression { attrs: vec![], kind: ExpressionKind::Path(name), span, origin: Origin::new() },
+//@     }
+//@     | attrs=OuterAttribute* name=FieldName `:` value=Expression
+//@     => StructExprField { attrs, name, value }
+//@
+//@ FieldName:
+//@     | name=IDENTIFIER => FieldName::Named(name)
+//@     | index=TupleIndex => FieldName::Index(index)
+//@ ```
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct StructExpression {
+    pub path: PathExpression,
+    pub fields: Vec<StructExprField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct StructExprField {
+    pub attrs: Vec<OuterAttribute>,
+    pub name: FieldName,
+    pub value: Expression,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub enum FieldName {
+    /// The field of a struct with named fields.
+    Named(Identifier),
+    /// The field of a tuple struct.
+    Index(usize),
+}
+
+//@ ## Struct expressions in conditions
+//@
+//@ A struct expression is not allowed at the top level of the condition of an `if` or
+//@ `while`, nor of the scrutinee of a `match` [ref:expr.struct.restrictions]: `if x == Foo {}
+//@ {}` would otherwise be ambiguous. Our grammar doesn't encode that restriction; instead the
+//@ parser discards the parses that violate it.
+impl Program {
+    pub fn has_struct_expression_in_condition(&self) -> bool {
+        let mut found = false;
+        self.visit_all_infallible(|expression: &Expression| {
+            let head = match &expression.kind {
+                ExpressionKind::If(if_expression) => &if_expression.condition,
+                ExpressionKind::Loop(LoopExpression {
+                    kind: LoopKind::While { condition, .. },
+                    ..
+                }) => condition,
+                ExpressionKind::Match(match_expression) => &match_expression.scrutinee,
+                _ => return,
+            };
+            found |= head.has_bare_struct_expression();
+        });
+        found
+    }
+}
+
+impl Expression {
+    /// Whether this contains a struct expression that isn't enclosed in delimiters.
+    fn has_bare_struct_expression(&self) -> bool {
+        match &self.kind {
+            ExpressionKind::Struct(_) | ExpressionKind::EnumVariant(_) => true,
+            ExpressionKind::Operator(operator) => match &**operator {
+                OperatorExpression::Borrow(BorrowExpression { expression, .. })
+                | OperatorExpression::Dereference(DereferenceExpression { expression }) => {
+                    expression.has_bare_struct_expression()
+                }
+                OperatorExpression::Negation(_, operand) => operand.has_bare_struct_expression(),
+                OperatorExpression::Binary(_, left, right)
+                | OperatorExpression::Comparison(_, left, right)
+                | OperatorExpression::Assignment(left, right)
+                | OperatorExpression::CompoundAssignment(_, left, right)
+                | OperatorExpression::LazyAnd(left, right)
+                | OperatorExpression::LazyOr(left, right) => {
+                    left.has_bare_struct_expression() || right.has_bare_struct_expression()
+                }
+            },
+            ExpressionKind::Let(let_expression) => {
+                let_expression.scrutinee.has_bare_struct_expression()
+            }
+            ExpressionKind::Field(field) => field.expression.has_bare_struct_expression(),
+            ExpressionKind::EnumProjection(projection) => {
+                projection.expression.has_bare_struct_expression()
+            }
+            ExpressionKind::Discriminant(discriminant) => {
+                discriminant.expression.has_bare_struct_expression()
+            }
+            ExpressionKind::TupleIndexing(tuple_indexing) => {
+                tuple_indexing.expression.has_bare_struct_expression()
+            }
+            ExpressionKind::Call(call) => call.callee.has_bare_struct_expression(),
+            _ => false,
+        }
+    }
+}
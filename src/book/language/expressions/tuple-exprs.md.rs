@@ -17,10 +17,10 @@ use crate::language::*; //#
 //@     => TupleIndexingExpression { expression: Box::new(expression), index }
 //@
 //@ TupleIndex -> usize:
-//@     index=INTEGER_LITERAL => usize::try_from(index).unwrap()
+//@     index=INTEGER_LITERAL => usize::try_from(index.value).unwrap()
 //@ ```
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct TupleIndexingExpression {
     pub expression: Box<Expression>,
     pub index: usize,
@@ -4,7 +4,7 @@ use crate::language::*; //#
 //@ Virtual expressions are expressions that we invented for the purpose of enabling some
 //@ desugarings. They don't exist in the surface language.
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub enum VirtualExpression {
     /// Coerce this value expression to a place expression by storing it in a temporary. See
     /// [Place-to-Value and Value-to-Place Coercions](../../pipeline/explicit-value-place.md.rs).
@@ -12,4 +12,7 @@ pub enum VirtualExpression {
     /// Coerce this place expression to a value expression by copying or moving out of it. See
     /// [Place-to-Value and Value-to-Place Coercions](../../pipeline/explicit-value-place.md.rs).
     PlaceToValueCoercion(Box<Expression>),
+    /// Stands for `unsafe { core::hint::unreachable_unchecked() }`, which we can't express yet. See
+    /// [Desugaring Matches](../../pipeline/match-desugaring.md.rs).
+    Unreachable,
 }
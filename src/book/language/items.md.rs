@@ -6,27 +6,41 @@ use crate::language::*; //#
 //@ ```grammar
 //@ Item:
 //@     attrs=OuterAttribute* visibility=Visibility? kind=ItemKind
-//@     => Item { attrs, visibility, kind }
+//@     => Item { attrs, visibility, kind, span, origin: Origin::new() }
 //@
 //@ ItemKind:
-//@     function=Function => ItemKind::Function(function)
+//@     | function=Function => ItemKind::Function(function)
+//@     | adt=Struct => ItemKind::Struct(adt)
+//@     | adt=Enum => ItemKind::Enum(adt)
 //@ ```
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct Item {
     pub attrs: Vec<OuterAttribute>,
     pub visibility: Option<Visibility>,
     pub kind: ItemKind,
+    #[serde(default)] //#
+    pub span: Span,
+    #[serde(default = "Origin::new")] //#
+    pub origin: Origin,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub enum ItemKind {
     Function(Function),
+    Struct(Struct),
+    Enum(Enum),
 }
 
 //@ ## Submodules
+#[path = "items/enums.md.rs"]
+pub mod enums;
 #[path = "items/functions.md.rs"]
 pub mod functions;
+#[path = "items/structs.md.rs"]
+pub mod structs;
 
+pub use enums::*;
 pub use functions::*;
+pub use structs::*;
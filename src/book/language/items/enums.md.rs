@@ -0,0 +1,64 @@
+//@ # Enumerations
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+use crate::language::*; //#
+//@ An enumeration is a nominal sum type: a value of an enum type is a value of exactly one of its
+//@ variants [ref:items.enum.intro]. Each variant has fields, which are declared like the fields of
+//@ a [struct](structs.md.rs).
+//@
+//@ ```grammar
+//@ Enum:
+//@     `enum` name=IDENTIFIER generic_params=GenericParams? where_clauses=WhereClauses?
+//@         `{` variants=EnumVariants? `}`
+//@     => Enum {
+//@         name,
+//@         generic_params: generic_params.unwrap_or_default(),
+//@         where_clauses: where_clauses.unwrap_or_default(),
+//@         variants: variants.unwrap_or_default(),
+//@     }
+//@
+//@ EnumVariants -> Vec<EnumVariant>:
+//@     first=EnumVariant rest=(`,` EnumVariant)* `,`?
+//@     => [first].into_iter().chain(rest).collect()
+//@
+//@ EnumVariant:
+//@     | attrs=OuterAttribute* name=IDENTIFIER `{` fields=StructFields? `}`
+//@     => EnumVariant { attrs, name, fields: StructFields::Named(fields.unwrap_or_default()) }
+//@     | attrs=OuterAttribute* name=IDENTIFIER `(` fields=TupleFields? `)`
+//@     => EnumVariant { attrs, name, fields: StructFields::Tuple(fields.unwrap_or_default()) }
+//@     | attrs=OuterAttribute* name=IDENTIFIER
+//@     => EnumVariant { attrs, name, fields: StructFields::Unit }
+//@ ```
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct Enum {
+    /// The name of the enum.
+    pub name: Identifier,
+    /// Generic parameters, for polymorphic enums.
+    pub generic_params: GenericParams,
+    /// Additional predicates that must hold for the enum type to be well-formed.
+    pub where_clauses: WhereClauses,
+    /// The variants of the enum.
+    pub variants: Vec<EnumVariant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct EnumVariant {
+    pub attrs: Vec<OuterAttribute>,
+    pub name: Identifier,
+    pub fields: StructFields,
+}
+
+//@ We don't support explicit discriminants yet: the discriminant of each variant is its index in
+//@ the declaration, starting at zero [ref:items.enum.discriminant.implicit].
+impl Enum {
+    /// Find a variant by name, along with its discriminant.
+    pub fn variant(&self, name: &Identifier) -> Option<(usize, &EnumVariant)> {
+        self.variants
+            .iter()
+            .enumerate()
+            .find(|(_, variant)| variant.name == *name)
+    }
+}
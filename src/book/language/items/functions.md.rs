@@ -28,7 +28,7 @@ use crate::language::*; //#
 //@ ```
 //@
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct Function {
     pub qualifiers: FunctionQualifiers,
     /// The name of the function.
@@ -46,7 +46,7 @@ pub struct Function {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub enum FunctionBody {
     /// The body of a function is a block.
     Block(BlockExpression),
@@ -72,7 +72,7 @@ pub enum FunctionBody {
 //@     => ExternAbi { abi }
 //@ ```
 #[derive(Debug, Default, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct FunctionQualifiers {
     pub is_const: bool,
     pub is_async: bool,
@@ -81,14 +81,14 @@ pub struct FunctionQualifiers {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub enum ItemSafety {
     Safe,
     Unsafe,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct ExternAbi {
     pub abi: Option<String>,
 }
@@ -112,14 +112,14 @@ pub struct ExternAbi {
 //@ ```
 
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct FunctionParam {
     pub attrs: Vec<OuterAttribute>,
     pub kind: FunctionParamKind,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub enum FunctionParamKind {
     Regular {
         pattern: Option<Pattern>,
@@ -136,7 +136,7 @@ pub enum FunctionParamKind {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub enum FunctionParamType {
     Type(Type),
     Variadic,
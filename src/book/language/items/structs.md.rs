@@ -0,0 +1,115 @@
+//@ # Structs
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+use crate::language::*; //#
+//@ A struct is a nominal product type. Its fields are either named (`struct Foo { a: bool }`),
+//@ positional (`struct Foo(bool);`) or absent (`struct Foo;`).
+//@
+//@ ```grammar
+//@ Struct:
+//@     | `struct` name=IDENTIFIER generic_params=GenericParams? where_clauses=WhereClauses?
+//@         `{` fields=StructFields? `}`
+//@     => Struct {
+//@         name,
+//@         generic_params: generic_params.unwrap_or_default(),
+//@         where_clauses: where_clauses.unwrap_or_default(),
+//@         fields: StructFields::Named(fields.unwrap_or_default()),
+//@     }
+//@     | `struct` name=IDENTIFIER generic_params=GenericParams?
+//@         `(` fields=TupleFields? `)` where_clauses=WhereClauses? `;`
+//@     => Struct {
+//@         name,
+//@         generic_params: generic_params.unwrap_or_default(),
+//@         where_clauses: where_clauses.unwrap_or_default(),
+//@         fields: StructFields::Tuple(fields.unwrap_or_default()),
+//@     }
+//@     | `struct` name=IDENTIFIER generic_params=GenericParams? where_clauses=WhereClauses? `;`
+//@     => Struct {
+//@         name,
+//@         generic_params: generic_params.unwrap_or_default(),
+//@         where_clauses: where_clauses.unwrap_or_default(),
+//@         fields: StructFields::Unit,
+//@     }
+//@
+//@ StructFields -> Vec<StructField>:
+//@     first=StructField rest=(`,` StructField)* `,`?
+//@     => [first].into_iter().chain(rest).collect()
+//@
+//@ StructField:
+//@     attrs=OuterAttribute* visibility=Visibility? name=IDENTIFIER `:` ty=Type
+//@     => StructField { attrs, visibility, name, ty }
+//@
+//@ TupleFields -> Vec<TupleField>:
+//@     first=TupleField rest=(`,` TupleField)* `,`?
+//@     => [first].into_iter().chain(rest).collect()
+//@
+//@ TupleField:
+//@     attrs=OuterAttribute* visibility=Visibility? ty=Type
+//@     => TupleField { attrs, visibility, ty }
+//@ ```
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct Struct {
+    /// The name of the struct.
+    pub name: Identifier,
+    /// Generic parameters, for polymorphic structs.
+    pub generic_params: GenericParams,
+    /// Additional predicates that must hold for the struct type to be well-formed.
+    pub where_clauses: WhereClauses,
+    /// The fields of the struct.
+    pub fields: StructFields,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub enum StructFields {
+    /// `struct Foo { a: bool, b: () }`
+    Named(Vec<StructField>),
+    /// `struct Foo(bool, ());`
+    Tuple(Vec<TupleField>),
+    /// `struct Foo;`
+    Unit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct StructField {
+    pub attrs: Vec<OuterAttribute>,
+    pub visibility: Option<Visibility>,
+    pub name: Identifier,
+    pub ty: Type,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct TupleField {
+    pub attrs: Vec<OuterAttribute>,
+    pub visibility: Option<Visibility>,
+    pub ty: Type,
+}
+
+impl Struct {
+    /// The fields of the struct in declaration order, along with the name used to access them.
+    pub fn field_list(&self) -> Vec<(FieldName, &Type)> {
+        self.fields.field_list()
+    }
+}
+
+impl StructFields {
+    /// The fields in declaration order, along with the name used to access them.
+    pub fn field_list(&self) -> Vec<(FieldName, &Type)> {
+        match self {
+            StructFields::Named(fields) => fields
+                .iter()
+                .map(|field| (FieldName::Named(field.name.clone()), &field.ty))
+                .collect(),
+            StructFields::Tuple(fields) => fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| (FieldName::Index(index), &field.ty))
+                .collect(),
+            StructFields::Unit => vec![],
+        }
+    }
+}
@@ -2,6 +2,7 @@
 //@
 //@ > This section is a work-in-progress experiment about making the book executable.
 //@
+use crate::language::{Deserialize, Identifier, IntType, IntegerLiteral, Serialize}; //#
 use logos::Logos; //#
 //@
 #[derive(Clone, Debug, PartialEq, Eq, Logos)]
@@ -36,6 +37,24 @@ pub enum Token {
     If,
     #[token("else")]
     Else,
+    #[token("loop")]
+    Loop,
+    #[token("while")]
+    While,
+    #[token("break")]
+    Break,
+    #[token("continue")]
+    Continue,
+    #[token("match")]
+    Match,
+    #[token("struct")]
+    Struct,
+    #[token("enum")]
+    Enum,
+    #[token("enum#discriminant")]
+    EnumDiscriminant,
+    #[token("discriminant_of!")]
+    DiscriminantOf,
     #[token("mut")]
     Mut,
     #[token("self")]
@@ -46,22 +65,94 @@ pub enum Token {
     Bool,
     #[token("str")]
     Str,
+    #[token("u8")]
+    U8,
+    #[token("u16")]
+    U16,
+    #[token("u32")]
+    U32,
+    #[token("u64")]
+    U64,
+    #[token("u128")]
+    U128,
+    #[token("usize")]
+    Usize,
+    #[token("i8")]
+    I8,
+    #[token("i16")]
+    I16,
+    #[token("i32")]
+    I32,
+    #[token("i64")]
+    I64,
+    #[token("i128")]
+    I128,
+    #[token("isize")]
+    Isize,
     #[token("true")]
     True,
     #[token("false")]
     False,
     #[token("->")]
     Arrow,
+    #[token("=>")]
+    FatArrow,
     #[token("=")]
     Eq,
+    #[token("+=")]
+    PlusEq,
+    #[token("-=")]
+    MinusEq,
+    #[token("*=")]
+    StarEq,
+    #[token("/=")]
+    SlashEq,
+    #[token("%=")]
+    PercentEq,
+    #[token("&=")]
+    AndEq,
+    #[token("|=")]
+    OrEq,
+    #[token("^=")]
+    CaretEq,
+    #[token("<<=")]
+    ShlEq,
+    #[token(">>=")]
+    ShrEq,
+    #[token("==")]
+    EqEq,
+    #[token("!=")]
+    Ne,
+    #[token("<=")]
+    Le,
+    #[token(">=")]
+    Ge,
     #[token("+")]
     Plus,
     #[token("-")]
     Minus,
     #[token("*")]
     Star,
+    #[token("/")]
+    Slash,
+    #[token("%")]
+    Percent,
+    #[token("^")]
+    Caret,
+    #[token("!")]
+    Not,
     #[token("&")]
     Amp,
+    #[token("&&")]
+    AndAnd,
+    #[token("|")]
+    Or,
+    #[token("||")]
+    OrOr,
+    #[token("<<")]
+    Shl,
+    #[token(">>")]
+    Shr,
     #[token("::")]
     PathSep,
     #[token("<")]
@@ -90,16 +181,58 @@ pub enum Token {
     Underscore,
     #[token("$crate")]
     MacroCrate,
-    #[regex(r"[0-9]+", |lex| lex.slice().parse::<u128>().unwrap())]
-    IntegerLiteral(u128),
-    #[regex(r"[A-Za-z_][A-Za-z0-9_]*", |lex| lex.slice().to_owned(), priority = 1)]
-    Identifier(String),
+    #[regex(
+        r"(0x[0-9A-Fa-f_]+|0o[0-7_]+|0b[01_]+|[0-9][0-9_]*)([iu](8|16|32|64|128|size))?",
+        integer_literal
+    )]
+    IntegerLiteral(IntegerLiteral),
+    /// An identifier, possibly followed by a hygiene marker such as `#1`. Only the programs we
+    /// printed may have markers, see [`Identifier`].
+    #[regex(r"[A-Za-z_][A-Za-z0-9_]*(#[0-9]+)?", identifier, priority = 1)]
+    Identifier(Identifier),
     #[regex(r#""[^"]*""#, string_literal)]
     StringLiteral(String),
     #[regex(r"'[A-Za-z_][A-Za-z0-9_]*", |lex| lex.slice().to_owned())]
     Lifetime(String),
     #[token("__unsupported__")]
     Unsupported,
+    /// Never produced by the lexer; only used as the precedence of prefix operators.
+    Prefix,
+    /// Never produced by the lexer; only used as the precedence of `let` scrutinees.
+    LetScrutinee,
+}
+
+/// Parse an integer literal, rejecting values that don't fit in a `u128` as well as prefixed
+/// literals without any digits, such as `0x_`.
+fn integer_literal(lex: &mut logos::Lexer<'_, Token>) -> Option<IntegerLiteral> {
+    let slice = lex.slice();
+    let (digits, suffix) = match slice.find(['i', 'u']) {
+        Some(index) => (&slice[..index], Some(IntType::from_name(&slice[index..])?)),
+        None => (slice, None),
+    };
+    let digits = digits.replace('_', "");
+    let value = if let Some(digits) = digits.strip_prefix("0x") {
+        u128::from_str_radix(digits, 16)
+    } else if let Some(digits) = digits.strip_prefix("0o") {
+        u128::from_str_radix(digits, 8)
+    } else if let Some(digits) = digits.strip_prefix("0b") {
+        u128::from_str_radix(digits, 2)
+    } else {
+        digits.parse()
+    };
+    Some(IntegerLiteral {
+        value: value.ok()?,
+        suffix,
+    })
+}
+
+fn identifier(lex: &mut logos::Lexer<'_, Token>) -> Option<Identifier> {
+    Some(match lex.slice().split_once('#') {
+        Some((name, marker)) => {
+            Identifier::with_hygiene(name, marker.parse().ok().filter(|&marker| marker != 0)?)
+        }
+        None => Identifier::new(lex.slice()),
+    })
 }
 
 fn string_literal(lex: &mut logos::Lexer<'_, Token>) -> String {
@@ -107,9 +240,46 @@ fn string_literal(lex: &mut logos::Lexer<'_, Token>) -> String {
     slice[1..slice.len() - 1].to_owned()
 }
 
+//@ ## Spans
+//@
+//@ The parser records, for each expression, statement, item and type path, the range of bytes of
+//@ the source it was parsed from. The actions of the grammar refer to that range as `span`. Spans
+//@ are only used to point at the source in error messages: nodes made up by desugarings get the
+//@ span of the node they were made from (see [Provenance](provenance.md.rs)) or a dummy span, and
+//@ spans are ignored when comparing nodes.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)] //#
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// The span of nodes that don't come from the source.
+    pub fn dummy() -> Self {
+        Span::default()
+    }
+
+    pub fn is_dummy(self) -> bool {
+        self.end == 0
+    }
+}
+
+impl PartialEq for Span {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Span {}
+
 //@ ```lexer
 //@ %tokentype Token;
 //@ %start Program;
+//@ %location Span;
 //@
 //@ `fn` Fn;
 //@ `const` Const;
@@ -119,6 +289,15 @@ fn string_literal(lex: &mut logos::Lexer<'_, Token>) -> String {
 //@ `extern` Extern;
 //@ `if` If;
 //@ `else` Else;
+//@ `loop` Loop;
+//@ `while` While;
+//@ `break` Break;
+//@ `continue` Continue;
+//@ `match` Match;
+//@ `struct` Struct;
+//@ `enum` Enum;
+//@ `enum#discriminant` EnumDiscriminant;
+//@ `discriminant_of!` DiscriminantOf;
 //@ `let` Let;
 //@ `pub` Pub;
 //@ `crate` Crate;
@@ -130,14 +309,50 @@ fn string_literal(lex: &mut logos::Lexer<'_, Token>) -> String {
 //@ `Self` TraitSelf;
 //@ `bool` Bool;
 //@ `str` Str;
+//@ `u8` U8;
+//@ `u16` U16;
+//@ `u32` U32;
+//@ `u64` U64;
+//@ `u128` U128;
+//@ `usize` Usize;
+//@ `i8` I8;
+//@ `i16` I16;
+//@ `i32` I32;
+//@ `i64` I64;
+//@ `i128` I128;
+//@ `isize` Isize;
 //@ `true` True;
 //@ `false` False;
 //@ `->` Arrow;
+//@ `=>` FatArrow;
 //@ `=` Eq;
+//@ `+=` PlusEq;
+//@ `-=` MinusEq;
+//@ `*=` StarEq;
+//@ `/=` SlashEq;
+//@ `%=` PercentEq;
+//@ `&=` AndEq;
+//@ `|=` OrEq;
+//@ `^=` CaretEq;
+//@ `<<=` ShlEq;
+//@ `>>=` ShrEq;
+//@ `==` EqEq;
+//@ `!=` Ne;
+//@ `<=` Le;
+//@ `>=` Ge;
 //@ `+` Plus;
 //@ `-` Minus;
 //@ `*` Star;
+//@ `/` Slash;
+//@ `%` Percent;
+//@ `^` Caret;
+//@ `!` Not;
 //@ `&` Amp;
+//@ `&&` AndAnd;
+//@ `|` Or;
+//@ `||` OrOr;
+//@ `<<` Shl;
+//@ `>>` Shr;
 //@ `::` PathSep;
 //@ `<` Lt;
 //@ `>` Gt;
@@ -152,20 +367,31 @@ fn string_literal(lex: &mut logos::Lexer<'_, Token>) -> String {
 //@ `...` Ellipsis;
 //@ `_` Underscore;
 //@ `$crate` MacroCrate;
-//@ INTEGER_LITERAL IntegerLiteral(u128);
-//@ IDENTIFIER Identifier(String);
+//@ INTEGER_LITERAL IntegerLiteral(IntegerLiteral);
+//@ IDENTIFIER Identifier(Identifier);
 //@ STRING_LITERAL StringLiteral(String);
 //@ LIFETIME Lifetime(String);
 //@ UNSUPPORTED Unsupported;
+//@ PREFIX Prefix;
+//@ LET_SCRUTINEE LetScrutinee;
 //@
+//@ %precedence `break`;
 //@ %precedence `if`;
 //@ %precedence `else`;
 //@ %precedence `self`;
 //@ %precedence `:`;
-//@ %precedence `=`;
-//@ %precedence `+`;
-//@ %precedence `&`;
-//@ %precedence `*`;
+//@ %right `=` `+=` `-=` `*=` `/=` `%=` `&=` `|=` `^=` `<<=` `>>=`;
+//@ %left `||`;
+//@ %left `&&`;
+//@ %precedence LET_SCRUTINEE;
+//@ %precedence `==` `!=` `<` `>` `<=` `>=`;
+//@ %left `|`;
+//@ %left `^`;
+//@ %left `&`;
+//@ %left `<<` `>>`;
+//@ %left `+` `-`;
+//@ %left `*` `/` `%`;
+//@ %precedence PREFIX;
 //@ %precedence `.`;
 //@
 //@ %allow unit_production_eliminated(Identifier);
@@ -4,7 +4,99 @@
 //@
 //@ Names are the syntactic forms used to refer to language entities. Entities include types,
 //@ items, generic parameters, variable bindings, and more.
+use crate::language::*; //#
 //@
+//@ ## Hygiene
+//@
+//@ Desugarings introduce bindings of their own, e.g. to hold temporaries. To make sure these don't
+//@ clash with the names of the program, we give them a hygiene marker: a number that no other name
+//@ of the program has. Names of the source program have no marker. This is a simple form of
+//@ [Explicit Hygiene Markers](../features/hygiene-markers.md).
+//@
+//@ The printer writes the marker after a `#`, as in `tmp#1`. Rust doesn't allow `#` in names, so
+//@ this can't be mistaken for a user-written name. Source programs can't use that syntax either: only
+//@ the tests, which parse the programs we print to check that they read back the same, accept it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct Identifier {
+    pub name: String,
+    /// The hygiene marker of a name made up by a desugaring, or 0 for a name of the source program.
+    #[serde(default)] //#
+    pub hygiene: u32,
+}
+
+impl Identifier {
+    /// A name of the source program.
+    pub fn new(name: impl Into<String>) -> Self {
+        Identifier {
+            name: name.into(),
+            hygiene: 0,
+        }
+    }
+
+    /// The name `name` with the hygiene marker `hygiene`, as written `name#hygiene`.
+    pub fn with_hygiene(name: impl Into<String>, hygiene: u32) -> Self {
+        Identifier {
+            name: name.into(),
+            hygiene,
+        }
+    }
+
+    pub fn is_hygienic(&self) -> bool {
+        self.hygiene != 0
+    }
+}
+
+/// Only a name without marker can be equal to a name written in the source.
+impl PartialEq<str> for Identifier {
+    fn eq(&self, other: &str) -> bool {
+        !self.is_hygienic() && self.name == other
+    }
+}
+
+impl PartialEq<&str> for Identifier {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl std::fmt::Display for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_hygienic() {
+            write!(f, "{}#{}", self.name, self.hygiene)
+        } else {
+            write!(f, "{}", self.name)
+        }
+    }
+}
+
+/// Makes up names that are distinct from each other and from the names of a program.
+pub struct NameSupply {
+    next_marker: u32,
+}
+
+impl NameSupply {
+    /// A supply of names for `program`. Since made-up names are only told apart by their marker, we
+    /// start after the markers already in the program, so that running a pass again on its output
+    /// still gets fresh names.
+    pub fn new(program: &Program) -> Self {
+        let mut last_marker = 0;
+        program.visit_all_infallible(|identifier: &Identifier| {
+            last_marker = last_marker.max(identifier.hygiene);
+        });
+        NameSupply {
+            next_marker: last_marker + 1,
+        }
+    }
+
+    /// A new name that reads as `name`.
+    pub fn fresh(&mut self, name: &str) -> Identifier {
+        let marker = self.next_marker;
+        self.next_marker += 1;
+        Identifier::with_hygiene(name, marker)
+    }
+}
+
 //@ ## Submodules
 #[path = "names/paths.md.rs"]
 pub mod paths;
@@ -15,7 +15,7 @@
 //@ ```
 //@
 #[derive(Debug, Default, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct Program {
     pub items: Vec<Item>,
 }
@@ -25,11 +25,11 @@ pub struct Program {
 //@ Some syntactic elements we haven't fleshed out yet.
 //@
 //@ ```grammar
-//@ Identifier -> String: IDENTIFIER
+//@ Identifier: IDENTIFIER
 //@     => IDENTIFIER
 //@ ```
 //@
-pub type Identifier = String;
+//@ See [Names](names.md.rs) for the definition of `Identifier`.
 
 //@ ```grammar
 //@ Abi -> String: STRING_LITERAL
@@ -45,7 +45,7 @@ pub type Abi = String;
 //@ ```
 //@
 #[derive(Debug, Clone, Copy, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub enum Mutability {
     Mutable,
     Immutable,
@@ -57,7 +57,7 @@ pub enum Mutability {
 //@ ```
 //@
 #[derive(Debug, Default, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct GenericParams {}
 
 //@ ```grammar
@@ -66,7 +66,7 @@ pub struct GenericParams {}
 //@ ```
 //@
 #[derive(Debug, Default, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct WhereClauses {}
 
 //@ ```grammar
@@ -75,7 +75,7 @@ pub struct WhereClauses {}
 //@ ```
 //@
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct OuterAttribute {}
 
 //@ ```grammar
@@ -84,7 +84,7 @@ pub struct OuterAttribute {}
 //@ ```
 //@
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct InnerAttribute {}
 
 //@ ```grammar
@@ -93,7 +93,7 @@ pub struct InnerAttribute {}
 //@ ```
 //@
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub struct Lifetime {}
 
 //@ ```grammar
@@ -105,7 +105,7 @@ pub struct Lifetime {}
 //@     | `pub` `(` `in` path=SimplePath `)` => Visibility::InPath(path),
 //@ ```
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub enum Visibility {
     Pub,
     PubCrate,
@@ -114,21 +114,9 @@ pub enum Visibility {
     InPath(Path),
 }
 
-//@ ```grammar
-//@ PatternNoTopAlt -> Pattern:
-//@     | name=IDENTIFIER => Pattern::Identifier(name)
-//@     | `_` => Pattern::Wildcard
-//@ ```
-//@
-#[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
-pub enum Pattern {
-    Identifier(Identifier),
-    Wildcard,
-}
-
 //@ ## Submodules
 pub use derive_generic_visitor::{Drive, DriveMut}; //#
+pub use serde::{Deserialize, Serialize}; //#
 #[path = "expressions.md.rs"]
 pub mod expressions;
 #[path = "items.md.rs"]
@@ -137,8 +125,12 @@ pub mod items;
 pub mod lexing;
 #[path = "names.md.rs"]
 pub mod names;
+#[path = "patterns.md.rs"]
+pub mod patterns;
 #[path = "print.md.rs"]
 pub mod print;
+#[path = "provenance.md.rs"]
+pub mod provenance;
 #[path = "statements.md.rs"]
 pub mod statements;
 #[path = "types.md.rs"]
@@ -150,7 +142,9 @@ pub use expressions::*;
 pub use items::*;
 pub use lexing::*;
 pub use names::*;
+pub use patterns::*;
 pub use print::*;
+pub use provenance::*;
 pub use statements::*;
 pub use types::*;
 pub use visitor::*;
@@ -0,0 +1,121 @@
+use crate::language::*; //#
+//@ # Patterns
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+//@ Patterns are used to match values against structures and to, optionally, bind variables to
+//@ values inside these structures [ref:patterns.intro].
+//@
+//@ ```grammar
+//@ PatternNoTopAlt -> Pattern:
+//@     | name=IDENTIFIER => Pattern::new(PatternKind::Identifier(name))
+//@     | `_` => Pattern::new(PatternKind::Wildcard)
+//@     | literal=LiteralExpression => Pattern::new(PatternKind::Literal(literal))
+//@     | `(` patterns=TuplePatternItems? `)`
+//@       => Pattern::new(PatternKind::Tuple(patterns.unwrap_or_default()))
+//@     | variant=VariantPattern => Pattern::new(PatternKind::Variant(variant))
+//@
+//@ TuplePatternItems -> Vec<Pattern>:
+//@     patterns=(PatternNoTopAlt `,`)+ last=PatternNoTopAlt?
+//@     => patterns.into_iter().chain(last).collect()
+//@ ```
+//@
+//@ A variant of an [enum](items/enums.md.rs) is matched with a path to it, followed by patterns for
+//@ its fields, written like a tuple or like a struct depending on the variant. Like in [enum
+//@ expressions](expressions/enum-exprs.md.rs), we represent all three forms with named fields,
+//@ where the fields of a tuple variant are named `0`, `1`, etc. We don't support `..` yet, so
+//@ every field must be mentioned.
+//@
+//@ ```grammar
+//@ VariantPattern:
+//@     | path=VariantPath => VariantPattern { path, fields: vec![] }
+//@     | path=VariantPath `(` patterns=TupleStructItems? `)`
+//@       => VariantPattern::tuple(path, patterns.unwrap_or_default())
+//@     | path=VariantPath `{` fields=StructPatternFields? `}`
+//@       => VariantPattern { path, fields: fields.unwrap_or_default() }
+//@
+//@ TupleStructItems -> Vec<Pattern>:
+//@     first=PatternNoTopAlt rest=(`,` PatternNoTopAlt)* `,`?
+//@     => [first].into_iter().chain(rest).collect()
+//@
+//@ StructPatternFields -> Vec<StructPatternField>:
+//@     first=StructPatternField rest=(`,` StructPatternField)* `,`?
+//@     => [first].into_iter().chain(rest).collect()
+//@
+//@ StructPatternField:
+//@     | name=IDENTIFIER
+//@     => StructPatternField {
+//@         name: FieldName::Named(name.clone()),
+//@         pattern: Pattern::new(PatternKind::Identifier(name)),
+//@     }
+//@     | name=FieldName `:` pattern=PatternNoTopAlt => StructPatternField { name, pattern }
+//@ ```
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct Pattern {
+    pub kind: PatternKind,
+    #[serde(default = "Origin::new")] //#
+    pub origin: Origin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub enum PatternKind {
+    /// Binds the matched value to a variable [ref:patterns.ident].
+    Identifier(Identifier),
+    /// Matches anything and binds nothing [ref:patterns.wildcard].
+    Wildcard,
+    /// Matches values equal to the literal [ref:patterns.literal].
+    Literal(LiteralExpression),
+    /// Matches each element of a tuple against the corresponding subpattern [ref:patterns.tuple].
+    Tuple(Vec<Pattern>),
+    /// Matches values of an enum variant whose fields match the corresponding subpatterns
+    /// [ref:patterns.struct] [ref:patterns.tuple-struct].
+    Variant(VariantPattern),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct VariantPattern {
+    pub path: VariantPath,
+    pub fields: Vec<StructPatternField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct StructPatternField {
+    pub name: FieldName,
+    pub pattern: Pattern,
+}
+
+impl Pattern {
+    pub fn new(kind: PatternKind) -> Self {
+        Pattern {
+            kind,
+            origin: Origin::new(),
+        }
+    }
+
+    /// A new pattern made from the node with origin `source`.
+    pub fn derived_from(source: &Origin, kind: PatternKind) -> Self {
+        Pattern {
+            kind,
+            origin: source.derive(),
+        }
+    }
+}
+
+impl VariantPattern {
+    /// The pattern `$path($patterns..)` of a tuple variant.
+    pub fn tuple(path: VariantPath, patterns: Vec<Pattern>) -> Self {
+        let fields = patterns
+            .into_iter()
+            .enumerate()
+            .map(|(index, pattern)| StructPatternField {
+                name: FieldName::Index(index),
+                pattern,
+            })
+            .collect();
+        VariantPattern { path, fields }
+    }
+}
@@ -7,6 +7,7 @@
 //@ AI disclaimer: this section is LLM-generated.
 use crate::language::*; //#
 use itertools::Itertools; //#
+use std::collections::{BTreeSet, HashMap, HashSet}; //#
 use std::fmt::{self, Display, Formatter}; //#
 //@
 pub fn print_program(program: &Program) -> String {
@@ -28,6 +29,11 @@ struct Printer {
     output: String,
     indent: usize,
     at_line_start: bool,
+    /// Set to print real Rust instead of the syntax of the book, see [`print_program_as_rust`].
+    rust: Option<RustEncoding>,
+    /// Whether the expression about to be printed is a place that gets mutated or mutably
+    /// borrowed. Only matters for real Rust.
+    mutable_place: bool,
 }
 
 impl Printer {
@@ -36,6 +42,8 @@ impl Printer {
             output: String::new(),
             indent: 0,
             at_line_start: true,
+            rust: None,
+            mutable_place: false,
         }
     }
 
@@ -95,6 +103,16 @@ impl Printer {
     }
 
     fn item(&mut self, item: &Item) {
+        let rust_enum = match &item.kind {
+            ItemKind::Enum(adt) if self.rust.is_some() => Some(adt),
+            _ => None,
+        };
+        if let Some(adt) = rust_enum
+            && !adt.variants.is_empty()
+        {
+            self.token("#[repr(isize)]");
+            self.newline();
+        }
         self.attrs(&item.attrs);
         if let Some(visibility) = &item.visibility {
             self.display(visibility);
@@ -102,9 +120,25 @@ impl Printer {
         }
         match &item.kind {
             ItemKind::Function(function) => self.function(function),
+            ItemKind::Struct(adt) => self.struct_item(adt),
+            ItemKind::Enum(adt) => self.enum_item(adt),
+        }
+        if let Some(adt) = rust_enum {
+            for variant in &adt.variants {
+                self.variant_struct(&adt.name, variant);
+            }
         }
     }
 
+    /// The name to print for a local variable.
+    fn local(&mut self, name: &Identifier) {
+        let name = match &self.rust {
+            Some(rust) => rust.local(name).clone(),
+            None => name.clone(),
+        };
+        self.display(name);
+    }
+
     fn attrs(&mut self, attrs: &[OuterAttribute]) {
         for attr in attrs {
             self.display(attr);
@@ -115,11 +149,11 @@ impl Printer {
     fn function(&mut self, function: &Function) {
         self.function_qualifiers(&function.qualifiers);
         self.token("fn ");
-        self.token(&function.name);
+        self.display(&function.name);
         self.display(&function.generic_params);
         self.token("(");
         self.comma_separated(&function.parameters, |printer, parameter| {
-            printer.display(parameter);
+            printer.parameter(parameter);
         });
         self.token(")");
         if let Some(return_type) = &function.return_type {
@@ -136,6 +170,78 @@ impl Printer {
         }
     }
 
+    fn struct_item(&mut self, adt: &Struct) {
+        self.token("struct ");
+        self.display(&adt.name);
+        self.display(&adt.generic_params);
+        match &adt.fields {
+            StructFields::Named(fields) => {
+                self.display(&adt.where_clauses);
+                if fields.is_empty() {
+                    self.token(" {}");
+                    return;
+                }
+                self.token(" {");
+                self.indented(|printer| {
+                    for field in fields {
+                        printer.newline();
+                        printer.display(field);
+                        printer.token(",");
+                    }
+                });
+                self.newline();
+                self.token("}");
+            }
+            StructFields::Tuple(fields) => {
+                self.token("(");
+                self.comma_separated(fields, |printer, field| printer.display(field));
+                self.token(")");
+                self.display(&adt.where_clauses);
+                self.token(";");
+            }
+            StructFields::Unit => {
+                self.display(&adt.where_clauses);
+                self.token(";");
+            }
+        }
+    }
+
+    fn parameter(&mut self, parameter: &FunctionParam) {
+        match &parameter.kind {
+            FunctionParamKind::Regular {
+                pattern: Some(pattern),
+                ty,
+            } if self.rust.is_some() => {
+                self.attrs(&parameter.attrs);
+                self.pattern(pattern);
+                self.token(": ");
+                self.display(ty);
+            }
+            _ => self.display(parameter),
+        }
+    }
+
+    fn enum_item(&mut self, adt: &Enum) {
+        self.token("enum ");
+        self.display(&adt.name);
+        self.display(&adt.generic_params);
+        self.display(&adt.where_clauses);
+        if adt.variants.is_empty() {
+            self.token(" {}");
+            return;
+        }
+        self.token(" {");
+        self.indented(|printer| {
+            for variant in &adt.variants {
+                printer.newline();
+                printer.display(variant);
+                printer.token(",");
+            }
+        });
+        self.newline();
+        self.token("}");
+    }
+
     fn function_qualifiers(&mut self, qualifiers: &FunctionQualifiers) {
         let mut parts = Vec::new();
         if qualifiers.is_const {
@@ -168,7 +274,13 @@ impl Printer {
 
         self.token("{");
         self.indented(|printer| {
+            if printer.rust.is_some() {
+                printer.hoisted_scoped_lets(block);
+            }
             for statement in &block.statements {
+                if printer.rust.is_some() && is_hoisted_declaration(statement) {
+                    continue;
+                }
                 printer.newline();
                 printer.statement(statement);
             }
@@ -185,6 +297,24 @@ impl Printer {
         match statement {
             Statement::Empty => self.token(";"),
             Statement::Item(item) => self.item(item),
+            Statement::Let {
+                attrs,
+                scope: Some(_),
+                pattern,
+                initial_value,
+                else_branch: None,
+                ..
+            } if self.rust.is_some() => {
+                // The bindings are declared at the start of the labeled block, see
+                // `hoisted_scoped_lets`.
+                if let Some(initial_value) = initial_value {
+                    self.attrs(attrs);
+                    self.assignee(pattern);
+                    self.token(" = ");
+                    self.expression(initial_value);
+                    self.token(";");
+                }
+            }
             Statement::Let {
                 attrs,
                 scope,
@@ -192,16 +322,20 @@ impl Printer {
                 ty,
                 initial_value,
                 else_branch,
+                span: _,
+                origin: _,
             } => {
                 self.attrs(attrs);
                 self.token("let");
-                if let Some(scope) = scope {
+                if let Some(scope) = scope
+                    && self.rust.is_none()
+                {
                     self.token("(in ");
                     self.display(scope);
                     self.token(")");
                 }
                 self.space();
-                self.display(pattern);
+                self.pattern(pattern);
                 if let Some(ty) = ty {
                     self.token(": ");
                     self.display(ty);
@@ -226,27 +360,75 @@ impl Printer {
     }
 
     fn expression(&mut self, expression: &Expression) {
+        let mutable_place = std::mem::take(&mut self.mutable_place);
         self.attrs(&expression.attrs);
         match &expression.kind {
             ExpressionKind::Literal(literal) => self.display(literal),
-            ExpressionKind::Path(path) => self.token(path),
+            ExpressionKind::Path(path) => self.local(path),
             ExpressionKind::Operator(operator) => self.operator_expression(operator),
             ExpressionKind::Virtual(virtual_expression) => {
                 self.virtual_expression(virtual_expression)
             }
             ExpressionKind::Grouped(grouped) => {
                 self.token("(");
+                self.mutable_place = mutable_place;
                 self.expression(grouped);
                 self.token(")");
             }
             ExpressionKind::Block(block) => self.block(block),
             ExpressionKind::If(if_expression) => self.if_expression(if_expression),
+            ExpressionKind::Let(let_expression) => {
+                self.token("let ");
+                self.pattern(&let_expression.pattern);
+                self.token(" = ");
+                self.expression(&let_expression.scrutinee);
+            }
+            ExpressionKind::Loop(loop_expression) => self.loop_expression(loop_expression),
+            ExpressionKind::Match(match_expression) => self.match_expression(match_expression),
+            ExpressionKind::Break(break_expression) => {
+                self.token("break");
+                if let Some(label) = &break_expression.label {
+                    self.space();
+                    self.display(label);
+                }
+                if let Some(value) = &break_expression.value {
+                    self.space();
+                    self.expression(value);
+                }
+            }
+            ExpressionKind::Continue(continue_expression) => {
+                self.token("continue");
+                if let Some(label) = &continue_expression.label {
+                    self.space();
+                    self.display(label);
+                }
+            }
             ExpressionKind::Tuple(elements) => self.tuple(elements),
             ExpressionKind::TupleIndexing(tuple_indexing) => {
-                self.expression(&tuple_indexing.expression);
+                self.mutable_place = mutable_place;
+                self.projection_base(&tuple_indexing.expression);
                 self.token(".");
                 self.token(tuple_indexing.index.to_string());
             }
+            ExpressionKind::Struct(adt) => self.struct_expression(adt),
+            ExpressionKind::VariantPath(path) => self.display(path),
+            ExpressionKind::EnumVariant(variant) => {
+                self.display(&variant.path);
+                self.struct_expression_fields(&variant.fields);
+            }
+            ExpressionKind::Field(field) => {
+                self.mutable_place = mutable_place;
+                self.projection_base(&field.expression);
+                self.token(".");
+                self.display(&field.field);
+            }
+            ExpressionKind::EnumProjection(projection) => {
+                self.enum_projection(projection, mutable_place)
+            }
+            ExpressionKind::Discriminant(discriminant) => {
+                self.discriminant(discriminant, mutable_place)
+            }
+            ExpressionKind::DiscriminantOf(path) => self.discriminant_of(path),
             ExpressionKind::Call(call) => {
                 self.expression(&call.callee);
                 self.token("(");
@@ -258,6 +440,80 @@ impl Printer {
         }
     }
 
+    fn enum_projection(&mut self, projection: &EnumProjectionExpression, mutable_place: bool) {
+        if let Some(rust) = &self.rust
+            && let Some(layout) = rust.variant(&projection.variant)
+            && let Some(index) = layout
+                .fields
+                .iter()
+                .position(|field| *field == projection.field)
+        {
+            // The discriminant comes before the fields.
+            let (struct_name, index) = (layout.struct_name.to_string(), index + 1);
+            self.token("(");
+            self.cast_place(&projection.expression, &struct_name, mutable_place);
+            self.token(format!(").{index}"));
+            return;
+        }
+        self.mutable_place = mutable_place;
+        self.projection_base(&projection.expression);
+        self.token(".");
+        self.display(&projection.variant.variant);
+        self.token(".");
+        self.display(&projection.field);
+    }
+
+    fn discriminant(&mut self, discriminant: &DiscriminantExpression, mutable_place: bool) {
+        if self.rust.is_some() {
+            self.token("(");
+            self.cast_place(&discriminant.expression, "isize", mutable_place);
+            self.token(")");
+            return;
+        }
+        self.projection_base(&discriminant.expression);
+        self.token(".enum#discriminant");
+    }
+
+    fn discriminant_of(&mut self, path: &VariantPath) {
+        if let Some(rust) = &self.rust
+            && let Some(layout) = rust.variant(path)
+        {
+            let discriminant = layout.discriminant;
+            self.token(format!("{discriminant}isize"));
+            return;
+        }
+        self.token("discriminant_of!(");
+        self.display(&path.enum_name);
+        self.token(", ");
+        self.display(&path.variant);
+        self.token(")");
+    }
+
+    /// The place at the address of `place`, seen as a `ty`. Only used to print real Rust.
+    fn cast_place(&mut self, place: &Expression, ty: &str, mutable_place: bool) {
+        let (reference, raw) = if mutable_place {
+            ("&mut", "&raw mut")
+        } else {
+            ("&", "&raw const")
+        };
+        self.token(format!("*unsafe {{ {reference} *({raw} "));
+        self.mutable_place = mutable_place;
+        self.projection_base(place);
+        self.token(format!(").cast::<{ty}>() }}"));
+    }
+
+    /// The operand of a field or tuple indexing expression. Desugarings remove the parentheses
+    /// that were needed around e.g. a dereference, so we put them back.
+    fn projection_base(&mut self, expression: &Expression) {
+        if let ExpressionKind::Operator(_) = expression.kind {
+            self.token("(");
+            self.expression(expression);
+            self.token(")");
+        } else {
+            self.expression(expression);
+        }
+    }
+
     fn tuple(&mut self, elements: &[Expression]) {
         self.token("(");
         self.comma_separated(elements, |printer, element| {
@@ -269,6 +525,26 @@ impl Printer {
         self.token(")");
     }
 
+    fn struct_expression(&mut self, adt: &StructExpression) {
+        self.display(&adt.path);
+        self.struct_expression_fields(&adt.fields);
+    }
+
+    fn struct_expression_fields(&mut self, fields: &[StructExprField]) {
+        if fields.is_empty() {
+            self.token(" {}");
+            return;
+        }
+        self.token(" { ");
+        self.comma_separated(fields, |printer, field| {
+            printer.attrs(&field.attrs);
+            printer.display(&field.name);
+            printer.token(": ");
+            printer.expression(&field.value);
+        });
+        self.token(" }");
+    }
+
     fn if_expression(&mut self, if_expression: &IfExpression) {
         self.token("if ");
         self.expression(&if_expression.condition);
@@ -292,30 +568,118 @@ impl Printer {
         }
     }
 
+    fn loop_expression(&mut self, loop_expression: &LoopExpression) {
+        if let Some(label) = &loop_expression.label {
+            self.display(label);
+            self.token(": ");
+        }
+        match &loop_expression.kind {
+            LoopKind::Infinite(body) => {
+                self.token("loop ");
+                self.block(body);
+            }
+            LoopKind::While { condition, body } => {
+                self.token("while ");
+                self.expression(condition);
+                self.space();
+                self.block(body);
+            }
+        }
+    }
+
+    fn match_expression(&mut self, match_expression: &MatchExpression) {
+        self.token("match ");
+        self.expression(&match_expression.scrutinee);
+        if match_expression.arms.is_empty() {
+            self.token(" {}");
+            return;
+        }
+        self.token(" {");
+        self.indented(|printer| {
+            for arm in &match_expression.arms {
+                printer.newline();
+                printer.attrs(&arm.attrs);
+                printer.pattern(&arm.pattern);
+                if let Some(guard) = &arm.guard {
+                    printer.token(" if ");
+                    printer.expression(guard);
+                }
+                printer.token(" => ");
+                printer.expression(&arm.body);
+                if !arm.body.is_with_block() {
+                    printer.token(",");
+                }
+            }
+        });
+        self.newline();
+        self.token("}");
+    }
+
     fn operator_expression(&mut self, operator: &OperatorExpression) {
+        let precedence = operator.precedence();
         match operator {
             OperatorExpression::Borrow(borrow) => {
                 self.token("&");
                 self.display(borrow.mutability);
-                self.expression(&borrow.expression);
+                self.mutable_place = borrow.mutability == Mutability::Mutable;
+                self.operand(&borrow.expression, precedence);
             }
             OperatorExpression::Dereference(dereference) => {
                 self.token("*");
-                self.expression(&dereference.expression);
+                self.operand(&dereference.expression, precedence);
             }
-            OperatorExpression::Add(left, right) => {
-                self.expression(left);
-                self.token(" + ");
-                self.expression(right);
+            OperatorExpression::Negation(operator, operand) => {
+                self.token(operator.symbol());
+                self.operand(operand, precedence);
+            }
+            OperatorExpression::Binary(operator, left, right) => {
+                self.operand(left, precedence);
+                self.token(format!(" {} ", operator.symbol()));
+                self.operand(right, precedence + 1);
+            }
+            OperatorExpression::Comparison(operator, left, right) => {
+                self.operand(left, precedence + 1);
+                self.token(format!(" {} ", operator.symbol()));
+                self.operand(right, precedence + 1);
             }
             OperatorExpression::Assignment(left, right) => {
-                self.expression(left);
+                self.mutable_place = true;
+                self.operand(left, precedence + 1);
                 self.token(" = ");
-                self.expression(right);
+                self.operand(right, precedence);
+            }
+            OperatorExpression::CompoundAssignment(operator, left, right) => {
+                self.mutable_place = true;
+                self.operand(left, precedence + 1);
+                self.token(format!(" {}= ", operator.symbol()));
+                self.operand(right, precedence);
+            }
+            OperatorExpression::LazyAnd(left, right) => {
+                self.operand(left, precedence);
+                self.token(" && ");
+                self.operand(right, precedence + 1);
+            }
+            OperatorExpression::LazyOr(left, right) => {
+                self.operand(left, precedence);
+                self.token(" || ");
+                self.operand(right, precedence + 1);
             }
         }
     }
 
+    /// An operand of an operator that binds at least as tightly as `min_precedence`. Desugarings
+    /// remove the parentheses that were needed around looser operators, so we put them back.
+    fn operand(&mut self, expression: &Expression, min_precedence: u8) {
+        match &expression.kind {
+            ExpressionKind::Operator(operator) if operator.precedence() < min_precedence => {
+                self.token("(");
+                self.expression(expression);
+                self.token(")");
+            }
+            _ => self.expression(expression),
+        }
+    }
+
     fn virtual_expression(&mut self, virtual_expression: &VirtualExpression) {
         match virtual_expression {
             VirtualExpression::ValueToPlaceCoercion(expression) => {
@@ -328,10 +692,160 @@ impl Printer {
                 self.expression(expression);
                 self.token(")");
             }
+            VirtualExpression::Unreachable => self.token("unreachable_unchecked!()"),
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) {
+        match &pattern.kind {
+            // Our bindings can all be mutated.
+            PatternKind::Identifier(name) if self.rust.is_some() => {
+                self.token("mut ");
+                self.local(name);
+            }
+            PatternKind::Tuple(patterns) if self.rust.is_some() => {
+                self.token("(");
+                self.comma_separated(patterns, |printer, pattern| printer.pattern(pattern));
+                if patterns.len() == 1 {
+                    self.token(",");
+                }
+                self.token(")");
+            }
+            PatternKind::Variant(variant) if self.rust.is_some() => {
+                self.display(&variant.path);
+                if variant.fields.is_empty() {
+                    self.token(" {}");
+                    return;
+                }
+                self.token(" { ");
+                self.comma_separated(&variant.fields, |printer, field| {
+                    printer.display(&field.name);
+                    printer.token(": ");
+                    printer.pattern(&field.pattern);
+                });
+                self.token(" }");
+            }
+            _ => self.display(pattern),
+        }
+    }
+
+    /// The pattern of a scoped `let` as the left-hand side of an assignment to its bindings.
+    fn assignee(&mut self, pattern: &Pattern) {
+        match &pattern.kind {
+            PatternKind::Identifier(name) => self.local(name),
+            PatternKind::Tuple(patterns) => {
+                self.token("(");
+                self.comma_separated(patterns, |printer, pattern| printer.assignee(pattern));
+                if patterns.len() == 1 {
+                    self.token(",");
+                }
+                self.token(")");
+            }
+            PatternKind::Wildcard | PatternKind::Literal(_) | PatternKind::Variant(_) => {
+                self.display(pattern)
+            }
+        }
+    }
+
+    /// Declare the bindings of the scoped `let`s that name this block.
+    fn hoisted_scoped_lets(&mut self, block: &BlockExpression) {
+        let Some(label) = &block.label else {
+            return;
+        };
+        let mut bindings = vec![];
+        block.visit_all_infallible(|statement: &Statement| {
+            if let Statement::Let {
+                scope: Some(scope),
+                pattern,
+                ty,
+                else_branch: None,
+                ..
+            } = statement
+                && scope == label
+            {
+                pattern_bindings(pattern, ty.as_ref(), &mut bindings);
+            }
+        });
+        for (name, ty) in bindings {
+            self.newline();
+            self.token("let mut ");
+            self.local(&name);
+            if let Some(ty) = ty {
+                self.token(": ");
+                self.display(ty);
+            }
+            self.token(";");
+        }
+    }
+
+    /// A `#[repr(C)]` struct laid out like the variant of a `#[repr(isize)]` enum.
+    fn variant_struct(&mut self, enum_name: &Identifier, variant: &EnumVariant) {
+        let path = VariantPath {
+            enum_name: enum_name.clone(),
+            variant: variant.name.clone(),
+        };
+        let Some(layout) = self.rust.as_ref().and_then(|rust| rust.variant(&path)) else {
+            return;
+        };
+        let struct_name = layout.struct_name.clone();
+        let field_types: Vec<&Type> = match &variant.fields {
+            StructFields::Named(fields) => fields.iter().map(|field| &field.ty).collect(),
+            StructFields::Tuple(fields) => fields.iter().map(|field| &field.ty).collect(),
+            StructFields::Unit => vec![],
+        };
+        self.newline();
+        self.newline();
+        self.token("#[repr(C)]");
+        self.newline();
+        self.token(format!("struct {struct_name}(isize"));
+        for ty in field_types {
+            self.token(", ");
+            self.display(ty);
         }
+        self.token(");");
     }
 }
 
+/// The bindings of `pattern`, with their type when `ty` gives it.
+fn pattern_bindings(
+    pattern: &Pattern,
+    ty: Option<&Type>,
+    bindings: &mut Vec<(Identifier, Option<Type>)>,
+) {
+    match &pattern.kind {
+        PatternKind::Identifier(name) => bindings.push((name.clone(), ty.cloned())),
+        PatternKind::Tuple(patterns) => {
+            for (index, pattern) in patterns.iter().enumerate() {
+                let ty = match ty.map(|ty| &ty.kind) {
+                    Some(TypeKind::Tuple(types)) => types.get(index),
+                    _ => None,
+                };
+                pattern_bindings(pattern, ty, bindings);
+            }
+        }
+        // We'd need the declaration of the enum to know the types of the fields.
+        PatternKind::Variant(variant) => {
+            for field in &variant.fields {
+                pattern_bindings(&field.pattern, None, bindings);
+            }
+        }
+        PatternKind::Wildcard | PatternKind::Literal(_) => {}
+    }
+}
+
+/// A scoped `let` without an initial value, which `hoisted_scoped_lets` already printed.
+fn is_hoisted_declaration(statement: &Statement) -> bool {
+    matches!(
+        statement,
+        Statement::Let {
+            scope: Some(_),
+            initial_value: None,
+            else_branch: None,
+            ..
+        }
+    )
+}
+
 impl Display for Program {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.items.iter().format("\n\n"))
@@ -376,7 +890,83 @@ impl Display for ItemKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             ItemKind::Function(function) => write!(f, "{function}"),
+            ItemKind::Struct(adt) => write!(f, "{adt}"),
+            ItemKind::Enum(adt) => write!(f, "{adt}"),
+        }
+    }
+}
+
+impl Display for Struct {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "struct {}{}", self.name, self.generic_params)?;
+        match &self.fields {
+            StructFields::Named(fields) if fields.is_empty() => {
+                write!(f, "{} {{}}", self.where_clauses)
+            }
+            StructFields::Named(fields) => write!(
+                f,
+                "{} {{ {} }}",
+                self.where_clauses,
+                fields.iter().format(", ")
+            ),
+            StructFields::Tuple(fields) => {
+                write!(f, "({}){};", fields.iter().format(", "), self.where_clauses)
+            }
+            StructFields::Unit => write!(f, "{};", self.where_clauses),
+        }
+    }
+}
+
+impl Display for Enum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "enum {}{}{} {{",
+            self.name, self.generic_params, self.where_clauses
+        )?;
+        if !self.variants.is_empty() {
+            write!(f, " {} ", self.variants.iter().format(", "))?;
+        }
+        f.write_str("}")
+    }
+}
+
+impl Display for EnumVariant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.attrs.is_empty() {
+            write!(f, "{} ", self.attrs.iter().format(" "))?;
+        }
+        write!(f, "{}", self.name)?;
+        match &self.fields {
+            StructFields::Named(fields) if fields.is_empty() => f.write_str(" {}"),
+            StructFields::Named(fields) => write!(f, " {{ {} }}", fields.iter().format(", ")),
+            StructFields::Tuple(fields) => write!(f, "({})", fields.iter().format(", ")),
+            StructFields::Unit => Ok(()),
+        }
+    }
+}
+
+impl Display for StructField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.attrs.is_empty() {
+            write!(f, "{} ", self.attrs.iter().format(" "))?;
+        }
+        if let Some(visibility) = &self.visibility {
+            write!(f, "{visibility} ")?;
         }
+        write!(f, "{}: {}", self.name, self.ty)
+    }
+}
+
+impl Display for TupleField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.attrs.is_empty() {
+            write!(f, "{} ", self.attrs.iter().format(" "))?;
+        }
+        if let Some(visibility) = &self.visibility {
+            write!(f, "{visibility} ")?;
+        }
+        write!(f, "{}", self.ty)
     }
 }
 
@@ -489,12 +1079,14 @@ impl Display for FunctionParamType {
 
 impl Display for Type {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Type::Tuple(types) => write_tuple(f, types),
-            Type::Bool => write!(f, "bool"),
-            Type::Str => write!(f, "str"),
-            Type::TraitSelf => write!(f, "Self"),
-            Type::Ref(lifetime, mutability, ty) => {
+        match &self.kind {
+            TypeKind::Tuple(types) => write_tuple(f, types),
+            TypeKind::Bool => write!(f, "bool"),
+            TypeKind::Str => write!(f, "str"),
+            TypeKind::Int(ty) => write!(f, "{ty}"),
+            TypeKind::TraitSelf => write!(f, "Self"),
+            TypeKind::Path(path, _) => write!(f, "{path}"),
+            TypeKind::Ref(lifetime, mutability, ty) => {
                 f.write_str("&")?;
                 if let Some(lifetime) = lifetime {
                     write!(f, "{lifetime} ")?;
@@ -534,6 +1126,8 @@ impl Display for Statement {
                 ty,
                 initial_value,
                 else_branch,
+                span: _,
+                origin: _,
             } => {
                 write!(f, "{} ", attrs.iter().format(" "))?;
                 f.write_str("let")?;
@@ -560,7 +1154,13 @@ impl Display for Statement {
 
 impl Expression {
     fn is_with_block(&self) -> bool {
-        matches!(self.kind, ExpressionKind::Block(_) | ExpressionKind::If(_))
+        matches!(
+            self.kind,
+            ExpressionKind::Block(_)
+                | ExpressionKind::If(_)
+                | ExpressionKind::Loop(_)
+                | ExpressionKind::Match(_)
+        )
     }
 }
 
@@ -581,9 +1181,23 @@ impl Display for ExpressionKind {
             ExpressionKind::Grouped(grouped) => write!(f, "({grouped})"),
             ExpressionKind::Block(block) => write!(f, "{block}"),
             ExpressionKind::If(if_expression) => write!(f, "{if_expression}"),
+            ExpressionKind::Let(let_expression) => write!(f, "{let_expression}"),
+            ExpressionKind::Loop(loop_expression) => write!(f, "{loop_expression}"),
+            ExpressionKind::Match(match_expression) => write!(f, "{match_expression}"),
+            ExpressionKind::Break(break_expression) => write!(f, "{break_expression}"),
+            ExpressionKind::Continue(continue_expression) => write!(f, "{continue_expression}"),
             ExpressionKind::Tuple(elements) => write_tuple(f, elements),
             ExpressionKind::Call(call) => write!(f, "{call}"),
             ExpressionKind::TupleIndexing(tuple_indexing) => write!(f, "{tuple_indexing}"),
+            ExpressionKind::Struct(adt) => write!(f, "{adt}"),
+            ExpressionKind::VariantPath(path) => write!(f, "{path}"),
+            ExpressionKind::EnumVariant(variant) => write!(f, "{variant}"),
+            ExpressionKind::Field(field) => write!(f, "{field}"),
+            ExpressionKind::EnumProjection(projection) => write!(f, "{projection}"),
+            ExpressionKind::Discriminant(discriminant) => write!(f, "{discriminant}"),
+            ExpressionKind::DiscriminantOf(path) => {
+                write!(f, "discriminant_of!({}, {})", path.enum_name, path.variant)
+            }
         }
     }
 }
@@ -598,6 +1212,22 @@ impl Display for LiteralExpression {
     }
 }
 
+impl Display for IntegerLiteral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)?;
+        if let Some(suffix) = self.suffix {
+            write!(f, "{suffix}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for IntType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 impl Display for IfExpression {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "if{}{}", self.condition, self.then_branch)?;
@@ -616,12 +1246,142 @@ impl Display for IfExpression {
     }
 }
 
+impl Display for LetExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "let {} = {}", self.pattern, self.scrutinee)
+    }
+}
+
+impl Display for LoopExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(label) = &self.label {
+            write!(f, "{label}: ")?;
+        }
+        match &self.kind {
+            LoopKind::Infinite(body) => write!(f, "loop {body}"),
+            LoopKind::While { condition, body } => write!(f, "while {condition} {body}"),
+        }
+    }
+}
+
+impl Display for MatchExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "match {} {{", self.scrutinee)?;
+        for arm in &self.arms {
+            write!(f, " {arm}")?;
+        }
+        f.write_str(" }")
+    }
+}
+
+impl Display for MatchArm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.attrs.is_empty() {
+            write!(f, "{} ", self.attrs.iter().format(" "))?;
+        }
+        write!(f, "{}", self.pattern)?;
+        if let Some(guard) = &self.guard {
+            write!(f, " if {guard}")?;
+        }
+        write!(f, " => {},", self.body)
+    }
+}
+
+impl Display for BreakExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("break")?;
+        if let Some(label) = &self.label {
+            write!(f, " {label}")?;
+        }
+        if let Some(value) = &self.value {
+            write!(f, " {value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for ContinueExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("continue")?;
+        if let Some(label) = &self.label {
+            write!(f, " {label}")?;
+        }
+        Ok(())
+    }
+}
+
 impl Display for TupleIndexingExpression {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}.{}", self.expression, self.index)
     }
 }
 
+impl Display for StructExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {{", self.path)?;
+        if !self.fields.is_empty() {
+            write!(f, " {} ", self.fields.iter().format(", "))?;
+        }
+        f.write_str("}")
+    }
+}
+
+impl Display for StructExprField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.attrs.is_empty() {
+            write!(f, "{} ", self.attrs.iter().format(" "))?;
+        }
+        write!(f, "{}: {}", self.name, self.value)
+    }
+}
+
+impl Display for FieldName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldName::Named(name) => write!(f, "{name}"),
+            FieldName::Index(index) => write!(f, "{index}"),
+        }
+    }
+}
+
+impl Display for VariantPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.enum_name, self.variant)
+    }
+}
+
+impl Display for EnumVariantExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {{", self.path)?;
+        if !self.fields.is_empty() {
+            write!(f, " {} ", self.fields.iter().format(", "))?;
+        }
+        f.write_str("}")
+    }
+}
+
+impl Display for EnumProjectionExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}",
+            self.expression, self.variant.variant, self.field
+        )
+    }
+}
+
+impl Display for DiscriminantExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.enum#discriminant", self.expression)
+    }
+}
+
+impl Display for FieldExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.expression, self.field)
+    }
+}
+
 impl Display for CallExpression {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}({})", self.callee, self.args.iter().format(", "))
@@ -637,6 +1397,7 @@ impl Display for VirtualExpression {
             VirtualExpression::PlaceToValueCoercion(expression) => {
                 write!(f, "place_to_value!({expression})")
             }
+            VirtualExpression::Unreachable => f.write_str("unreachable_unchecked!()"),
         }
     }
 }
@@ -646,8 +1407,21 @@ impl Display for OperatorExpression {
         match self {
             OperatorExpression::Borrow(borrow) => write!(f, "{borrow}"),
             OperatorExpression::Dereference(dereference) => write!(f, "{dereference}"),
-            OperatorExpression::Add(left, right) => write!(f, "{left} + {right}"),
+            OperatorExpression::Negation(operator, operand) => {
+                write!(f, "{}{operand}", operator.symbol())
+            }
+            OperatorExpression::Binary(operator, left, right) => {
+                write!(f, "{left} {} {right}", operator.symbol())
+            }
+            OperatorExpression::Comparison(operator, left, right) => {
+                write!(f, "{left} {} {right}", operator.symbol())
+            }
             OperatorExpression::Assignment(left, right) => write!(f, "{left} = {right}"),
+            OperatorExpression::CompoundAssignment(operator, left, right) => {
+                write!(f, "{left} {}= {right}", operator.symbol())
+            }
+            OperatorExpression::LazyAnd(left, right) => write!(f, "{left} && {right}"),
+            OperatorExpression::LazyOr(left, right) => write!(f, "{left} || {right}"),
         }
     }
 }
@@ -690,9 +1464,154 @@ impl Display for Lifetime {
 
 impl Display for Pattern {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Pattern::Identifier(name) => f.write_str(name),
-            Pattern::Wildcard => f.write_str("_"),
+        match &self.kind {
+            PatternKind::Identifier(name) => write!(f, "{name}"),
+            PatternKind::Wildcard => f.write_str("_"),
+            PatternKind::Literal(literal) => write!(f, "{literal}"),
+            PatternKind::Tuple(patterns) => write_tuple(f, patterns),
+            PatternKind::Variant(variant) => write!(f, "{variant}"),
+        }
+    }
+}
+
+impl Display for VariantPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {{", self.path)?;
+        if !self.fields.is_empty() {
+            write!(f, " {} ", self.fields.iter().format(", "))?;
         }
+        f.write_str("}")
+    }
+}
+
+impl Display for StructPatternField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.pattern)
+    }
+}
+
+//@ ## Printing real Rust
+//@
+//@ The programs above use syntax that only exists in this book, so rustc can't compile them.
+//@ `print_program_as_rust` prints a program as self-contained Rust instead, that can be pasted
+//@ into the playground:
+//@ - it starts with a prelude that defines `print` and a macro for each virtual expression;
+//@ - bindings are all `mut`, since our language doesn't keep track of which ones get mutated;
+//@ - a name with a hygiene marker like `tmp#1` becomes a plain name like `tmp_1`, or `tmp_1_` if
+//@   the program already uses `tmp_1`;
+//@ - a scoped `let` becomes an assignment, and its bindings are declared at the start of the block
+//@   it names;
+//@ - enums get `#[repr(isize)]`, which lays each variant out like a `#[repr(C)]` struct that starts
+//@   with the discriminant. We declare such a struct for each variant, then access the discriminant
+//@   and the enum projections of a place through a raw pointer to it, cast to `isize` or to the
+//@   struct of the variant.
+pub fn print_program_as_rust(program: &Program) -> String {
+    let mut printer = Printer::new();
+    printer.rust = Some(RustEncoding::new(program));
+    printer.program(program);
+    format!("{RUST_PRELUDE}\n{}", printer.finish())
+}
+
+/// The definitions that real Rust needs to compile the programs of the book.
+pub const RUST_PRELUDE: &str = "\
+#![allow(unused, non_camel_case_types)]
+
+fn print<T: std::fmt::Display>(value: T) {
+    println!(\"{value}\");
+}
+
+macro_rules! place_to_value {
+    ($place:expr) => {
+        $place
+    };
+}
+
+macro_rules! value_to_place {
+    ($value:expr) => {
+        $value
+    };
+}
+
+macro_rules! unreachable_unchecked {
+    () => {
+        unsafe { ::std::hint::unreachable_unchecked() }
+    };
+}
+";
+
+/// How the book-only syntax of a program is written in real Rust.
+struct RustEncoding {
+    /// The plain names that replace the names with a hygiene marker.
+    locals: HashMap<Identifier, Identifier>,
+    variants: Vec<VariantLayout>,
+}
+
+struct VariantLayout {
+    path: VariantPath,
+    discriminant: usize,
+    /// The struct laid out like the variant.
+    struct_name: Identifier,
+    /// The fields of the variant, in the order of the struct.
+    fields: Vec<FieldName>,
+}
+
+impl RustEncoding {
+    fn new(program: &Program) -> Self {
+        let mut taken = HashSet::new();
+        let mut hygienic = BTreeSet::new();
+        program.visit_all_infallible(|name: &Identifier| {
+            if name.is_hygienic() {
+                hygienic.insert(name.clone());
+            } else {
+                taken.insert(name.name.clone());
+            }
+        });
+        let mut enums = vec![];
+        program.visit_all_infallible(|adt: &Enum| enums.push(adt.clone()));
+
+        let mut fresh = |mut name: String| {
+            while !taken.insert(name.clone()) {
+                name.push('_');
+            }
+            Identifier::new(name)
+        };
+        let mut locals = HashMap::new();
+        for name in hygienic {
+            let plain = fresh(format!("{}_{}", name.name, name.hygiene));
+            locals.insert(name, plain);
+        }
+        let mut variants = vec![];
+        for adt in enums {
+            for (discriminant, variant) in adt.variants.iter().enumerate() {
+                let fields = match &variant.fields {
+                    StructFields::Named(fields) => fields
+                        .iter()
+                        .map(|field| FieldName::Named(field.name.clone()))
+                        .collect(),
+                    StructFields::Tuple(fields) => {
+                        (0..fields.len()).map(FieldName::Index).collect()
+                    }
+                    StructFields::Unit => vec![],
+                };
+                variants.push(VariantLayout {
+                    path: VariantPath {
+                        enum_name: adt.name.clone(),
+                        variant: variant.name.clone(),
+                    },
+                    discriminant,
+                    struct_name: fresh(format!("{}_{}", adt.name, variant.name)),
+                    fields,
+                });
+            }
+        }
+        RustEncoding { locals, variants }
+    }
+
+    fn local<'a>(&'a self, name: &'a Identifier) -> &'a Identifier {
+        self.locals.get(name).unwrap_or(name)
+    }
+
+    fn variant(&self, path: &VariantPath) -> Option<&VariantLayout> {
+        self.variants.iter().find(|layout| layout.path == *path)
     }
 }
@@ -0,0 +1,207 @@
+//@ # Provenance
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+//@ Each expression, block, pattern, type, `let` statement and item carries an `Origin`, made of a
+//@ `NodeId` that identifies the node and, for a node that a desugaring made out of another one, a
+//@ link to that other node. A node that a pass only modifies in place keeps its id. Following the links from a node of the
+//@ desugared program therefore leads back to a node of the source program, through the nodes the
+//@ passes made along the way.
+//@
+//@ Ids are given per program, see [Node ids](#node-ids) below.
+use std::collections::{HashMap, HashSet}; //#
+
+use crate::language::*; //#
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)] //#
+#[derive(Serialize, Deserialize)] //#
+pub struct NodeId(pub u32);
+
+impl NodeId {
+    /// The id of a node that didn't get one yet.
+    pub const UNASSIGNED: NodeId = NodeId(0);
+}
+
+/// Where a node comes from. Like spans, origins are ignored when comparing nodes.
+#[derive(Debug, Clone)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct Origin {
+    pub id: NodeId,
+    /// For a node made by a desugaring, the node it was made from.
+    #[serde(default, skip_serializing_if = "Option::is_none")] //#
+    pub derived_from: Option<Box<Derivation>>,
+}
+
+#[derive(Debug, Clone)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct Derivation {
+    /// The pass that made the node. Passes don't fill this in themselves: the pipeline does after
+    /// running each pass.
+    pub pass: Option<String>,
+    pub source: Origin,
+}
+
+impl Origin {
+    /// The origin of a node that wasn't made from another one, e.g. a node of the source program.
+    /// The node gets its id from [`NodeIds::assign`].
+    pub fn new() -> Self {
+        Origin {
+            id: NodeId::UNASSIGNED,
+            derived_from: None,
+        }
+    }
+
+    /// The origin of a new node made from the node with origin `self`. The node gets its id from
+    /// [`NodeIds::assign`].
+    pub fn derive(&self) -> Self {
+        Origin {
+            id: NodeId::UNASSIGNED,
+            derived_from: Some(Box::new(Derivation {
+                pass: None,
+                source: self.clone(),
+            })),
+        }
+    }
+
+    /// The ids of this node, of the node it was made from, and so on until a node that wasn't made
+    /// from another one. Each id comes with the pass that made that node, if any.
+    pub fn chain(&self) -> Vec<(NodeId, Option<&str>)> {
+        let mut chain = vec![];
+        let mut origin = self;
+        loop {
+            let derivation = origin.derived_from.as_deref();
+            chain.push((origin.id, derivation.and_then(|d| d.pass.as_deref())));
+            match derivation {
+                Some(derivation) => origin = &derivation.source,
+                None => return chain,
+            }
+        }
+    }
+
+    /// The id of the node this node was ultimately made from.
+    pub fn source(&self) -> NodeId {
+        match &self.derived_from {
+            Some(derivation) => derivation.source.source(),
+            None => self.id,
+        }
+    }
+
+    /// Record that `pass` made this node, unless we already know which pass did.
+    pub fn attribute_to(&mut self, pass: &str) {
+        if let Some(derivation) = &mut self.derived_from
+            && derivation.pass.is_none()
+        {
+            derivation.pass = Some(pass.to_owned());
+        }
+    }
+}
+
+impl Default for Origin {
+    fn default() -> Self {
+        Origin::new()
+    }
+}
+
+impl PartialEq for Origin {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Origin {}
+
+//@ ## Node ids
+//@
+//@ The ids of a program are numbered from 1, in the order we visit its nodes. Passes don't hand out
+//@ ids themselves: they make nodes with `Origin::new()` or `derive()`, which leave the id
+//@ unassigned, and may copy nodes with `clone()`. Once the pass is done, the pipeline gives the new
+//@ nodes ids that come after every id the program used so far. A copy of a node must not share
+//@ its id, so each copy after the first one becomes a node derived from it. This makes ids
+//@ deterministic, and lets a program read back from JSON keep its ids: the new nodes get ids
+//@ after the ones it already has.
+/// Hands out the ids of the nodes of a program.
+pub struct NodeIds {
+    next: u32,
+}
+
+impl NodeIds {
+    /// A supply of ids for `program`. We start after every id the program mentions, including the
+    /// ids of the nodes its nodes were made from, so that an id never names two different nodes.
+    pub fn new(program: &Program) -> Self {
+        let mut last = 0;
+        program.visit_all_infallible(|origin: &Origin| last = last.max(origin.id.0));
+        NodeIds { next: last + 1 }
+    }
+
+    pub fn fresh(&mut self) -> NodeId {
+        let id = NodeId(self.next);
+        self.next += 1;
+        id
+    }
+
+    /// Give an id to the nodes of `program` that don't have one yet, and turn the nodes that share
+    /// their id with a node we visited before them into nodes derived from that node.
+    pub fn assign(&mut self, program: &mut Program) {
+        let mut seen = HashSet::new();
+        for_each_node_origin(program, |origin| {
+            if origin.id == NodeId::UNASSIGNED {
+                origin.id = self.fresh();
+            } else if !seen.insert(origin.id) {
+                let mut copy = origin.derive();
+                copy.id = self.fresh();
+                *origin = copy;
+            }
+        });
+    }
+}
+
+impl Program {
+    /// Give ids to the nodes of a program that was just parsed or built, see [`NodeIds`].
+    pub fn assign_node_ids(&mut self) {
+        NodeIds::new(self).assign(self);
+    }
+}
+
+impl Expression {
+    /// A copy of this expression, for a pass that needs to mention it more than once. Each node of
+    /// the copy is derived from the node it copies.
+    pub fn derived_copy(&self) -> Expression {
+        let mut copy = Some(self.clone());
+        for_each_node_origin(&mut copy, |origin| *origin = origin.derive());
+        copy.unwrap()
+    }
+}
+
+/// Call `f` on the origin of each node inside `node`. Unlike visiting every `Origin`, this skips
+/// the origins of the nodes they were made from.
+fn for_each_node_origin(node: &mut impl AstVisitable, mut f: impl FnMut(&mut Origin)) {
+    node.visit_all_mut_infallible(|item: &mut Item| f(&mut item.origin));
+    node.visit_all_mut_infallible(|statement: &mut Statement| {
+        if let Statement::Let { origin, .. } = statement {
+            f(origin);
+        }
+    });
+    node.visit_all_mut_infallible(|expression: &mut Expression| f(&mut expression.origin));
+    node.visit_all_mut_infallible(|block: &mut BlockExpression| f(&mut block.origin));
+    node.visit_all_mut_infallible(|pattern: &mut Pattern| f(&mut pattern.origin));
+    node.visit_all_mut_infallible(|ty: &mut Type| f(&mut ty.origin));
+}
+
+//@ To point at the source a node of the desugared program comes from, we record where each node
+//@ of the parsed program is before desugaring it, and look up the `source()` of the node there.
+/// The span of each node of `program`, by id.
+pub fn spans_by_id(program: &Program) -> HashMap<NodeId, Span> {
+    let mut spans = HashMap::new();
+    program.visit_all_infallible(|expression: &Expression| {
+        spans.insert(expression.origin.id, expression.span);
+    });
+    program.visit_all_infallible(|statement: &Statement| {
+        if let Statement::Let { origin, span, .. } = statement {
+            spans.insert(origin.id, *span);
+        }
+    });
+    program.visit_all_infallible(|item: &Item| {
+        spans.insert(item.origin.id, item.span);
+    });
+    spans
+}
@@ -15,14 +15,14 @@ use crate::language::*; //#
 //@     ( `=` initial_value=Expression )?
 //@     ( `else` else_branch=BlockExpressionNoInnerAttributes )?
 //@     `;`
-//@     => Statement::Let { attrs, scope: None, pattern, ty, initial_value, else_branch },
+//@     => Statement::Let { attrs, scope: None, pattern, ty, initial_value, else_branch, span, origin: Origin::new() },
 //@
 //@ ExpressionStatement -> Expression:
 //@     | expr=ExpressionWithoutBlock `;` => expr,
 //@     | expr=ExpressionWithBlock `;`? => expr,
 //@ ```
 #[derive(Debug, Clone, PartialEq, Eq)] //#
-#[derive(Drive, DriveMut)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
 pub enum Statement {
     Empty,
     Item(Item),
@@ -30,7 +30,7 @@ pub enum Statement {
         attrs: Vec<OuterAttribute>,
         /// A block label, to make a `let` defined in another scope than the current one. This is a
         /// made-up feature to make desugarings easier, see "Scoped Let" for a description.
-        scope: Option<Identifier>,
+        scope: Option<String>,
         /// The "binding" part of a `let` can be an arbitrary pattern.
         pattern: Pattern,
         /// Optional type annotation.
@@ -40,6 +40,30 @@ pub enum Statement {
         /// Optional `else` branch, executed if the pattern fails to match the initial value
         /// provided.
         else_branch: Option<BlockExpression>,
+        #[serde(default)] //#
+        span: Span,
+        #[serde(default = "Origin::new")] //#
+        origin: Origin,
     },
     Expr(Expression),
 }
+
+impl Statement {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Empty => Span::dummy(),
+            Statement::Item(item) => item.span,
+            Statement::Let { span, .. } => *span,
+            Statement::Expr(expression) => expression.span,
+        }
+    }
+
+    pub fn origin(&self) -> Option<&Origin> {
+        match self {
+            Statement::Empty => None,
+            Statement::Item(item) => Some(&item.origin),
+            Statement::Let { origin, .. } => Some(origin),
+            Statement::Expr(expression) => Some(&expression.origin),
+        }
+    }
+}
@@ -4,11 +4,16 @@
 //@
 //@ ```grammar
 //@ Type:
-//@     | `bool` => Type::Bool
-//@     | `str` => Type::Str
-//@     | `(` types=TupleTypes? `)` => Type::Tuple(types.unwrap_or_default())
-//@     | `Self` => Type::TraitSelf
-//@     | `&` lifetime=Lifetime? m=Mutability ty=Type => Type::Ref(lifetime, m, Box::new(ty))
+//@     | `bool` => Type::new(TypeKind::Bool)
+//@     | `str` => Type::new(TypeKind::Str)
+//@     | ty=IntType => Type::new(TypeKind::Int(ty))
+//@     | `(` types=TupleTypes? `)` => Type::new(TypeKind::Tuple(types.unwrap_or_default()))
+//@     | `Self` => Type::new(TypeKind::TraitSelf)
+//@     | path=SimplePath => Type::new(TypeKind::Path(path, span))
+//@     | `&` lifetime=Lifetime? m=Mutability ty=Type
+//@       => Type::new(TypeKind::Ref(lifetime, m, Box::new(ty)))
+//@     | `&&` lifetime=Lifetime? m=Mutability ty=Type
+//@       => Type::double_ref(lifetime, m, ty)
 //@
 //@ TupleTypes -> Vec<Type>:
 //@     types=(Type `,`)+ last=Type?
@@ -16,17 +21,151 @@
 //@ ```
 //@
 use crate::language::*; //#
-#[derive(Debug, Clone, PartialEq, Eq, Drive, DriveMut)] //#
-pub enum Type {
+#[derive(Debug, Clone, PartialEq, Eq, Drive, DriveMut, Serialize, Deserialize)] //#
+pub struct Type {
+    pub kind: TypeKind,
+    #[serde(default = "Origin::new")] //#
+    pub origin: Origin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Drive, DriveMut, Serialize, Deserialize)] //#
+pub enum TypeKind {
     Bool,
     Str,
+    Int(IntType),
     Tuple(Vec<Type>),
     TraitSelf,
     Ref(Option<Lifetime>, Mutability, Box<Type>),
+    /// A nominal type, such as a struct.
+    Path(
+        Path,
+        #[serde(default)] //#
+        Span,
+    ),
 }
 
 impl Type {
+    pub fn new(kind: TypeKind) -> Self {
+        Type {
+            kind,
+            origin: Origin::new(),
+        }
+    }
+
+    /// A new type made from the node with origin `source`.
+    pub fn derived_from(source: &Origin, kind: TypeKind) -> Self {
+        Type {
+            kind,
+            origin: source.derive(),
+        }
+    }
+
+    /// `&&` is lexed as a single token, so `&&T` is parsed as two nested reference types.
+    pub fn double_ref(lifetime: Option<Lifetime>, mutability: Mutability, ty: Type) -> Self {
+        let inner = Type::new(TypeKind::Ref(lifetime, mutability, Box::new(ty)));
+        Type::new(TypeKind::Ref(None, Mutability::Immutable, Box::new(inner)))
+    }
+
     pub fn mk_unit() -> Type {
-        Type::Tuple(Vec::new())
+        Type::new(TypeKind::Tuple(Vec::new()))
+    }
+}
+
+//@ ## Integer types
+//@
+//@ The unsigned and signed integer types of every supported width, plus the pointer-sized
+//@ `usize` and `isize` [ref:type.numeric.int].
+//@
+//@ ```grammar
+//@ IntType:
+//@     | `u8` => IntType::U8
+//@     | `u16` => IntType::U16
+//@     | `u32` => IntType::U32
+//@     | `u64` => IntType::U64
+//@     | `u128` => IntType::U128
+//@     | `usize` => IntType::Usize
+//@     | `i8` => IntType::I8
+//@     | `i16` => IntType::I16
+//@     | `i32` => IntType::I32
+//@     | `i64` => IntType::I64
+//@     | `i128` => IntType::I128
+//@     | `isize` => IntType::Isize
+//@ ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)] //#
+#[derive(Drive, DriveMut, Serialize, Deserialize)] //#
+pub enum IntType {
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+}
+
+impl IntType {
+    pub const ALL: [IntType; 12] = [
+        IntType::U8,
+        IntType::U16,
+        IntType::U32,
+        IntType::U64,
+        IntType::U128,
+        IntType::Usize,
+        IntType::I8,
+        IntType::I16,
+        IntType::I32,
+        IntType::I64,
+        IntType::I128,
+        IntType::Isize,
+    ];
+
+    /// The name of the type, which is also its literal suffix.
+    pub fn name(self) -> &'static str {
+        match self {
+            IntType::U8 => "u8",
+            IntType::U16 => "u16",
+            IntType::U32 => "u32",
+            IntType::U64 => "u64",
+            IntType::U128 => "u128",
+            IntType::Usize => "usize",
+            IntType::I8 => "i8",
+            IntType::I16 => "i16",
+            IntType::I32 => "i32",
+            IntType::I64 => "i64",
+            IntType::I128 => "i128",
+            IntType::Isize => "isize",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<IntType> {
+        IntType::ALL.into_iter().find(|ty| ty.name() == name)
+    }
+
+    pub fn is_signed(self) -> bool {
+        matches!(
+            self,
+            IntType::I8
+                | IntType::I16
+                | IntType::I32
+                | IntType::I64
+                | IntType::I128
+                | IntType::Isize
+        )
+    }
+
+    /// The size in bytes. We only target 64-bit platforms, so `usize` and `isize` are 8 bytes.
+    pub fn size_in_bytes(self) -> u8 {
+        match self {
+            IntType::U8 | IntType::I8 => 1,
+            IntType::U16 | IntType::I16 => 2,
+            IntType::U32 | IntType::I32 => 4,
+            IntType::U64 | IntType::I64 | IntType::Usize | IntType::Isize => 8,
+            IntType::U128 | IntType::I128 => 16,
+        }
     }
 }
@@ -13,7 +13,7 @@ use derive_generic_visitor::*; //#
     visitor(drive(&VisitAst)),
     visitor(drive_mut(&mut VisitAstMut)),
     skip(
-        (), String, bool, char,
+        (), String, bool, char, NodeId, Span,
         u8, u16, u32, u64, u128, usize,
         i8, i16, i32, i64, i128, isize,
     ),
@@ -28,11 +28,21 @@ use derive_generic_visitor::*; //#
     override(
         BlockExpression,
         BorrowExpression,
+        BreakExpression,
         CallExpression,
+        ContinueExpression,
         DereferenceExpression,
+        Derivation,
+        DiscriminantExpression,
+        Enum,
+        EnumProjectionExpression,
+        EnumVariant,
+        EnumVariantExpression,
         ExternAbi,
         Expression,
         ExpressionKind,
+        FieldExpression,
+        FieldName,
         Function,
         FunctionBody,
         FunctionParam,
@@ -40,21 +50,41 @@ use derive_generic_visitor::*; //#
         FunctionParamType,
         FunctionQualifiers,
         GenericParams,
+        Identifier,
         IfExpression,
         InnerAttribute,
+        IntType,
+        IntegerLiteral,
         Item,
         ItemKind,
         ItemSafety,
         Lifetime,
+        LetExpression,
         LiteralExpression,
+        LoopExpression,
+        LoopKind,
+        MatchArm,
+        MatchExpression,
         Mutability,
         OperatorExpression,
+        Origin,
         OuterAttribute,
         Pattern,
+        PatternKind,
         Program,
         Statement,
+        Struct,
+        StructExprField,
+        StructExpression,
+        StructField,
+        StructFields,
+        StructPatternField,
+        TupleField,
         TupleIndexingExpression,
         Type,
+        TypeKind,
+        VariantPath,
+        VariantPattern,
         Visibility,
         VirtualExpression,
         WhereClauses,
@@ -0,0 +1,107 @@
+//@ # Lazy Boolean Operators
+//@
+//@ The boolean operators `&&` and `||` are lazy, which means that they only evaluate their
+//@ right-hand-side if it is can influence the final value of the operation.
+//@
+//@ ```rust,example
+//@ $lhs || $rhs
+//@ // desugars to:
+//@ if $lhs { true } else { $rhs }
+//@ ```
+//@
+//@ ```rust,example
+//@ $lhs && $rhs
+//@ // desugars to:
+//@ if $lhs { $rhs } else { false }
+//@ ```
+//@
+//@ Inside an `if` expression, the `&&` and [`||`](../features/extended-let-chains.md)
+//@ operators are also allowed to be mixed with `if let` (this is called "`let`-chains").
+//@ We do not touch these at this stage, they will be dealt with in [a later pass](./let-chains.md).
+//@
+//@ After this step, the only `&&` and `||` operators left are involved in `let`-chains.
+//@ In particular, they're all directly inside an `if`.
+//@
+//@ > The rest of this section is a work-in-progress experiment about making the book executable.
+//@
+//@ We only support `&&` in let chains for now, so the `&&`s that have a `let` somewhere among
+//@ their operands are left alone; the boolean conditions inside the chain are desugared as usual.
+use crate::desugarings::*; //#
+use crate::interactive_example; //#
+
+interactive_example! {
+    desugar_lazy_booleans,
+    fn main() {
+        let a: bool = true;
+        let b: bool = false;
+        if a && b || !a {
+            print(a);
+        }
+    }
+}
+pub fn desugar_lazy_booleans(program: &mut Program) -> Result<(), CompilationError> {
+    program.visit_all_mut_infallible(desugar_lazy_boolean);
+    Ok(())
+}
+
+fn desugar_lazy_boolean(expression: &mut Expression) {
+    if is_let_chain(expression) {
+        return;
+    }
+    let ExpressionKind::Operator(operator) = &mut expression.kind else {
+        return;
+    };
+    let origin = &expression.origin;
+    let (condition, then_value, else_value) = match &mut **operator {
+        OperatorExpression::LazyAnd(left, right) => {
+            (take(left), take(right), bool_literal(false, origin))
+        }
+        OperatorExpression::LazyOr(left, right) => {
+            (take(left), bool_literal(true, origin), take(right))
+        }
+        _ => return,
+    };
+    expression.kind = ExpressionKind::If(IfExpression {
+        condition: Box::new(condition),
+        then_branch: Box::new(branch(then_value, &expression.origin)),
+        else_branch: Some(Box::new(branch(else_value, &expression.origin))),
+    });
+    expression.origin = expression.origin.derive();
+}
+
+/// Whether this is a `let` condition or a `&&` chain that contains one.
+pub(crate) fn is_let_chain(expression: &Expression) -> bool {
+    match &expression.kind {
+        ExpressionKind::Let(_) => true,
+        ExpressionKind::Operator(operator) => match &**operator {
+            OperatorExpression::LazyAnd(left, right) => is_let_chain(left) || is_let_chain(right),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn take(expression: &mut Expression) -> Expression {
+    std::mem::replace(expression, Expression::new(ExpressionKind::Tuple(vec![])))
+}
+
+/// A literal made from the node with origin `source`.
+fn bool_literal(value: bool, source: &Origin) -> Expression {
+    Expression {
+        origin: source.derive(),
+        ..Expression::new(ExpressionKind::Literal(LiteralExpression::Bool(value)))
+    }
+}
+
+/// `{ $value }`, or just `$value` if it's already a block. The block is made from the node with
+/// origin `source`.
+fn branch(value: Expression, source: &Origin) -> Expression {
+    match &value.kind {
+        ExpressionKind::Block(block) if value.attrs.is_empty() && block.label.is_none() => value,
+        _ => Expression {
+            span: value.span,
+            origin: source.derive(),
+            ..BlockExpression::derived_from(source, vec![], Some(value)).into()
+        },
+    }
+}
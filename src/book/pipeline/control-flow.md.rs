@@ -0,0 +1,13 @@
+//@ # Control-flow Desugarings
+//@
+//@ At the end of this series of steps, the remaining control-flow operators are `loop`, `if`, all
+//@ pattern matching constructs like `match` and `let .. else`, and
+//@ [`on_unwind`](../features/on-unwind.md).
+//@
+//@ > The rest of this section is a work-in-progress experiment about making the book executable.
+//@
+//@ ## Submodules
+#[path = "boolean-operators.md.rs"]
+pub mod boolean_operators;
+#[path = "loop-desugaring.md.rs"]
+pub mod loop_desugaring;
@@ -0,0 +1,93 @@
+//@ # Desugaring Bindings
+//@
+//@ All the `let` expressions left are now bindings.
+//@ We desugar them all into binding declarations and assignments:
+//@
+//@ - By-value bindings:
+//@
+//@     ```rust,example
+//@     let x = $place;
+//@
+//@     // becomes
+//@     let x: $ty;
+//@     x = &$place;
+//@     ```
+//@
+//@ - By-ref bindings:
+//@
+//@     ```rust,example
+//@     let ref x = $place;
+//@
+//@     // becomes
+//@     let x: $ty;
+//@     x = &$place;
+//@     ```
+//@
+//@ - By-ref-mut bindings:
+//@
+//@     ```rust,example
+//@     let ref mut x = $place;
+//@
+//@     // becomes
+//@     let x: $ty;
+//@     x = &mut $place;
+//@     ```
+//@
+//@ - Place aliases:
+//@
+//@     For place aliases, the RHS is already a side-effect-free place expression.
+//@     We can therefore simply substitute `$place` for `p` syntactically.
+//@     For example:
+//@     ```rust,example
+//@     let place p = x.field;
+//@     something(&p);
+//@     something_else(p);
+//@
+//@     // becomes:
+//@     something(&x.field);
+//@     something_else(x.field);
+//@     ```
+//@
+//@ At the end of this step, all the bindings are declared uninitialized (`let x;`/`let mut x;`).
+//@
+//@ ## Explicit binding types
+//@
+//@ > The rest of this section is a work-in-progress experiment about making the book executable.
+//@
+//@ None of the above is implemented yet. What we do implement is writing the type of every binding
+//@ explicitly, as computed by [Type Checking](type-checking.md.rs):
+//@ ```rust,example
+//@ let x = &true;
+//@ // becomes
+//@ let x: &bool = &true;
+//@ ```
+//@
+//@ We run this step right after type checking, so that the later steps can count on every `let`
+//@ statement having a type. The steps that introduce new `let` statements give them their type
+//@ themselves.
+use crate::desugarings::*; //#
+use crate::interactive_example; //#
+
+interactive_example! {
+    explicit_binding_types,
+    fn main() {
+        let x = &true;
+        let (a, b) = (1, false);
+        let c;
+        c = a + 2;
+    }
+}
+pub fn explicit_binding_types(program: &mut Program) -> Result<(), CompilationError> {
+    let types = type_checking::node_types(program)?;
+    program.visit_all_mut_infallible(|statement: &mut Statement| {
+        if let Statement::Let { ty, origin, .. } = statement
+            && ty.is_none()
+            && let Some(inferred) = types.get(&origin.id)
+        {
+            let mut inferred = Some(inferred.clone());
+            inferred.visit_all_mut_infallible(|ty: &mut Type| ty.origin = origin.derive());
+            *ty = inferred;
+        }
+    });
+    Ok(())
+}
@@ -5,17 +5,20 @@
 use crate::CompilationError; //#
 use crate::interactive_example; //#
 use crate::language::*; //#
-enum ExprCategory {
+pub(crate) enum ExprCategory {
     Value,
     Place,
 }
 
 impl Expression {
-    fn categorize(&self) -> ExprCategory {
+    pub(crate) fn categorize(&self) -> ExprCategory {
         match &self.kind {
             //@ These are all the place expressions [ref:expr.place-value.place-expr-kinds]:
             ExpressionKind::Path(_) => ExprCategory::Place,
             ExpressionKind::TupleIndexing(..) => ExprCategory::Place,
+            ExpressionKind::Field(..) => ExprCategory::Place,
+            ExpressionKind::EnumProjection(..) => ExprCategory::Place,
+            ExpressionKind::Discriminant(..) => ExprCategory::Place,
             ExpressionKind::Operator(OperatorExpression::Dereference(_)) => ExprCategory::Place,
             ExpressionKind::Virtual(VirtualExpression::ValueToPlaceCoercion(_)) => {
                 ExprCategory::Place
@@ -25,17 +28,31 @@ impl Expression {
             //@ Anything else is a value expression [ref:expr.place-value.value-expr-kinds]:
             ExpressionKind::Operator(
                 OperatorExpression::Borrow(..)
-                | OperatorExpression::Add(..)
-                | OperatorExpression::Assignment(..),
+                | OperatorExpression::Negation(..)
+                | OperatorExpression::Binary(..)
+                | OperatorExpression::Comparison(..)
+                | OperatorExpression::Assignment(..)
+                | OperatorExpression::CompoundAssignment(..)
+                | OperatorExpression::LazyAnd(..)
+                | OperatorExpression::LazyOr(..),
             )
             | ExpressionKind::Literal(..)
             | ExpressionKind::Block(..)
             | ExpressionKind::If(..)
+            | ExpressionKind::Let(..)
+            | ExpressionKind::Loop(..)
+            | ExpressionKind::Match(..)
+            | ExpressionKind::Break(..)
+            | ExpressionKind::Continue(..)
             | ExpressionKind::Tuple(..)
+            | ExpressionKind::Struct(..)
+            | ExpressionKind::VariantPath(..)
+            | ExpressionKind::EnumVariant(..)
+            | ExpressionKind::DiscriminantOf(..)
             | ExpressionKind::Call(..)
-            | ExpressionKind::Virtual(VirtualExpression::PlaceToValueCoercion(_)) => {
-                ExprCategory::Value
-            }
+            | ExpressionKind::Virtual(
+                VirtualExpression::PlaceToValueCoercion(_) | VirtualExpression::Unreachable,
+            ) => ExprCategory::Value,
         }
     }
 }
@@ -58,20 +75,30 @@ interactive_example! {
 pub fn make_place_coercions_explicit(program: &mut Program) -> Result<(), CompilationError> {
     // Add place/value coercions to all the subexpressions of each expression.
     program.visit_all_mut_infallible(|expression: &mut Expression| match &mut expression.kind {
-        ExpressionKind::Literal(_) | ExpressionKind::Path(_) => {}
+        ExpressionKind::Literal(_)
+        | ExpressionKind::Path(_)
+        | ExpressionKind::VariantPath(_)
+        | ExpressionKind::DiscriminantOf(_) => {}
         ExpressionKind::Operator(operator) => match &mut **operator {
             OperatorExpression::Borrow(borrow) => expect_place(&mut borrow.expression),
             OperatorExpression::Dereference(dereference) => {
                 expect_place(&mut dereference.expression)
             }
-            OperatorExpression::Add(left, right) => {
+            OperatorExpression::Negation(_, operand) => expect_value(operand),
+            OperatorExpression::Binary(_, left, right)
+            | OperatorExpression::Comparison(_, left, right) => {
                 expect_value(left);
                 expect_value(right);
             }
-            OperatorExpression::Assignment(left, right) => {
+            OperatorExpression::Assignment(left, right)
+            | OperatorExpression::CompoundAssignment(_, left, right) => {
                 expect_place(left);
                 expect_value(right);
             }
+            OperatorExpression::LazyAnd(left, right) | OperatorExpression::LazyOr(left, right) => {
+                expect_value(left);
+                expect_value(right);
+            }
         },
         ExpressionKind::Grouped(_) => {}
         ExpressionKind::Block(block) => {
@@ -86,6 +113,30 @@ pub fn make_place_coercions_explicit(program: &mut Program) -> Result<(), Compil
                 expect_value(else_branch);
             }
         }
+        // Like a `let` statement, see below.
+        ExpressionKind::Let(let_expression) => match &let_expression.pattern.kind {
+            PatternKind::Identifier(_) => expect_value(&mut let_expression.scrutinee),
+            _ => expect_place(&mut let_expression.scrutinee),
+        },
+        ExpressionKind::Match(match_expression) => {
+            expect_place(&mut match_expression.scrutinee);
+            for arm in &mut match_expression.arms {
+                if let Some(guard) = &mut arm.guard {
+                    expect_value(guard);
+                }
+                expect_value(&mut arm.body);
+            }
+        }
+        ExpressionKind::Loop(loop_expression) => match &mut loop_expression.kind {
+            LoopKind::Infinite(_) => {}
+            LoopKind::While { condition, .. } => expect_value(condition),
+        },
+        ExpressionKind::Break(break_expression) => {
+            if let Some(value) = &mut break_expression.value {
+                expect_value(value);
+            }
+        }
+        ExpressionKind::Continue(_) => {}
         ExpressionKind::Tuple(elements) => {
             for element in elements {
                 expect_value(element);
@@ -94,6 +145,19 @@ pub fn make_place_coercions_explicit(program: &mut Program) -> Result<(), Compil
         ExpressionKind::TupleIndexing(tuple_indexing) => {
             expect_place(&mut tuple_indexing.expression);
         }
+        ExpressionKind::Struct(adt) => {
+            for field in &mut adt.fields {
+                expect_value(&mut field.value);
+            }
+        }
+        ExpressionKind::EnumVariant(variant) => {
+            for field in &mut variant.fields {
+                expect_value(&mut field.value);
+            }
+        }
+        ExpressionKind::Field(field) => expect_place(&mut field.expression),
+        ExpressionKind::EnumProjection(projection) => expect_place(&mut projection.expression),
+        ExpressionKind::Discriminant(discriminant) => expect_place(&mut discriminant.expression),
         ExpressionKind::Call(call) => {
             for argument in &mut call.args {
                 expect_value(argument);
@@ -102,6 +166,7 @@ pub fn make_place_coercions_explicit(program: &mut Program) -> Result<(), Compil
         ExpressionKind::Virtual(virtual_expression) => match virtual_expression {
             VirtualExpression::ValueToPlaceCoercion(expression) => expect_value(expression),
             VirtualExpression::PlaceToValueCoercion(expression) => expect_place(expression),
+            VirtualExpression::Unreachable => {}
         },
     });
 
@@ -113,8 +178,8 @@ pub fn make_place_coercions_explicit(program: &mut Program) -> Result<(), Compil
             initial_value: Some(expr),
             pattern,
             ..
-        } => match pattern {
-            Pattern::Identifier(_) => expect_value(expr),
+        } => match &pattern.kind {
+            PatternKind::Identifier(_) => expect_value(expr),
             _ => expect_place(expr),
         },
         Statement::Let { .. } => {}
@@ -131,14 +196,20 @@ fn expect(expression: &mut Expression, cat: ExprCategory) {
         (ExprCategory::Value, ExprCategory::Value) | (ExprCategory::Place, ExprCategory::Place) => {
         }
         (ExprCategory::Place, ExprCategory::Value) => {
-            *expression = Expression::new(ExpressionKind::Virtual(
-                VirtualExpression::PlaceToValueCoercion(Box::new(expression.clone())),
-            ));
+            *expression = Expression::derived_from(
+                expression,
+                ExpressionKind::Virtual(VirtualExpression::PlaceToValueCoercion(Box::new(
+                    expression.clone(),
+                ))),
+            );
         }
         (ExprCategory::Value, ExprCategory::Place) => {
-            *expression = Expression::new(ExpressionKind::Virtual(
-                VirtualExpression::ValueToPlaceCoercion(Box::new(expression.clone())),
-            ));
+            *expression = Expression::derived_from(
+                expression,
+                ExpressionKind::Virtual(VirtualExpression::ValueToPlaceCoercion(Box::new(
+                    expression.clone(),
+                ))),
+            );
         }
     }
 }
@@ -6,7 +6,7 @@
 //@ At the end of this series of steps, every [place
 //@ context](https://nadrieril.github.io/blog/2025/12/06/on-places-and-their-magic.html) apart from
 //@ simple `let`-bindings contains a side-effect-free place expression and every value context contains
-//@ an operand, as defined in the [Final Language](final-language.md) section.
+//@ an operand, as defined in the [Final Language](final-language.md.rs) section.
 //@
 //@ > The rest of this section is a work-in-progress experiment about making the book executable.
 use crate::desugarings::*; //#
@@ -19,5 +19,7 @@ pub fn desugar_nested_exprs(program: &mut Program) -> Result<(), CompilationErro
 //@ ## Submodules
 #[path = "explicit-value-place.md.rs"]
 pub mod explicit_value_place;
+#[path = "subexpr-elim.md.rs"]
+pub mod subexpr_elim;
 #[path = "value-to-place.md.rs"]
 pub mod value_to_place;
@@ -28,23 +28,46 @@ fn split_let_initializers(block: &mut BlockExpression) {
         if let Statement::Let {
             attrs,
             scope,
-            pattern: Pattern::Identifier(name),
+            pattern:
+                Pattern {
+                    kind: PatternKind::Identifier(name),
+                    origin: pattern_origin,
+                },
             ty,
             initial_value: Some(value),
             else_branch: None,
+            span,
+            origin,
         } = statement
         {
             block.statements.push(Statement::Let {
                 attrs,
                 scope,
-                pattern: Pattern::Identifier(name.clone()),
+                pattern: Pattern {
+                    kind: PatternKind::Identifier(name.clone()),
+                    origin: pattern_origin,
+                },
                 ty,
                 initial_value: None,
                 else_branch: None,
+                span,
+                origin: origin.clone(),
             });
-            let assignment = Expression::new(ExpressionKind::Operator(Box::new(
-                OperatorExpression::Assignment(Expression::new(ExpressionKind::Path(name)), value),
-            )));
+            // Errors about the assignment point at the `let` it comes from.
+            let assignment = Expression {
+                span,
+                origin: origin.derive(),
+                ..Expression::new(ExpressionKind::Operator(Box::new(
+                    OperatorExpression::Assignment(
+                        Expression {
+                            span,
+                            origin: origin.derive(),
+                            ..Expression::new(ExpressionKind::Path(name))
+                        },
+                        value,
+                    ),
+                )))
+            };
             block.statements.push(Statement::Expr(assignment));
         } else {
             block.statements.push(statement)
@@ -0,0 +1,337 @@
+//@ # The Final Language
+//@
+//@ We have now fully desugared our Rust program. The resulting program uses a very limited subset of
+//@ Rust, described here.
+//@
+//@ An "constant value" `$const` is:
+//@ - A constant literal `true`, `42u32`, `3.14f64`, `"str literal"`, etc;
+//@ - A named constant `CONSTANT`;
+//@ - A function item ZST [ref:type.fn-item].
+//@
+//@ A "place expression" `$place` is:
+//@ - A local variable `x`;
+//@ - A named static or thread local `STATIC`;
+//@ - A dereference `*$place`;
+//@ - A field access `$place.$field_name`;
+//@ - An enum field access `$place.$variant_name.$field_name`;
+//@ - A discriminant access `$place.enum#discriminant` (see [Enum Discriminant
+//@   Access](../features/enum-discriminant.md));
+//@ - Unchecked indexing `unchecked_index!($place, $operand)`, `unchecked_index!($place,
+//@   $operand..=$operand)` (see [Unchecked Indexing](../features/unchecked-indexing.md)).
+//@
+//@ An "operand" `$operand` is:
+//@ - A place access `copy!($place)` or `move!($place)` (see [Explicit Copy/Move](../features/explicit-copy-move.md));
+//@ - A constant `$const`.
+//@
+//@ An "value expression" `$val_expr` is:
+//@ - An operand `$operand`;
+//@ - A borrow `&$place`/`&mut $place`/`&raw const $place`/`&raw mut $place`/[`&uniq $place`](../features/uniq-borrow.md);
+//@ - A cast `$operand as $ty`;
+//@ - A built-in operation `$operand + $operand`, `$operand >= $operand`, `!$operand`, etc;
+//@ - A repeat expression `[$operand; $const]`.
+//@ <!-- - A coroutine yield `yield $operand`; -->
+//@ <!-- - An async block `async { $block }`. -->
+//@
+//@ A "statement" `$statement` is:
+//@ - A variable declaration `let x: $ty;`/`let mut x: $ty;`.
+//@ - Assignment `$place = $val_expr`;
+//@   <!-- - Place mention `let _ = $place;` (needs to be kept for accurate borrow-checking); -->
+//@ - Function call `$place = $operand($operand..)`;
+//@ - If expression `if $operand { $block } else { $block }`;
+//@ - Loop expression `'a: loop { $block }`;
+//@ - Unwind cleanup expression `on_unwind { $block } { $block }` (see [Cleanup On Unwinding](../features/on-unwind.md));
+//@ - Named block `'a: { $block };`;
+//@ - Jumps `break 'a`/`continue 'a`;
+//@ - Return `return $operand`;
+//@ - [`scope_end!($local)`](../features/scope-end.md)
+//@ - [`drop_in_place!($place)`](../features/in-place-drop.md)
+//@ - `inline_asm!(..)`.
+//@
+//@ A "block" `$block` is a list of `;`-terminated statements. It is always of type `()`.
+//@
+//@ A fully desugared function body is a block.
+//@
+//@ ## Checking the final language
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+//@ We check that what our pipeline produces is in this language, apart from two constructs that
+//@ need steps we don't implement yet: blocks may have tail expressions ([Removing Tail
+//@ Expressions](remove-tail-exprs.md)), and `if` conditions may be `let`-chains ([Let
+//@ Chains](let-chains.md)) whose `let`s bind a variable. We also don't check for the constructs
+//@ that our subset doesn't have. Every other context must hold the right kind of expression: a
+//@ place, an operand, or a value expression whose subexpressions are operands ([Intermediate
+//@ Subexpression Elimination](subexpr-elim.md.rs)). We run this after the last step in debug
+//@ builds, to catch desugaring bugs.
+use crate::desugarings::control_flow::boolean_operators::is_let_chain; //#
+use crate::desugarings::expr_unnesting::explicit_value_place::ExprCategory; //#
+use crate::desugarings::expr_unnesting::subexpr_elim::is_operand; //#
+use crate::desugarings::*; //#
+use std::fmt::Display; //#
+
+pub fn check_final_language(program: &Program) -> Result<(), CompilationError> {
+    program.visit_all(check_statement)?;
+    program.visit_all(check_block)?;
+    program.visit_all(check_expression)
+}
+
+/// The error for a node that has no business in the final language. This is a bug in one of the
+/// desugarings.
+fn not_final(span: Span, what: &str, node: impl Display) -> CompilationError {
+    CompilationError::Internal(
+        Diagnostic::new(format!(
+            "{what} is not allowed in the final language: `{node}`"
+        ))
+        .with_span(span),
+    )
+}
+
+//@ Every `let` is a declaration with an explicit type. [Pattern Unnesting](pattern-unnesting.md.rs)
+//@ turns destructuring `let`s into one `let` per binding, and the [Final
+//@ Desugarings](final-desugarings.md.rs) split those into a declaration and an assignment.
+fn check_statement(statement: &Statement) -> Result<(), CompilationError> {
+    let span = statement.span();
+    match statement {
+        Statement::Let { ty: None, .. } => {
+            Err(not_final(span, "a `let` without a type", statement))
+        }
+        Statement::Let {
+            else_branch: Some(_),
+            ..
+        } => Err(not_final(span, "a `let`-`else`", statement)),
+        Statement::Let {
+            initial_value: Some(_),
+            ..
+        } => Err(not_final(span, "a `let` with an initializer", statement)),
+        Statement::Let { .. } | Statement::Empty | Statement::Item(_) => Ok(()),
+        Statement::Expr(expression) => check_context(expression, Context::Any),
+    }
+}
+
+fn check_block(block: &BlockExpression) -> Result<(), CompilationError> {
+    match &block.tail {
+        Some(tail) => check_context(tail, Context::Value),
+        None => Ok(()),
+    }
+}
+
+//@ Parentheses, `match`, `while`, `||`, value-to-place coercions and `if` without `else` are all
+//@ desugared away, as is `&&` outside of `let`-chains.
+fn check_expression(expression: &Expression) -> Result<(), CompilationError> {
+    let leftover = |what: &str| Err(not_final(expression.span, what, expression));
+    match &expression.kind {
+        ExpressionKind::Grouped(_) => leftover("a parenthesized expression"),
+        ExpressionKind::Match(_) => leftover("a `match`"),
+        ExpressionKind::Loop(loop_expression) => match &loop_expression.kind {
+            LoopKind::Infinite(_) => Ok(()),
+            LoopKind::While { .. } => leftover("a `while` loop"),
+        },
+        ExpressionKind::If(if_expression) => {
+            let Some(else_branch) = &if_expression.else_branch else {
+                return leftover("an `if` without `else`");
+            };
+            check_context(&if_expression.condition, Context::Condition)?;
+            check_context(&if_expression.then_branch, Context::Value)?;
+            check_context(else_branch, Context::Value)
+        }
+        ExpressionKind::Let(let_expression) => match let_expression.pattern.kind {
+            PatternKind::Identifier(_) => check_context(&let_expression.scrutinee, Context::Any),
+            _ => check_context(&let_expression.scrutinee, Context::Place),
+        },
+        ExpressionKind::Operator(operator) => match &**operator {
+            OperatorExpression::Borrow(borrow) => check_context(&borrow.expression, Context::Place),
+            OperatorExpression::Dereference(dereference) => {
+                check_context(&dereference.expression, Context::Place)
+            }
+            OperatorExpression::Negation(_, operand) => check_context(operand, Context::Operand),
+            OperatorExpression::Binary(_, left, right)
+            | OperatorExpression::Comparison(_, left, right) => {
+                check_context(left, Context::Operand)?;
+                check_context(right, Context::Operand)
+            }
+            OperatorExpression::Assignment(left, right) => {
+                check_context(left, Context::Place)?;
+                check_context(right, Context::Value)
+            }
+            OperatorExpression::CompoundAssignment(_, left, right) => {
+                check_context(left, Context::Place)?;
+                check_context(right, Context::Operand)
+            }
+            OperatorExpression::LazyAnd(left, right) => {
+                if !is_let_chain(expression) {
+                    return leftover("a `&&` outside of a `let`-chain");
+                }
+                check_context(left, Context::Condition)?;
+                check_context(right, Context::Condition)
+            }
+            OperatorExpression::LazyOr(..) => leftover("a `||`"),
+        },
+        ExpressionKind::Break(break_expression) => match &break_expression.value {
+            Some(value) => check_context(value, Context::Value),
+            None => Ok(()),
+        },
+        ExpressionKind::Tuple(elements) => elements
+            .iter()
+            .try_for_each(|element| check_context(element, Context::Operand)),
+        ExpressionKind::Struct(adt) => adt
+            .fields
+            .iter()
+            .try_for_each(|field| check_context(&field.value, Context::Operand)),
+        ExpressionKind::EnumVariant(variant) => variant
+            .fields
+            .iter()
+            .try_for_each(|field| check_context(&field.value, Context::Operand)),
+        ExpressionKind::Call(call) => call
+            .args
+            .iter()
+            .try_for_each(|argument| check_context(argument, Context::Operand)),
+        ExpressionKind::TupleIndexing(tuple_indexing) => {
+            check_context(&tuple_indexing.expression, Context::Place)
+        }
+        ExpressionKind::Field(field) => check_context(&field.expression, Context::Place),
+        ExpressionKind::EnumProjection(projection) => {
+            check_context(&projection.expression, Context::Place)
+        }
+        ExpressionKind::Discriminant(discriminant) => {
+            check_context(&discriminant.expression, Context::Place)
+        }
+        ExpressionKind::Virtual(virtual_expression) => match virtual_expression {
+            VirtualExpression::ValueToPlaceCoercion(_) => leftover("a value-to-place coercion"),
+            VirtualExpression::PlaceToValueCoercion(place) => check_context(place, Context::Place),
+            VirtualExpression::Unreachable => Ok(()),
+        },
+        // Block tails are checked with the blocks, including loop bodies.
+        ExpressionKind::Literal(_)
+        | ExpressionKind::Path(_)
+        | ExpressionKind::Block(_)
+        | ExpressionKind::Continue(_)
+        | ExpressionKind::VariantPath(_)
+        | ExpressionKind::DiscriminantOf(_) => Ok(()),
+    }
+}
+
+/// Where a subexpression appears, which determines what kind of expression it may be.
+#[derive(Clone, Copy)]
+enum Context {
+    /// A place context, which holds a place expression.
+    Place,
+    /// A value context, which holds a value expression. Places are read with an explicit
+    /// place-to-value coercion.
+    Value,
+    /// An operand of a built-in operation, an argument of a call or a field of a tuple, struct or
+    /// enum variant expression, which holds an operand: a place read or a constant.
+    Operand,
+    /// The condition of an `if` or an operand of a `&&` in a `let`-chain, which holds an operand
+    /// or a `let`-chain.
+    Condition,
+    /// An expression statement, or the scrutinee of a `let` that binds a variable, which may be
+    /// either a place or a value.
+    Any,
+}
+
+//@ `let` conditions only appear in `if` conditions, and the kind of expression in each context
+//@ matches the kind the context expects, as established in [Place-to-Value and Value-to-Place
+//@ Coercions](explicit-value-place.md.rs). Operand positions don't hold nested value expressions.
+fn check_context(expression: &Expression, context: Context) -> Result<(), CompilationError> {
+    let span = expression.span;
+    if is_let_chain(expression) {
+        return match context {
+            Context::Condition => Ok(()),
+            _ => Err(not_final(
+                span,
+                "a `let` outside of an `if` condition",
+                expression,
+            )),
+        };
+    }
+    match (context, expression.categorize()) {
+        (Context::Place, ExprCategory::Value) => Err(not_final(
+            span,
+            "a value expression in a place context",
+            expression,
+        )),
+        (Context::Value | Context::Operand | Context::Condition, ExprCategory::Place) => Err(
+            not_final(span, "a place expression in a value context", expression),
+        ),
+        (Context::Operand | Context::Condition, ExprCategory::Value) if !is_operand(expression) => {
+            Err(not_final(
+                span,
+                "a nested value expression in an operand position",
+                expression,
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+//@
+//@ ## Difference with MIR
+//@
+//@ This target language is intentionally very close to
+//@ [MIR](https://rustc-dev-guide.rust-lang.org/mir/index.html)[^2]. The main differences are:
+//@ - Our language has structured control-flow whereas MIR has a graph of blocks with `goto`s;
+//@ - MIR has `StorageLive`/`StorageDead` statements to track allocation/deallocation of locals; we
+//@   instead have `let x;` to allocate and `scope_end!(x)` (see [Explicit End Of
+//@   Scope](../features/scope-end.md)) that marks where the local is deinitialized. This may or may not
+//@   be exactly equivalent;
+//@ - Instead of `return value;`, MIR has a return place that must be written to before returning.
+//@
+//@ There a probably a ton of subtle differences I haven't noticed,
+//@ but so far going from this to MIR looks pretty straightforward.
+//@
+//@ ## Difference with MiniRust
+//@
+//@ MiniRust is intentionally quite close to MIR[^3]. Beyond the differences with MIR we already saw,
+//@ from what I know to get valid MiniRust we'd also need at least the following:
+//@ - Corountine transform, which transforms `async` blocks into state machines; I didn't know where to
+//@   fit that in the desugarings and generally skipped anything related to `async`;
+//@ - Change a bunch of intrinsic calls like `ptr::read`, `u32::add_with_overflow` to built-in
+//@   operations.
+//@
+//@ There's likely more, I haven't investigated in detail yet.
+//@
+//@ ---
+//@
+//@ ## Discussion
+//@
+//@ I feel like this accomplished the goals I set out in the introduction pretty well.
+//@ I noted decisions made and caveats throughout the book.
+//@
+//@ The most important caveat to note is the question of borrow-checking.
+//@ In [the relevant section](borrow-checking.md), I highlight how borrow-checking after our desugarings
+//@ would allow unsound code to compile.
+//@ We therefore need to either borrow-check somewhere in the middle or desugar differently.
+//@ I am leaving this question open for now.
+//@
+//@ I am also left unsatisfied with [the desugaring of `||`-chains](let-chains.md).
+//@ It seems we have two bad choices: duplicate user code (and risk exponential blowup),
+//@ or emit nasty-looking code.
+//@ There may be a clever language feature that can alleviate this conundrum.
+//@
+//@ ## Conclusion
+//@
+//@ I am pretty pleased with the shape this took.
+//@ I am now convinced that this way of presenting things is fruitful.
+//@ In an ideal universe this book would be combined with MiniRust and a-mir-formality to make
+//@ a reference interpreter written in literate programming style;
+//@ if I had to choose I would quite like that as an official spec for Rust.
+//@
+//@ By far the trickiest part of all this was the interaction between temporaries and patterns[^4]. I'd
+//@ like to thank @dianne for helping me get temporary lifetimes right and figure out a pass ordering
+//@ that doesn't loop onto itself all over the place.
+//@
+//@ I don't know what will become of this book now.
+//@ It would be a shame for it not to be kept up-to-date as the language evolves.
+//@ Only time will tell if this will be deemed a worthy investment.
+//@
+//@ Thanks for reading, and please come chat[^1] if you've read this far!
+//@ If you find mistakes or missing details I welcome
+//@ [issues](https://github.com/Nadrieril/rust-via-desugarings/issues) and PRs.
+//@
+//@ [^1]: Check my [GitHub profile](https://github.com/Nadrieril) for contact info.
+//@ [^2]: MIR is actually a bunch of languages in a trenchcoat. The MIR I'm talking about here is a MIR
+//@ post-drop elaboration but pre-coroutine transform.
+//@ [^3]: In this case, a different MIR than I was talking about. MiniRust is closer to [runtime
+//@ MIR](https://doc.rust-lang.org/nightly/nightly-rustc/rustc_middle/mir/enum.MirPhase.html#variant.Runtime).
+//@ [^4]: Desugaring or-patterns required temporaries to be handled, but these seemed to require let
+//@ chains to be desugared, but we can't desugar let chains in if let guards before desugaring match
+//@ guards in some form, etc etc. It was all a big fun mutually-dependent knot.
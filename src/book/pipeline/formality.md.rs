@@ -9,9 +9,9 @@
 //@ the end. In particular, the desugarings should make the translation as direct as possible.
 //@ While we're experimenting, this translation may take liberties with that principle, for the
 //@ sake of being able to run more examples.
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
-use crate::{CompilationError, language};
+use crate::{CompilationError, Diagnostic, language};
 use formality_rust::{
     check,
     grammar::{
@@ -23,12 +23,31 @@ use formality_rust::{
 };
 
 pub fn translate_to_formality(program: &language::Program) -> Result<Crates, CompilationError> {
-    FormalityTranslator.translate_program(program)
+    FormalityTranslator { only_body: None }.translate_program(program)
 }
 
+//@ a-mir-formality doesn't tell which function failed to check. To point at it, we check the
+//@ functions again one at a time, leaving out the bodies of the others.
 pub fn check_with_formality(program: &language::Program) -> Result<(), CompilationError> {
     let crates = translate_to_formality(program)?;
-    let _checked = check::check_all_crates(crates.clone())
+    check_crates(crates).map_err(|error| {
+        let culprit = program.items.iter().enumerate().find(|(index, item)| {
+            matches!(item.kind, language::ItemKind::Function(_))
+                && FormalityTranslator {
+                    only_body: Some(*index),
+                }
+                .translate_program(program)
+                .is_ok_and(|crates| check_crates(crates).is_err())
+        });
+        match culprit {
+            Some((_, item)) => error.with_span(item.span),
+            None => error,
+        }
+    })
+}
+
+fn check_crates(crates: Crates) -> Result<(), CompilationError> {
+    let _checked = check::check_all_crates(crates)
         .into_singleton()
         .map_err(|error| {
             formality_error(format!(
@@ -39,16 +58,31 @@ pub fn check_with_formality(program: &language::Program) -> Result<(), Compilati
     Ok(())
 }
 
-struct FormalityTranslator;
+struct FormalityTranslator {
+    /// If set, only the function at this index of the program gets a body; the others are
+    /// translated as mere declarations.
+    only_body: Option<usize>,
+}
 
 impl FormalityTranslator {
     fn translate_program(&self, program: &language::Program) -> Result<Crates, CompilationError> {
         let items = program
             .items
             .iter()
-            .map(|item| {
-                let language::ItemKind::Function(function) = &item.kind;
-                self.translate_function(function).map(CrateItem::Fn)
+            .enumerate()
+            .map(|(index, item)| {
+                match &item.kind {
+                    language::ItemKind::Function(function) => {
+                        let with_body = self.only_body.is_none_or(|only| only == index);
+                        self.translate_function(function, with_body)
+                            .map(CrateItem::Fn)
+                    }
+                    language::ItemKind::Struct(adt) => {
+                        self.translate_struct(adt).map(CrateItem::Struct)
+                    }
+                    language::ItemKind::Enum(adt) => self.translate_enum(adt).map(CrateItem::Enum),
+                }
+                .map_err(|error| error.with_span(item.span))
             })
             .collect::<Result<Vec<_>, _>>()?;
         Ok(Crates {
@@ -62,6 +96,7 @@ impl FormalityTranslator {
     fn translate_function(
         &self,
         function: &language::Function,
+        with_body: bool,
     ) -> Result<RustFn, CompilationError> {
         let input_args = function
             .parameters
@@ -73,13 +108,15 @@ impl FormalityTranslator {
             .as_ref()
             .map_or(Ok(Ty::unit()), translate_type)?;
         let body = match &function.body {
-            language::FunctionBody::Block(block) => MaybeFnBody::FnBody(FnBody::Expr(
-                FunctionTranslator::new().translate_body(block)?,
+            language::FunctionBody::Block(block) if with_body => MaybeFnBody::FnBody(FnBody::Expr(
+                FunctionTranslator::new(&function.parameters).translate_body(block)?,
             )),
-            language::FunctionBody::Missing => MaybeFnBody::NoFnBody,
+            language::FunctionBody::Block(_) | language::FunctionBody::Missing => {
+                MaybeFnBody::NoFnBody
+            }
         };
         Ok(RustFn {
-            id: ValueId::new(&function.name),
+            id: ValueId::new(&function.name.to_string()),
             safety: translate_item_safety(function.qualifiers.safety.as_ref()),
             binder: rust::Binder::dummy(FnBoundData {
                 input_args,
@@ -90,6 +127,36 @@ impl FormalityTranslator {
         })
     }
 
+    fn translate_struct(&self, adt: &language::Struct) -> Result<rust::Struct, CompilationError> {
+        Ok(rust::Struct {
+            id: rust::AdtId::new(&adt.name.to_string()),
+            binder: rust::Binder::dummy(rust::StructBoundData {
+                where_clauses: vec![],
+                fields: translate_fields(&adt.fields)?,
+            }),
+        })
+    }
+
+    fn translate_enum(&self, adt: &language::Enum) -> Result<rust::Enum, CompilationError> {
+        let variants = adt
+            .variants
+            .iter()
+            .map(|variant| {
+                Ok(rust::Variant {
+                    name: rust::VariantId::new(&variant.name.to_string()),
+                    fields: translate_fields(&variant.fields)?,
+                })
+            })
+            .collect::<Result<Vec<_>, CompilationError>>()?;
+        Ok(rust::Enum {
+            id: rust::AdtId::new(&adt.name.to_string()),
+            binder: rust::Binder::dummy(rust::EnumBoundData {
+                where_clauses: vec![],
+                variants,
+            }),
+        })
+    }
+
     fn translate_parameter(
         parameter: &language::FunctionParam,
     ) -> Result<InputArg, CompilationError> {
@@ -103,7 +170,7 @@ impl FormalityTranslator {
             )));
         };
         Ok(InputArg {
-            id: ValueId::new(pattern_name(pattern)?),
+            id: ValueId::new(&pattern_name(pattern)?.to_string()),
             ty: translate_type(ty)?,
         })
     }
@@ -112,11 +179,33 @@ impl FormalityTranslator {
 #[derive(Default)]
 struct FunctionTranslator {
     lifetimes: Vec<rust::BoundVar>,
+    /// The locals in scope that have an integer type, used to give unsuffixed integer literals the
+    /// type their context expects.
+    int_locals: HashMap<language::Identifier, language::IntType>,
+    next_temporary: usize,
 }
 
 impl FunctionTranslator {
-    fn new() -> Self {
-        Self::default()
+    fn new(parameters: &[language::FunctionParam]) -> Self {
+        let mut translator = Self::default();
+        for parameter in parameters {
+            if let language::FunctionParamKind::Regular {
+                pattern: Some(pattern),
+                ty: language::FunctionParamType::Type(ty),
+            } = &parameter.kind
+                && let Ok(name) = pattern_name(pattern)
+            {
+                translator.declare_local(name, ty);
+            }
+        }
+        translator
+    }
+
+    fn declare_local(&mut self, name: &language::Identifier, ty: &language::Type) {
+        match &ty.kind {
+            language::TypeKind::Int(ty) => self.int_locals.insert(name.clone(), *ty),
+            _ => self.int_locals.remove(name),
+        };
     }
 
     fn translate_body(
@@ -142,10 +231,12 @@ impl FunctionTranslator {
     ) -> Result<rust_expr::Block, CompilationError> {
         let mut stmts = Vec::new();
         for statement in &block.statements {
-            self.translate_statement(statement, &mut stmts)?;
+            self.translate_statement(statement, &mut stmts)
+                .map_err(|error| error.with_span(statement.span()))?;
         }
         if let Some(tail) = &block.tail {
-            self.translate_expression_statement(tail, &mut stmts)?;
+            self.translate_expression_statement(tail, &mut stmts)
+                .map_err(|error| error.with_span(tail.span))?;
         }
         Ok(rust_expr::Block { label: None, stmts })
     }
@@ -172,16 +263,22 @@ impl FunctionTranslator {
                 let ty = ty.as_ref().ok_or_else(|| {
                     formality_error("formality translation needs typed `let` bindings")
                 })?;
+                let expected_int = match &ty.kind {
+                    language::TypeKind::Int(ty) => Some(*ty),
+                    _ => None,
+                };
                 let init = initial_value
                     .as_ref()
                     .map(|value| {
-                        self.translate_expression(value)
+                        self.translate_expression_as(value, expected_int)
                             .map(|expr| rust_expr::Init { expr })
                     })
                     .transpose()?;
+                let name = pattern_name(pattern)?;
+                self.declare_local(name, ty);
                 stmts.push(rust_expr::Stmt::Let {
                     label: None,
-                    id: ValueId::new(pattern_name(pattern)?),
+                    id: ValueId::new(&name.to_string()),
                     ty: self.translate_type(ty)?,
                     init,
                 });
@@ -210,6 +307,18 @@ impl FunctionTranslator {
             return Ok(());
         }
 
+        if let language::ExpressionKind::Operator(operator) = &expression.kind
+            && let language::OperatorExpression::Assignment(target, value) = &**operator
+            && matches!(
+                value.kind,
+                language::ExpressionKind::If(_) | language::ExpressionKind::Block(_)
+            )
+        {
+            let expected_int = self.int_type(target);
+            let place = self.translate_place(target)?;
+            return self.translate_assignment_into(&place, expected_int, value, stmts);
+        }
+
         if let language::ExpressionKind::Call(call) = &expression.kind {
             if expression_path(&call.callee)? == "print" {
                 if call.args.len() != 1 {
@@ -236,14 +345,99 @@ impl FunctionTranslator {
         if_expression: &language::IfExpression,
         stmts: &mut Vec<rust_expr::Stmt>,
     ) -> Result<(), CompilationError> {
+        let condition = self.translate_condition(&if_expression.condition, stmts)?;
         stmts.push(rust_expr::Stmt::If {
-            condition: self.translate_expression(&if_expression.condition)?,
+            condition,
             then_block: self.translate_if_branch(&if_expression.then_branch)?,
             else_block: self.translate_if_else_branch(if_expression.else_branch.as_deref())?,
         });
         Ok(())
     }
 
+    /// The condition of an `if`. Conditions that are themselves `if`s or blocks, e.g. from the
+    /// lazy boolean desugaring, are computed into a temporary first.
+    fn translate_condition(
+        &mut self,
+        condition: &language::Expression,
+        stmts: &mut Vec<rust_expr::Stmt>,
+    ) -> Result<rust_expr::Expr, CompilationError> {
+        match &condition.kind {
+            language::ExpressionKind::If(_) | language::ExpressionKind::Block(_) => {
+                let name = format!("__condition{}", self.next_temporary);
+                self.next_temporary += 1;
+                stmts.push(rust_expr::Stmt::Let {
+                    label: None,
+                    id: ValueId::new(&name),
+                    ty: Ty::bool(),
+                    init: None,
+                });
+                let place = rust_expr::PlaceExpr::Var(ValueId::new(&name));
+                self.translate_assignment_into(&place, None, condition, stmts)?;
+                Ok(rust_expr::Expr::Place(place))
+            }
+            _ => self.translate_expression(condition),
+        }
+    }
+
+    /// `place = value`. Formality only has `if` statements, so when `value` is an `if` or a block
+    /// we move the assignment to the end of each branch.
+    fn translate_assignment_into(
+        &mut self,
+        place: &rust_expr::PlaceExpr,
+        expected_int: Option<language::IntType>,
+        value: &language::Expression,
+        stmts: &mut Vec<rust_expr::Stmt>,
+    ) -> Result<(), CompilationError> {
+        match &value.kind {
+            language::ExpressionKind::If(if_expression) if value.attrs.is_empty() => {
+                let condition = self.translate_condition(&if_expression.condition, stmts)?;
+                let else_branch = if_expression.else_branch.as_deref().ok_or_else(|| {
+                    formality_error(
+                        "formality translation expects `if` expressions without `else` to be desugared",
+                    )
+                })?;
+                stmts.push(rust_expr::Stmt::If {
+                    condition,
+                    then_block: self.translate_branch_into(
+                        place,
+                        expected_int,
+                        &if_expression.then_branch,
+                    )?,
+                    else_block: self.translate_branch_into(place, expected_int, else_branch)?,
+                });
+            }
+            language::ExpressionKind::Block(block) if value.attrs.is_empty() => {
+                for statement in &block.statements {
+                    self.translate_statement(statement, stmts)?;
+                }
+                let tail = block.tail.as_deref().ok_or_else(|| {
+                    formality_error(
+                        "formality translation expects a block used as a value to have a tail",
+                    )
+                })?;
+                self.translate_assignment_into(place, expected_int, tail, stmts)?;
+            }
+            _ => stmts.push(rust_expr::Stmt::Expr {
+                expr: rust_expr::Expr::Assign {
+                    place: place.clone(),
+                    expr: Arc::new(self.translate_expression_as(value, expected_int)?),
+                },
+            }),
+        }
+        Ok(())
+    }
+
+    fn translate_branch_into(
+        &mut self,
+        place: &rust_expr::PlaceExpr,
+        expected_int: Option<language::IntType>,
+        branch: &language::Expression,
+    ) -> Result<rust_expr::Block, CompilationError> {
+        let mut stmts = Vec::new();
+        self.translate_assignment_into(place, expected_int, branch, &mut stmts)?;
+        Ok(rust_expr::Block { label: None, stmts })
+    }
+
     fn translate_if_else_branch(
         &mut self,
         else_branch: Option<&language::Expression>,
@@ -286,16 +480,10 @@ impl FunctionTranslator {
             language::ExpressionKind::Literal(language::LiteralExpression::Bool(false)) => {
                 Ok(rust_expr::Expr::False)
             }
-            language::ExpressionKind::Literal(language::LiteralExpression::Integer(value)) => {
-                let value = usize::try_from(*value).map_err(|_| {
-                    formality_error(format!(
-                        "formality translation only supports integer literals that fit in `usize`, got `{value}`"
-                    ))
-                })?;
-                Ok(rust_expr::Expr::Literal {
-                    value,
-                    ty: ScalarId::Usize,
-                })
+            language::ExpressionKind::Literal(language::LiteralExpression::Integer(literal)) => {
+                // Unsuffixed literals default to `usize` when the context doesn't tell.
+                let ty = literal.suffix.unwrap_or(language::IntType::Usize);
+                translate_integer_literal(literal, ty)
             }
             language::ExpressionKind::Literal(language::LiteralExpression::String(_)) => Err(
                 formality_error("formality translation does not yet support string literals"),
@@ -321,13 +509,50 @@ impl FunctionTranslator {
                     rust_expr::Expr::Place(self.translate_dereference(dereference)?),
                 ),
                 language::OperatorExpression::Assignment(target, value) => {
+                    let expected_int = self.int_type(target);
                     Ok(rust_expr::Expr::Assign {
                         place: self.translate_place(target)?,
-                        expr: Arc::new(self.translate_expression(value)?),
+                        expr: Arc::new(self.translate_expression_as(value, expected_int)?),
                     })
                 }
-                language::OperatorExpression::Add(..) => Err(formality_error(
-                    "formality translation does not yet support `+`",
+                language::OperatorExpression::Negation(operator, operand) => {
+                    let expected_int = self.int_type(operand);
+                    Ok(rust_expr::Expr::UnaryOp {
+                        op: translate_negation_operator(*operator),
+                        operand: Arc::new(self.translate_expression_as(operand, expected_int)?),
+                    })
+                }
+                language::OperatorExpression::Binary(..) => {
+                    let expected_int = self.int_type(expression);
+                    self.translate_expression_as(expression, expected_int)
+                }
+                language::OperatorExpression::Comparison(operator, left, right) => {
+                    let expected_int = self.int_type(left).or_else(|| self.int_type(right));
+                    Ok(rust_expr::Expr::BinaryOp {
+                        op: translate_comparison_operator(*operator),
+                        lhs: Arc::new(self.translate_expression_as(left, expected_int)?),
+                        rhs: Arc::new(self.translate_expression_as(right, expected_int)?),
+                    })
+                }
+                // `a op= b` only evaluates `a` once, but our places have no side effects so we can
+                // read it again.
+                language::OperatorExpression::CompoundAssignment(operator, target, value) => {
+                    let expected_int = self.int_type(target);
+                    let place = self.translate_place(target)?;
+                    let value = self.translate_binary_operands(
+                        *operator,
+                        rust_expr::Expr::Place(place.clone()),
+                        value,
+                        expected_int,
+                    )?;
+                    Ok(rust_expr::Expr::Assign {
+                        place,
+                        expr: Arc::new(value),
+                    })
+                }
+                language::OperatorExpression::LazyAnd(..)
+                | language::OperatorExpression::LazyOr(..) => Err(formality_error(
+                    "formality translation expects lazy boolean operators to be desugared",
                 )),
             },
             language::ExpressionKind::Grouped(_) => Err(formality_error(
@@ -339,18 +564,132 @@ impl FunctionTranslator {
             language::ExpressionKind::If(_) => Err(formality_error(
                 "formality translation does not yet support `if` as a value",
             )),
+            language::ExpressionKind::Loop(_)
+            | language::ExpressionKind::Break(_)
+            | language::ExpressionKind::Continue(_) => Err(formality_error(
+                "formality translation does not yet support loops",
+            )),
+            language::ExpressionKind::Let(_) | language::ExpressionKind::Match(_) => Err(
+                formality_error("formality translation does not yet support pattern matching"),
+            ),
             language::ExpressionKind::Tuple(_) => Err(formality_error(
                 "formality translation does not yet support tuple expressions",
             )),
             language::ExpressionKind::TupleIndexing(tuple_indexing) => Ok(rust_expr::Expr::Place(
                 self.translate_tuple_indexing(tuple_indexing)?,
             )),
+            language::ExpressionKind::Struct(adt) => Ok(rust_expr::Expr::Struct {
+                adt_id: rust::AdtId::new(&adt.path.to_string()),
+                field_exprs: adt
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        Ok(rust_expr::FieldExpr {
+                            name: translate_field_name(&field.name),
+                            value: self.translate_expression(&field.value)?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, CompilationError>>()?,
+            }),
+            language::ExpressionKind::Field(field) => {
+                Ok(rust_expr::Expr::Place(self.translate_field(field)?))
+            }
+            language::ExpressionKind::VariantPath(_)
+            | language::ExpressionKind::EnumVariant(_)
+            | language::ExpressionKind::EnumProjection(_)
+            | language::ExpressionKind::Discriminant(_)
+            | language::ExpressionKind::DiscriminantOf(_) => Err(formality_error(
+                "formality translation does not yet support enum expressions",
+            )),
             language::ExpressionKind::Virtual(virtual_expression) => {
                 self.translate_virtual_expression(virtual_expression)
             }
         }
     }
 
+    /// Translate an expression that the context expects to have integer type `expected_int`, if
+    /// known. Unsuffixed integer literals get that type.
+    fn translate_expression_as(
+        &mut self,
+        expression: &language::Expression,
+        expected_int: Option<language::IntType>,
+    ) -> Result<rust_expr::Expr, CompilationError> {
+        match &expression.kind {
+            language::ExpressionKind::Literal(language::LiteralExpression::Integer(literal)) => {
+                let ty = literal
+                    .suffix
+                    .or(expected_int)
+                    .unwrap_or(language::IntType::Usize);
+                translate_integer_literal(literal, ty)
+            }
+            language::ExpressionKind::Operator(operator) => match &**operator {
+                language::OperatorExpression::Binary(operator, left, right) => {
+                    let left = self.translate_expression_as(left, expected_int)?;
+                    self.translate_binary_operands(*operator, left, right, expected_int)
+                }
+                language::OperatorExpression::Negation(operator, operand) => {
+                    Ok(rust_expr::Expr::UnaryOp {
+                        op: translate_negation_operator(*operator),
+                        operand: Arc::new(self.translate_expression_as(operand, expected_int)?),
+                    })
+                }
+                _ => self.translate_expression(expression),
+            },
+            language::ExpressionKind::Virtual(
+                language::VirtualExpression::PlaceToValueCoercion(inner),
+            ) => self.translate_expression_as(inner, expected_int),
+            _ => self.translate_expression(expression),
+        }
+    }
+
+    /// `left op right`, where `left` is already translated.
+    fn translate_binary_operands(
+        &mut self,
+        operator: language::BinaryOperator,
+        left: rust_expr::Expr,
+        right: &language::Expression,
+        expected_int: Option<language::IntType>,
+    ) -> Result<rust_expr::Expr, CompilationError> {
+        // The right operand of a shift can have any integer type.
+        let right_int = if operator.is_shift() {
+            self.int_type(right)
+        } else {
+            expected_int
+        };
+        Ok(rust_expr::Expr::BinaryOp {
+            op: translate_binary_operator(operator),
+            lhs: Arc::new(left),
+            rhs: Arc::new(self.translate_expression_as(right, right_int)?),
+        })
+    }
+
+    /// The integer type of an expression, when we can tell without type inference.
+    fn int_type(&self, expression: &language::Expression) -> Option<language::IntType> {
+        match &expression.kind {
+            language::ExpressionKind::Literal(language::LiteralExpression::Integer(literal)) => {
+                literal.suffix
+            }
+            language::ExpressionKind::Path(path) => self.int_locals.get(path).copied(),
+            language::ExpressionKind::Operator(operator) => match &**operator {
+                language::OperatorExpression::Negation(_, operand) => self.int_type(operand),
+                language::OperatorExpression::Binary(
+                    language::BinaryOperator::Shl | language::BinaryOperator::Shr,
+                    left,
+                    _,
+                ) => self.int_type(left),
+                language::OperatorExpression::Binary(_, left, right) => {
+                    self.int_type(left).or_else(|| self.int_type(right))
+                }
+                _ => None,
+            },
+            language::ExpressionKind::Virtual(
+                language::VirtualExpression::PlaceToValueCoercion(inner)
+                | language::VirtualExpression::ValueToPlaceCoercion(inner),
+            ) => self.int_type(inner),
+            _ => None,
+        }
+    }
+
     fn translate_place(
         &mut self,
         expression: &language::Expression,
@@ -360,6 +699,7 @@ impl FunctionTranslator {
             language::ExpressionKind::TupleIndexing(tuple_indexing) => {
                 self.translate_tuple_indexing(tuple_indexing)
             }
+            language::ExpressionKind::Field(field) => self.translate_field(field),
             language::ExpressionKind::Operator(operator) => match &**operator {
                 language::OperatorExpression::Dereference(dereference) => {
                     self.translate_dereference(dereference)
@@ -389,6 +729,9 @@ impl FunctionTranslator {
             | language::VirtualExpression::PlaceToValueCoercion(expression) => {
                 self.translate_expression(expression)
             }
+            language::VirtualExpression::Unreachable => Err(formality_error(
+                "formality translation does not yet support `unreachable_unchecked!()`",
+            )),
         }
     }
 
@@ -401,6 +744,9 @@ impl FunctionTranslator {
             | language::VirtualExpression::PlaceToValueCoercion(expression) => {
                 self.translate_place(expression)
             }
+            language::VirtualExpression::Unreachable => Err(formality_error(
+                "formality translation expected a place expression, got `unreachable_unchecked!()`",
+            )),
         }
     }
 
@@ -423,13 +769,23 @@ impl FunctionTranslator {
         })
     }
 
+    fn translate_field(
+        &mut self,
+        field: &language::FieldExpression,
+    ) -> Result<rust_expr::PlaceExpr, CompilationError> {
+        Ok(rust_expr::PlaceExpr::Field {
+            prefix: Arc::new(self.translate_place(&field.expression)?),
+            field_name: FieldName::Id(rust::FieldId::new(&field.field.to_string())),
+        })
+    }
+
     fn translate_simple_path(path: &language::PathExpression) -> rust_expr::PlaceExpr {
-        rust_expr::PlaceExpr::Var(ValueId::new(path))
+        rust_expr::PlaceExpr::Var(ValueId::new(&path.to_string()))
     }
 
     fn translate_type(&mut self, ty: &language::Type) -> Result<Ty, CompilationError> {
-        match ty {
-            language::Type::Tuple(types) => {
+        match &ty.kind {
+            language::TypeKind::Tuple(types) => {
                 let parameters = types
                     .iter()
                     .map(|ty| {
@@ -439,18 +795,20 @@ impl FunctionTranslator {
                     .collect::<Result<Vec<_>, _>>()?;
                 Ok(Ty::rigid(RigidName::Tuple(types.len()), parameters))
             }
-            language::Type::Bool => Ok(Ty::bool()),
-            language::Type::Ref(_, mutability, inner) => {
+            language::TypeKind::Bool => Ok(Ty::bool()),
+            language::TypeKind::Int(ty) => translate_int_type(*ty),
+            language::TypeKind::Ref(_, mutability, inner) => {
                 let inner = self.translate_type(inner)?;
                 Ok(match mutability {
                     language::Mutability::Immutable => inner.ref_ty(self.fresh_lifetime()),
                     language::Mutability::Mutable => inner.ref_mut_ty(self.fresh_lifetime()),
                 })
             }
-            language::Type::Str => Err(formality_error(
+            language::TypeKind::Path(path, _) => Ok(translate_adt_type(path)),
+            language::TypeKind::Str => Err(formality_error(
                 "formality translation does not yet support `str`",
             )),
-            language::Type::TraitSelf => Err(formality_error(format!(
+            language::TypeKind::TraitSelf => Err(formality_error(format!(
                 "formality translation does not yet support type `{ty}`"
             ))),
         }
@@ -464,31 +822,136 @@ impl FunctionTranslator {
 }
 
 fn translate_type(ty: &language::Type) -> Result<Ty, CompilationError> {
-    match ty {
-        language::Type::Tuple(types) => {
+    match &ty.kind {
+        language::TypeKind::Tuple(types) => {
             let parameters = types
                 .iter()
                 .map(|ty| translate_type(ty).map(|ty| Parameter::Ty(Arc::new(ty))))
                 .collect::<Result<Vec<_>, _>>()?;
             Ok(Ty::rigid(RigidName::Tuple(types.len()), parameters))
         }
-        language::Type::Bool => Ok(Ty::bool()),
-        language::Type::Ref(_, mutability, inner) => {
+        language::TypeKind::Bool => Ok(Ty::bool()),
+        language::TypeKind::Int(ty) => translate_int_type(*ty),
+        language::TypeKind::Ref(_, mutability, inner) => {
             let inner = translate_type(inner)?;
             Ok(match mutability {
                 language::Mutability::Immutable => inner.ref_ty(Lt::Erased),
                 language::Mutability::Mutable => inner.ref_mut_ty(Lt::Erased),
             })
         }
-        language::Type::Str => Err(formality_error(
+        language::TypeKind::Path(path, _) => Ok(translate_adt_type(path)),
+        language::TypeKind::Str => Err(formality_error(
             "formality translation does not yet support `str`",
         )),
-        language::Type::TraitSelf => Err(formality_error(format!(
+        language::TypeKind::TraitSelf => Err(formality_error(format!(
             "formality translation does not yet support type `{ty}`"
         ))),
     }
 }
 
+fn translate_integer_literal(
+    literal: &language::IntegerLiteral,
+    ty: language::IntType,
+) -> Result<rust_expr::Expr, CompilationError> {
+    let value = usize::try_from(literal.value).map_err(|_| {
+        formality_error(format!(
+            "formality translation only supports integer literals that fit in `usize`, got `{literal}`"
+        ))
+    })?;
+    Ok(rust_expr::Expr::Literal {
+        value,
+        ty: translate_scalar(ty)?,
+    })
+}
+
+fn translate_binary_operator(operator: language::BinaryOperator) -> rust_expr::BinaryOp {
+    match operator {
+        language::BinaryOperator::Add => rust_expr::BinaryOp::Add,
+        language::BinaryOperator::Sub => rust_expr::BinaryOp::Sub,
+        language::BinaryOperator::Mul => rust_expr::BinaryOp::Mul,
+        language::BinaryOperator::Div => rust_expr::BinaryOp::Div,
+        language::BinaryOperator::Rem => rust_expr::BinaryOp::Rem,
+        language::BinaryOperator::BitAnd => rust_expr::BinaryOp::BitAnd,
+        language::BinaryOperator::BitOr => rust_expr::BinaryOp::BitOr,
+        language::BinaryOperator::BitXor => rust_expr::BinaryOp::BitXor,
+        language::BinaryOperator::Shl => rust_expr::BinaryOp::Shl,
+        language::BinaryOperator::Shr => rust_expr::BinaryOp::Shr,
+    }
+}
+
+fn translate_comparison_operator(operator: language::ComparisonOperator) -> rust_expr::BinaryOp {
+    match operator {
+        language::ComparisonOperator::Eq => rust_expr::BinaryOp::Eq,
+        language::ComparisonOperator::Ne => rust_expr::BinaryOp::Ne,
+        language::ComparisonOperator::Lt => rust_expr::BinaryOp::Lt,
+        language::ComparisonOperator::Gt => rust_expr::BinaryOp::Gt,
+        language::ComparisonOperator::Le => rust_expr::BinaryOp::Le,
+        language::ComparisonOperator::Ge => rust_expr::BinaryOp::Ge,
+    }
+}
+
+fn translate_negation_operator(operator: language::NegationOperator) -> rust_expr::UnaryOp {
+    match operator {
+        language::NegationOperator::Neg => rust_expr::UnaryOp::Neg,
+        language::NegationOperator::Not => rust_expr::UnaryOp::Not,
+    }
+}
+
+fn translate_int_type(ty: language::IntType) -> Result<Ty, CompilationError> {
+    Ok(Ty::rigid(
+        RigidName::ScalarId(translate_scalar(ty)?),
+        Vec::<Parameter>::new(),
+    ))
+}
+
+fn translate_scalar(ty: language::IntType) -> Result<ScalarId, CompilationError> {
+    Ok(match ty {
+        language::IntType::U8 => ScalarId::U8,
+        language::IntType::U16 => ScalarId::U16,
+        language::IntType::U32 => ScalarId::U32,
+        language::IntType::U64 => ScalarId::U64,
+        language::IntType::Usize => ScalarId::Usize,
+        language::IntType::I8 => ScalarId::I8,
+        language::IntType::I16 => ScalarId::I16,
+        language::IntType::I32 => ScalarId::I32,
+        language::IntType::I64 => ScalarId::I64,
+        language::IntType::Isize => ScalarId::Isize,
+        // a-mir-formality has no 128-bit integers.
+        language::IntType::U128 | language::IntType::I128 => {
+            return Err(formality_error(format!(
+                "formality translation does not support `{ty}`"
+            )));
+        }
+    })
+}
+
+fn translate_fields(fields: &language::StructFields) -> Result<Vec<rust::Field>, CompilationError> {
+    fields
+        .field_list()
+        .into_iter()
+        .map(|(name, ty)| {
+            Ok(rust::Field {
+                name: translate_field_name(&name),
+                ty: translate_type(ty)?,
+            })
+        })
+        .collect()
+}
+
+fn translate_adt_type(path: &language::Path) -> Ty {
+    Ty::rigid(
+        RigidName::AdtId(rust::AdtId::new(&path.to_string())),
+        vec![],
+    )
+}
+
+fn translate_field_name(name: &language::FieldName) -> FieldName {
+    match name {
+        language::FieldName::Named(name) => FieldName::Id(rust::FieldId::new(&name.to_string())),
+        language::FieldName::Index(index) => FieldName::Index(*index),
+    }
+}
+
 fn translate_ref_kind(mutability: language::Mutability) -> RefKind {
     match mutability {
         language::Mutability::Mutable => RefKind::Mut,
@@ -503,7 +966,9 @@ fn translate_item_safety(safety: Option<&language::ItemSafety>) -> Safety {
     }
 }
 
-fn expression_path(expression: &language::Expression) -> Result<&str, CompilationError> {
+fn expression_path(
+    expression: &language::Expression,
+) -> Result<&language::Identifier, CompilationError> {
     match &expression.kind {
         language::ExpressionKind::Path(path) => Ok(path),
         language::ExpressionKind::Grouped(_) => Err(formality_error(
@@ -515,15 +980,18 @@ fn expression_path(expression: &language::Expression) -> Result<&str, Compilatio
     }
 }
 
-fn pattern_name(pattern: &language::Pattern) -> Result<&str, CompilationError> {
-    match pattern {
-        language::Pattern::Identifier(name) => Ok(name),
-        language::Pattern::Wildcard => Err(formality_error(
+fn pattern_name(pattern: &language::Pattern) -> Result<&language::Identifier, CompilationError> {
+    match &pattern.kind {
+        language::PatternKind::Identifier(name) => Ok(name),
+        language::PatternKind::Wildcard
+        | language::PatternKind::Literal(_)
+        | language::PatternKind::Tuple(_)
+        | language::PatternKind::Variant(_) => Err(formality_error(
             "formality translation needs named bindings",
         )),
     }
 }
 
 fn formality_error(message: impl Into<String>) -> CompilationError {
-    CompilationError::Formality(message.into())
+    CompilationError::Formality(Diagnostic::new(message))
 }
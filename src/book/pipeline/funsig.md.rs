@@ -5,27 +5,32 @@
 use crate::desugarings::*;
 
 pub fn desugar_fun_sigs(program: &mut Program) -> Result<(), CompilationError> {
-    program.visit_all_mut(|f: &mut Function| {
-        implicit_return(f);
-        shorthand_self(f);
-        self_first(f)?;
+    program.visit_all_mut(|item: &mut Item| {
+        if let ItemKind::Function(f) = &mut item.kind {
+            implicit_return(f, &item.origin);
+            shorthand_self(f, &item.origin);
+            self_first(f, item.span)?;
+        }
         Ok(())
     })
 }
 
 //@ If the output type is not explicitly stated, it is the unit type
 //@ [ref:items.fn.implicit-return].
-fn implicit_return(f: &mut Function) {
+fn implicit_return(f: &mut Function, origin: &Origin) {
     if f.return_type.is_none() {
-        f.return_type = Some(Type::mk_unit())
+        f.return_type = Some(Type::derived_from(origin, TypeKind::Tuple(Vec::new())))
     }
 }
 
 //@ A `self` parameter is only allowed as the first function argument [ref:items.fn.syntax].
-fn self_first(f: &Function) -> Result<(), CompilationError> {
+fn self_first(f: &Function, span: Span) -> Result<(), CompilationError> {
     for p in f.parameters.iter().skip(1) {
         if !matches!(p.kind, FunctionParamKind::Regular { .. }) {
-            desugaring_error!("A `self` parameter is only allowed as the first function argument")
+            desugaring_error!(
+                span,
+                "A `self` parameter is only allowed as the first function argument"
+            )
         }
     }
     Ok(())
@@ -33,7 +38,7 @@ fn self_first(f: &Function) -> Result<(), CompilationError> {
 
 //@ A `self` parameter is sugar for `self: Self`, and a `&self` parameter is sugar for `self:
 //@ &Self`. [ref:associated.fn.method.self-pat-shorthands]
-fn shorthand_self(f: &mut Function) {
+fn shorthand_self(f: &mut Function, origin: &Origin) {
     if let Some(p) = f.parameters.first_mut() {
         match &p.kind {
             FunctionParamKind::SelfParam {
@@ -42,7 +47,7 @@ fn shorthand_self(f: &mut Function) {
             } => {
                 p.kind = FunctionParamKind::SelfParam {
                     mutability: *mutability,
-                    ty: Some(Type::TraitSelf),
+                    ty: Some(Type::derived_from(origin, TypeKind::TraitSelf)),
                 }
             }
             FunctionParamKind::RefSelfShorthand {
@@ -51,10 +56,13 @@ fn shorthand_self(f: &mut Function) {
             } => {
                 p.kind = FunctionParamKind::SelfParam {
                     mutability: Mutability::Immutable,
-                    ty: Some(Type::Ref(
-                        lifetime.clone(),
-                        *mutability,
-                        Box::new(Type::TraitSelf),
+                    ty: Some(Type::derived_from(
+                        origin,
+                        TypeKind::Ref(
+                            lifetime.clone(),
+                            *mutability,
+                            Box::new(Type::derived_from(origin, TypeKind::TraitSelf)),
+                        ),
                     )),
                 }
             }
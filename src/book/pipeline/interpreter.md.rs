@@ -0,0 +1,1303 @@
+//@ # Reference Interpreter
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+//@ Each desugaring step must produce an equivalent program. To check that, we need to run programs
+//@ at every stage of the pipeline, not only once they're in the final language that the [MiniRust
+//@ translation](minirust.md.rs) understands. This is a direct interpreter of our AST that runs
+//@ `main`. The only observable effect of a program is what it `print`s.
+//@
+//@ Memory is a list of slots, one for each local variable and each temporary. A place is a slot,
+//@ plus a path of projections into the value stored there. We don't model drops, and nothing is
+//@ ever deallocated, so dangling references go unnoticed. We do report some of the errors that
+//@ would be undefined behavior or a panic in Rust, such as reading an uninitialized local or
+//@ dividing by zero.
+//@
+//@ The types of integer literals and bindings come from [Type Checking](type-checking.md.rs), so
+//@ the program must type-check. Integers wrap around on overflow, like they do once we run them in
+//@ MiniRust, since we don't desugar [overflow checks](overflow-checks.md) yet. The few integers we
+//@ make without a type, such as discriminants, are unbounded, and we report an overflow if they
+//@ don't fit in an `i128` or a `u128`.
+use crate::desugarings::*; //#
+use std::cmp::Ordering; //#
+use std::collections::HashMap; //#
+use std::fmt::{self, Display, Formatter}; //#
+
+pub fn interpret(program: &Program) -> Result<String, CompilationError> {
+    let items = Items::collect(program);
+    let types = type_checking::node_types(program)?;
+    let Some(main) = items.functions.get("main") else {
+        return Err(runtime_error("the program has no `main` function"));
+    };
+    let mut interpreter = Interpreter {
+        items: &items,
+        types: &types,
+        slots: vec![],
+        scopes: vec![],
+        output: String::new(),
+    };
+    interpreter.call(main, vec![])?;
+    Ok(interpreter.output)
+}
+
+fn runtime_error(message: impl Into<String>) -> CompilationError {
+    CompilationError::Runtime(Diagnostic::new(message))
+}
+
+//@ ## Values
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Bool(bool),
+    Int(Int),
+    Str(String),
+    Tuple(Vec<Value>),
+    /// The fields are sorted by name, so that the order of a struct expression doesn't matter.
+    Struct(Identifier, Vec<(FieldName, Value)>),
+    /// The current variant of an enum, and the fields that were written, by variant. A desugared
+    /// program may write the fields of a variant before setting the discriminant to that variant,
+    /// see [Enum Projections](../features/enum-projections.md).
+    Enum(VariantPath, Vec<((Identifier, FieldName), Value)>),
+    Ref(Place),
+}
+
+//@ No Rust integer type holds both the values of `i128` and those of `u128`, so we store the bits
+//@ of an integer, and whether to read them as an `i128` or as a `u128`.
+#[derive(Debug, Clone, Copy)]
+struct Int {
+    /// The value in two's complement, sign-extended to 128 bits.
+    bits: u128,
+    /// Whether `bits` is an `i128` rather than a `u128`.
+    signed: bool,
+    /// `None` if we don't know it, e.g. for a discriminant.
+    ty: Option<IntType>,
+}
+
+/// Integers are equal if they have the same value, whether we know their type or not.
+impl PartialEq for Int {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp_value(*other).is_eq()
+    }
+}
+
+impl Display for Int {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_negative() {
+            write!(f, "{}", self.bits as i128)
+        } else {
+            write!(f, "{}", self.bits)
+        }
+    }
+}
+
+impl Int {
+    /// A signed integer whose type we don't know.
+    fn signed(value: i128) -> Self {
+        Int {
+            bits: value as u128,
+            signed: true,
+            ty: None,
+        }
+    }
+
+    /// An unsigned integer whose type we don't know.
+    fn unsigned(value: u128) -> Self {
+        Int {
+            bits: value,
+            signed: false,
+            ty: None,
+        }
+    }
+
+    /// An integer of the same type with these bits.
+    fn with_bits(self, bits: u128) -> Self {
+        Int { bits, ..self }
+    }
+
+    /// This integer as a value of type `ty`, wrapped around to fit in that type.
+    fn wrap(self, ty: IntType) -> Self {
+        let width = u32::from(ty.size_in_bytes()) * 8;
+        let mut bits = self.bits;
+        if width < 128 {
+            let mask = (1 << width) - 1;
+            bits &= mask;
+            if ty.is_signed() && bits >> (width - 1) == 1 {
+                bits |= !mask;
+            }
+        }
+        Int {
+            bits,
+            signed: ty.is_signed(),
+            ty: Some(ty),
+        }
+    }
+
+    /// This integer as a value of type `ty`, if we know it.
+    fn with_type(self, ty: Option<IntType>) -> Self {
+        match ty {
+            Some(ty) => self.wrap(ty),
+            None => self,
+        }
+    }
+
+    fn is_negative(self) -> bool {
+        self.signed && (self.bits as i128) < 0
+    }
+
+    fn to_i128(self) -> Option<i128> {
+        if self.is_negative() {
+            Some(self.bits as i128)
+        } else {
+            i128::try_from(self.bits).ok()
+        }
+    }
+
+    fn to_u128(self) -> Option<u128> {
+        (!self.is_negative()).then_some(self.bits)
+    }
+
+    /// Compare the values of two integers, whatever their types. Two negative values compare
+    /// like their sign-extended bits do.
+    fn cmp_value(self, other: Self) -> Ordering {
+        other
+            .is_negative()
+            .cmp(&self.is_negative())
+            .then(self.bits.cmp(&other.bits))
+    }
+}
+
+impl Value {
+    fn unit() -> Self {
+        Value::Tuple(vec![])
+    }
+
+    fn as_bool(&self) -> Result<bool, CompilationError> {
+        match self {
+            Value::Bool(value) => Ok(*value),
+            _ => Err(runtime_error(format!("expected a `bool`, got {self:?}"))),
+        }
+    }
+
+    fn field(&self, name: &FieldName) -> Result<&Value, CompilationError> {
+        self.fields()
+            .and_then(|mut fields| fields.find(|(field, _)| *field == *name))
+            .map(|(_, value)| value)
+            .ok_or_else(|| runtime_error(format!("no field `{name}` in {self:?}")))
+    }
+
+    fn field_mut(&mut self, name: &FieldName) -> Result<&mut Value, CompilationError> {
+        // Find the field before borrowing it, so that the error can still describe `self`.
+        let position = match (&*self, name) {
+            (Value::Tuple(elements), FieldName::Index(index)) => {
+                (*index < elements.len()).then_some(*index)
+            }
+            (Value::Struct(_, fields), _) => fields.iter().position(|(field, _)| field == name),
+            _ => None,
+        };
+        match (self, position) {
+            (Value::Tuple(elements), Some(index)) => Ok(&mut elements[index]),
+            (Value::Struct(_, fields), Some(index)) => Ok(&mut fields[index].1),
+            (value, _) => Err(runtime_error(format!("no field `{name}` in {value:?}"))),
+        }
+    }
+
+    fn fields(&self) -> Option<Box<dyn Iterator<Item = (FieldName, &Value)> + '_>> {
+        match self {
+            Value::Tuple(elements) => Some(Box::new(
+                elements
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| (FieldName::Index(index), value)),
+            )),
+            Value::Struct(_, fields) => Some(Box::new(
+                fields.iter().map(|(field, value)| (field.clone(), value)),
+            )),
+            _ => None,
+        }
+    }
+
+    fn variant_field(
+        &self,
+        variant: &Identifier,
+        name: &FieldName,
+    ) -> Result<&Value, CompilationError> {
+        let Value::Enum(_, fields) = self else {
+            return Err(runtime_error(format!(
+                "cannot project {self:?} to variant `{variant}`, it is not an enum"
+            )));
+        };
+        fields
+            .iter()
+            .find(|((field_variant, field), _)| field_variant == variant && field == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| {
+                runtime_error(format!(
+                    "use of the uninitialized field `{name}` of variant `{variant}`"
+                ))
+            })
+    }
+
+    fn variant_field_mut(
+        &mut self,
+        variant: &Identifier,
+        name: &FieldName,
+    ) -> Result<&mut Value, CompilationError> {
+        let Value::Enum(_, fields) = self else {
+            return Err(runtime_error(format!(
+                "cannot project {self:?} to variant `{variant}`, it is not an enum"
+            )));
+        };
+        fields
+            .iter_mut()
+            .find(|((field_variant, field), _)| field_variant == variant && field == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| {
+                runtime_error(format!(
+                    "use of the uninitialized field `{name}` of variant `{variant}`"
+                ))
+            })
+    }
+}
+
+fn sorted_fields(mut fields: Vec<(FieldName, Value)>) -> Vec<(FieldName, Value)> {
+    fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+    fields
+}
+
+fn positional_fields(values: Vec<Value>) -> Vec<(FieldName, Value)> {
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| (FieldName::Index(index), value))
+        .collect()
+}
+
+fn variant_fields(
+    variant: &Identifier,
+    fields: Vec<(FieldName, Value)>,
+) -> Vec<((Identifier, FieldName), Value)> {
+    fields
+        .into_iter()
+        .map(|(field, value)| ((variant.clone(), field), value))
+        .collect()
+}
+
+//@ ## Places
+
+#[derive(Debug, Clone, PartialEq)]
+struct Place {
+    slot: usize,
+    projections: Vec<Projection>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Projection {
+    Field(FieldName),
+    /// A field of the given variant of an enum, see [Enum
+    /// Projections](../features/enum-projections.md).
+    VariantField(Identifier, FieldName),
+    /// The discriminant of an enum. Writing it changes the variant of the enum.
+    Discriminant,
+}
+
+impl Place {
+    fn project(mut self, projection: Projection) -> Self {
+        self.projections.push(projection);
+        self
+    }
+}
+
+struct Slot {
+    /// The local variable this slot is for, or `None` for a temporary.
+    name: Option<Identifier>,
+    /// The type of the slot, if it is a known integer type.
+    int_type: Option<IntType>,
+    value: Option<Value>,
+}
+
+//@ ## Evaluation
+
+/// The items of the program, by name. Without modules, items can be used anywhere.
+#[derive(Default)]
+struct Items {
+    functions: HashMap<Identifier, Function>,
+    structs: HashMap<Identifier, Struct>,
+    enums: HashMap<Identifier, Enum>,
+}
+
+impl Items {
+    fn collect(program: &Program) -> Self {
+        let mut items = Items::default();
+        program.visit_all_infallible(|function: &Function| {
+            items
+                .functions
+                .insert(function.name.clone(), function.clone());
+        });
+        program.visit_all_infallible(|adt: &Struct| {
+            items.structs.insert(adt.name.clone(), adt.clone());
+        });
+        program.visit_all_infallible(|adt: &Enum| {
+            items.enums.insert(adt.name.clone(), adt.clone());
+        });
+        items
+    }
+
+    fn discriminant(&self, path: &VariantPath) -> Result<i128, CompilationError> {
+        self.enums
+            .get(&path.enum_name)
+            .and_then(|adt| adt.variant(&path.variant))
+            .map(|(discriminant, _)| discriminant as i128)
+            .ok_or_else(|| runtime_error(format!("no variant `{path}`")))
+    }
+
+    fn variant_name(
+        &self,
+        enum_name: &Identifier,
+        discriminant: Int,
+    ) -> Result<Identifier, CompilationError> {
+        self.enums
+            .get(enum_name)
+            .and_then(|adt| {
+                adt.variants
+                    .get(usize::try_from(discriminant.to_u128()?).ok()?)
+            })
+            .map(|variant| variant.name.clone())
+            .ok_or_else(|| {
+                runtime_error(format!(
+                    "enum `{enum_name}` has no variant with discriminant {discriminant}"
+                ))
+            })
+    }
+}
+
+struct Interpreter<'a> {
+    items: &'a Items,
+    /// The types of the nodes of the program, by id.
+    types: &'a HashMap<NodeId, Type>,
+    slots: Vec<Slot>,
+    /// The scopes of the function being run, innermost last.
+    scopes: Vec<Scope>,
+    output: String,
+}
+
+#[derive(Default)]
+struct Scope {
+    label: Option<String>,
+    locals: HashMap<Identifier, usize>,
+}
+
+/// Why the evaluation of an expression stopped without producing a value.
+enum Interrupt {
+    Break { label: Option<String>, value: Value },
+    Continue { label: Option<String> },
+    Error(CompilationError),
+}
+
+impl From<CompilationError> for Interrupt {
+    fn from(error: CompilationError) -> Self {
+        Interrupt::Error(error)
+    }
+}
+
+type Eval<T> = Result<T, Interrupt>;
+
+impl Interpreter<'_> {
+    fn call(&mut self, function: &Function, args: Vec<Value>) -> Result<Value, CompilationError> {
+        let FunctionBody::Block(body) = &function.body else {
+            return Err(runtime_error(format!(
+                "function `{}` has no body",
+                function.name
+            )));
+        };
+        if args.len() != function.parameters.len() {
+            return Err(runtime_error(format!(
+                "function `{}` takes {} arguments but {} were supplied",
+                function.name,
+                function.parameters.len(),
+                args.len()
+            )));
+        }
+        // The callee can't see the locals of the caller.
+        let caller_scopes = std::mem::replace(&mut self.scopes, vec![Scope::default()]);
+        let result = self.call_body(function, body, args);
+        self.scopes = caller_scopes;
+        match result {
+            Ok(value) => Ok(value),
+            Err(Interrupt::Error(error)) => Err(error),
+            Err(Interrupt::Break { .. } | Interrupt::Continue { .. }) => {
+                Err(runtime_error(format!(
+                    "`break` or `continue` outside of a loop in `{}`",
+                    function.name
+                )))
+            }
+        }
+    }
+
+    fn call_body(
+        &mut self,
+        function: &Function,
+        body: &BlockExpression,
+        args: Vec<Value>,
+    ) -> Eval<Value> {
+        for (parameter, arg) in function.parameters.iter().zip(args) {
+            match &parameter.kind {
+                FunctionParamKind::Regular {
+                    pattern: Some(pattern),
+                    ..
+                } => {
+                    self.bind(pattern, arg, 0)?;
+                }
+                FunctionParamKind::Regular { pattern: None, .. } => {}
+                FunctionParamKind::SelfParam { .. }
+                | FunctionParamKind::RefSelfShorthand { .. } => {
+                    self.declare(Identifier::new("self"), None, Some(arg), 0)?;
+                }
+            }
+        }
+        self.block(body)
+    }
+
+    /// Run `f` in a new scope.
+    fn in_scope<T>(
+        &mut self,
+        label: Option<String>,
+        f: impl FnOnce(&mut Self) -> Eval<T>,
+    ) -> Eval<T> {
+        self.scopes.push(Scope {
+            label,
+            locals: HashMap::new(),
+        });
+        let result = f(self);
+        self.scopes.pop();
+        result
+    }
+
+    /// Make a new slot for a local variable of the scope at index `scope`.
+    fn declare(
+        &mut self,
+        name: Identifier,
+        int_type: Option<IntType>,
+        value: Option<Value>,
+        scope: usize,
+    ) -> Result<(), CompilationError> {
+        let slot = self.slots.len();
+        self.slots.push(Slot {
+            name: Some(name.clone()),
+            int_type,
+            value: None,
+        });
+        if let Some(value) = value {
+            self.write(&Place::from_slot(slot), value)?;
+        }
+        self.scopes[scope].locals.insert(name, slot);
+        Ok(())
+    }
+
+    /// Store `value` in a new temporary.
+    fn temporary(&mut self, value: Value) -> Place {
+        self.slots.push(Slot {
+            name: None,
+            int_type: None,
+            value: Some(value),
+        });
+        Place::from_slot(self.slots.len() - 1)
+    }
+
+    /// The integer type of the node with this origin, if it has one.
+    fn int_type(&self, origin: &Origin) -> Option<IntType> {
+        match self.types.get(&origin.id).map(|ty| &ty.kind) {
+            Some(TypeKind::Int(ty)) => Some(*ty),
+            _ => None,
+        }
+    }
+
+    fn local(&self, name: &str) -> Option<usize> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.locals.get(name).copied())
+    }
+
+    fn block(&mut self, block: &BlockExpression) -> Eval<Value> {
+        let result = self.in_scope(block.label.clone(), |this| {
+            for statement in &block.statements {
+                this.statement(statement)?;
+            }
+            match &block.tail {
+                Some(tail) => this.value(tail),
+                None => Ok(Value::unit()),
+            }
+        });
+        match result {
+            Err(Interrupt::Break {
+                label: Some(label),
+                value,
+            }) if block.label.as_ref() == Some(&label) => Ok(value),
+            result => result,
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Eval<()> {
+        match statement {
+            Statement::Empty | Statement::Item(_) => Ok(()),
+            Statement::Let {
+                scope,
+                pattern,
+                ty,
+                initial_value,
+                else_branch,
+                span,
+                ..
+            } => self
+                .let_statement(scope, pattern, initial_value, else_branch)
+                .map_err(|interrupt| interrupt.with_span(*span)),
+            Statement::Expr(expression) => self.value(expression).map(|_| ()),
+        }
+    }
+
+    fn let_statement(
+        &mut self,
+        scope: &Option<String>,
+        pattern: &Pattern,
+        initial_value: &Option<Expression>,
+        else_branch: &Option<BlockExpression>,
+    ) -> Eval<()> {
+        let value = match initial_value {
+            Some(initial_value) => Some(self.value(initial_value)?),
+            None => None,
+        };
+        //@ A [scoped `let`](../features/scoped-let.md) declares its bindings in the scope of the
+        //@ block with that label.
+        let scope = match scope {
+            Some(label) => self
+                .scopes
+                .iter()
+                .rposition(|scope| scope.label.as_ref() == Some(label))
+                .ok_or_else(|| runtime_error(format!("no enclosing block labeled `{label}`")))?,
+            None => self.scopes.len() - 1,
+        };
+        let Some(value) = value else {
+            let mut names = vec![];
+            pattern.visit_all_infallible(|pattern: &Pattern| {
+                if let PatternKind::Identifier(name) = &pattern.kind {
+                    names.push((name.clone(), self.int_type(&pattern.origin)));
+                }
+            });
+            for (name, int_type) in names {
+                self.declare(name, int_type, None, scope)?;
+            }
+            return Ok(());
+        };
+        if self.bind(pattern, value, scope)? {
+            return Ok(());
+        }
+        match else_branch {
+            Some(else_branch) => {
+                self.block(else_branch)?;
+                Err(runtime_error("the `else` block of a `let...else` did not diverge").into())
+            }
+            None => Err(runtime_error("refutable pattern in `let` did not match").into()),
+        }
+    }
+
+    /// Match `value` against `pattern`, and if it matches declare its bindings in the scope at
+    /// index `scope`. Returns whether it matched.
+    fn bind(
+        &mut self,
+        pattern: &Pattern,
+        value: Value,
+        scope: usize,
+    ) -> Result<bool, CompilationError> {
+        let mut bindings = vec![];
+        if !matches_pattern(pattern, &value, &mut bindings) {
+            return Ok(false);
+        }
+        for (name, origin, value) in bindings {
+            self.declare(name, self.int_type(origin), Some(value), scope)?;
+        }
+        Ok(true)
+    }
+
+    /// Evaluate `expression` as a value expression. Place expressions are read from.
+    fn value(&mut self, expression: &Expression) -> Eval<Value> {
+        self.value_kind(expression)
+            .map_err(|interrupt| interrupt.with_span(expression.span))
+    }
+
+    fn value_kind(&mut self, expression: &Expression) -> Eval<Value> {
+        match &expression.kind {
+            ExpressionKind::Literal(literal) => Ok(match literal_value(literal)? {
+                Value::Int(int) => Value::Int(int.with_type(self.int_type(&expression.origin))),
+                value => value,
+            }),
+            ExpressionKind::Path(_)
+            | ExpressionKind::TupleIndexing(_)
+            | ExpressionKind::Field(_)
+            | ExpressionKind::EnumProjection(_)
+            | ExpressionKind::Discriminant(_)
+            | ExpressionKind::Operator(OperatorExpression::Dereference(_))
+            | ExpressionKind::Virtual(
+                VirtualExpression::ValueToPlaceCoercion(_)
+                | VirtualExpression::PlaceToValueCoercion(_),
+            ) => {
+                let place = self.place_kind(expression)?;
+                Ok(self.read(&place)?)
+            }
+            ExpressionKind::Virtual(VirtualExpression::Unreachable) => {
+                Err(runtime_error("entered unreachable code").into())
+            }
+            ExpressionKind::Operator(operator) => self.operator(operator),
+            ExpressionKind::Grouped(inner) => self.value(inner),
+            ExpressionKind::Block(block) => self.block(block),
+            //@ The bindings of the `let`s in the condition of an `if` are in scope in its `then`
+            //@ branch only.
+            ExpressionKind::If(if_expression) => {
+                let then_value = self.in_scope(None, |this| {
+                    if this.value(&if_expression.condition)?.as_bool()? {
+                        Ok(Some(this.value(&if_expression.then_branch)?))
+                    } else {
+                        Ok(None)
+                    }
+                })?;
+                match (then_value, &if_expression.else_branch) {
+                    (Some(value), _) => Ok(value),
+                    (None, Some(else_branch)) => self.value(else_branch),
+                    (None, None) => Ok(Value::unit()),
+                }
+            }
+            ExpressionKind::Let(let_expression) => {
+                let value = self.value(&let_expression.scrutinee)?;
+                let scope = self.scopes.len() - 1;
+                Ok(Value::Bool(self.bind(
+                    &let_expression.pattern,
+                    value,
+                    scope,
+                )?))
+            }
+            ExpressionKind::Loop(loop_expression) => self.loop_expression(loop_expression),
+            ExpressionKind::Match(match_expression) => {
+                let scrutinee = self.value(&match_expression.scrutinee)?;
+                for arm in &match_expression.arms {
+                    let value = self.in_scope(None, |this| {
+                        let scope = this.scopes.len() - 1;
+                        if !this.bind(&arm.pattern, scrutinee.clone(), scope)? {
+                            return Ok(None);
+                        }
+                        if let Some(guard) = &arm.guard
+                            && !this.value(guard)?.as_bool()?
+                        {
+                            return Ok(None);
+                        }
+                        Ok(Some(this.value(&arm.body)?))
+                    })?;
+                    if let Some(value) = value {
+                        return Ok(value);
+                    }
+                }
+                Err(runtime_error("no `match` arm matched").into())
+            }
+            ExpressionKind::Break(break_expression) => {
+                let value = match &break_expression.value {
+                    Some(value) => self.value(value)?,
+                    None => Value::unit(),
+                };
+                Err(Interrupt::Break {
+                    label: break_expression.label.clone(),
+                    value,
+                })
+            }
+            ExpressionKind::Continue(continue_expression) => Err(Interrupt::Continue {
+                label: continue_expression.label.clone(),
+            }),
+            ExpressionKind::Tuple(elements) => Ok(Value::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.value(element))
+                    .collect::<Eval<_>>()?,
+            )),
+            ExpressionKind::Struct(adt) => {
+                let fields = self.field_values(&adt.fields)?;
+                Ok(Value::Struct(adt.path.clone(), sorted_fields(fields)))
+            }
+            ExpressionKind::EnumVariant(variant) => {
+                let fields = self.field_values(&variant.fields)?;
+                Ok(Value::Enum(
+                    variant.path.clone(),
+                    variant_fields(&variant.path.variant, fields),
+                ))
+            }
+            ExpressionKind::VariantPath(path) => Ok(Value::Enum(path.clone(), vec![])),
+            ExpressionKind::DiscriminantOf(path) => {
+                Ok(Value::Int(Int::signed(self.items.discriminant(path)?)))
+            }
+            ExpressionKind::Call(call) => self.call_expression(call),
+        }
+    }
+
+    fn field_values(&mut self, fields: &[StructExprField]) -> Eval<Vec<(FieldName, Value)>> {
+        fields
+            .iter()
+            .map(|field| Ok((field.name.clone(), self.value(&field.value)?)))
+            .collect()
+    }
+
+    fn loop_expression(&mut self, loop_expression: &LoopExpression) -> Eval<Value> {
+        let targets_us =
+            |label: &Option<String>| label.is_none() || *label == loop_expression.label;
+        loop {
+            let result = match &loop_expression.kind {
+                LoopKind::Infinite(body) => self.block(body).map(|_| true),
+                LoopKind::While { condition, body } => self.in_scope(None, |this| {
+                    if !this.value(condition)?.as_bool()? {
+                        return Ok(false);
+                    }
+                    this.block(body)?;
+                    Ok(true)
+                }),
+            };
+            match result {
+                Ok(true) => {}
+                Ok(false) => return Ok(Value::unit()),
+                Err(Interrupt::Break { label, value }) if targets_us(&label) => return Ok(value),
+                Err(Interrupt::Continue { label }) if targets_us(&label) => {}
+                Err(interrupt) => return Err(interrupt),
+            }
+        }
+    }
+
+    //@ A call is either a call to `print`, to a function of the program, or to the constructor
+    //@ of a tuple struct or tuple variant. The arguments are evaluated from left to right before
+    //@ the call.
+    fn call_expression(&mut self, call: &CallExpression) -> Eval<Value> {
+        let args = call
+            .args
+            .iter()
+            .map(|arg| self.value(arg))
+            .collect::<Eval<Vec<_>>>()?;
+        let mut callee = &*call.callee;
+        while let ExpressionKind::Grouped(inner) = &callee.kind {
+            callee = inner;
+        }
+        let items = self.items;
+        match &callee.kind {
+            ExpressionKind::Path(name) if self.local(name).is_none() => {
+                if let Some(function) = items.functions.get(name) {
+                    Ok(self.call(function, args)?)
+                } else if items.structs.contains_key(name) {
+                    Ok(Value::Struct(name.clone(), positional_fields(args)))
+                } else if name == "print" {
+                    self.print(args)?;
+                    Ok(Value::unit())
+                } else {
+                    Err(runtime_error(format!("cannot find function `{name}`")).into())
+                }
+            }
+            ExpressionKind::VariantPath(path) => Ok(Value::Enum(
+                path.clone(),
+                variant_fields(&path.variant, positional_fields(args)),
+            )),
+            _ => Err(runtime_error(format!(
+                "expected function, found `{}`; we don't support function values",
+                call.callee
+            ))
+            .into()),
+        }
+    }
+
+    fn print(&mut self, args: Vec<Value>) -> Result<(), CompilationError> {
+        let [value] = args.as_slice() else {
+            return Err(runtime_error(format!(
+                "`print` takes 1 argument but {} arguments were supplied",
+                args.len()
+            )));
+        };
+        let printed = match value {
+            Value::Bool(value) => value.to_string(),
+            Value::Int(int) => int.to_string(),
+            Value::Str(string) => string.clone(),
+            _ => {
+                return Err(runtime_error(format!(
+                    "`print` only supports integers, booleans and strings, got {value:?}"
+                )));
+            }
+        };
+        self.output += &printed;
+        self.output.push('\n');
+        Ok(())
+    }
+
+    fn operator(&mut self, operator: &OperatorExpression) -> Eval<Value> {
+        match operator {
+            OperatorExpression::Borrow(borrow) => Ok(Value::Ref(self.place(&borrow.expression)?)),
+            OperatorExpression::Dereference(_) => {
+                unreachable!("dereferences are place expressions")
+            }
+            OperatorExpression::Negation(operator, operand) => {
+                let operand = self.value(operand)?;
+                Ok(negate(*operator, operand)?)
+            }
+            OperatorExpression::Binary(operator, left, right) => {
+                let left = self.value(left)?;
+                let right = self.value(right)?;
+                Ok(binary(*operator, left, right)?)
+            }
+            OperatorExpression::Comparison(operator, left, right) => {
+                let left = self.value(left)?;
+                let right = self.value(right)?;
+                Ok(Value::Bool(compare(*operator, &left, &right)?))
+            }
+            //@ For assignments, the assigned value is evaluated before the place it is assigned
+            //@ to [ref:expr.assign.evaluation-order]; the same goes for compound assignments of
+            //@ primitive types [ref:expr.compound-assign.primitive-order].
+            OperatorExpression::Assignment(place, value) => {
+                let value = self.value(value)?;
+                let place = self.place(place)?;
+                self.write(&place, value)?;
+                Ok(Value::unit())
+            }
+            OperatorExpression::CompoundAssignment(operator, place, value) => {
+                let value = self.value(value)?;
+                let place = self.place(place)?;
+                let current = self.read(&place)?;
+                self.write(&place, binary(*operator, current, value)?)?;
+                Ok(Value::unit())
+            }
+            OperatorExpression::LazyAnd(left, right) => Ok(Value::Bool(
+                self.value(left)?.as_bool()? && self.value(right)?.as_bool()?,
+            )),
+            OperatorExpression::LazyOr(left, right) => Ok(Value::Bool(
+                self.value(left)?.as_bool()? || self.value(right)?.as_bool()?,
+            )),
+        }
+    }
+
+    /// Evaluate `expression` as a place expression. Value expressions are stored in a temporary.
+    fn place(&mut self, expression: &Expression) -> Eval<Place> {
+        self.place_kind(expression)
+            .map_err(|interrupt| interrupt.with_span(expression.span))
+    }
+
+    fn place_kind(&mut self, expression: &Expression) -> Eval<Place> {
+        match &expression.kind {
+            ExpressionKind::Path(name) => match self.local(name) {
+                Some(slot) => Ok(Place::from_slot(slot)),
+                //@ A unit struct is a value, and we don't support function values.
+                None if self.items.structs.contains_key(name) => {
+                    Ok(self.temporary(Value::Struct(name.clone(), vec![])))
+                }
+                None => Err(runtime_error(format!("cannot find local `{name}`")).into()),
+            },
+            //@ Field accesses go through references [ref:expr.field.autoderef].
+            ExpressionKind::TupleIndexing(tuple_indexing) => self.field_place(
+                &tuple_indexing.expression,
+                FieldName::Index(tuple_indexing.index),
+            ),
+            ExpressionKind::Field(field) => {
+                self.field_place(&field.expression, FieldName::Named(field.field.clone()))
+            }
+            ExpressionKind::EnumProjection(projection) => {
+                let place = self.place(&projection.expression)?;
+                Ok(place.project(Projection::VariantField(
+                    projection.variant.variant.clone(),
+                    projection.field.clone(),
+                )))
+            }
+            ExpressionKind::Discriminant(discriminant) => {
+                let place = self.place(&discriminant.expression)?;
+                Ok(place.project(Projection::Discriminant))
+            }
+            ExpressionKind::Operator(OperatorExpression::Dereference(dereference)) => {
+                match self.value(&dereference.expression)? {
+                    Value::Ref(place) => Ok(place),
+                    value => Err(runtime_error(format!("cannot dereference {value:?}")).into()),
+                }
+            }
+            ExpressionKind::Grouped(inner) => self.place(inner),
+            ExpressionKind::Virtual(VirtualExpression::PlaceToValueCoercion(place)) => {
+                let value = self.value(place)?;
+                Ok(self.temporary(value))
+            }
+            ExpressionKind::Virtual(VirtualExpression::ValueToPlaceCoercion(value)) => {
+                let value = self.value(value)?;
+                Ok(self.temporary(value))
+            }
+            //@ A value expression in a place context gets stored in a temporary
+            //@ [ref:expr.place-value.temporary].
+            _ => {
+                let value = self.value_kind(expression)?;
+                Ok(self.temporary(value))
+            }
+        }
+    }
+
+    //@ Until type checking resolves them, enum projections look like two nested field accesses:
+    //@ `$place.$name.$field` is one if `$place` holds an enum with a `$name` variant.
+    fn field_place(&mut self, base: &Expression, field: FieldName) -> Eval<Place> {
+        let place = match &base.kind {
+            ExpressionKind::Field(variant_access) if base.attrs.is_empty() => {
+                let place = self.place(&variant_access.expression)?;
+                let place = self.autoderef(place)?;
+                if let Value::Enum(path, _) = self.read(&place)?
+                    && let Some(adt) = self.items.enums.get(&path.enum_name)
+                    && adt.variant(&variant_access.field).is_some()
+                {
+                    let projection = Projection::VariantField(variant_access.field.clone(), field);
+                    return Ok(place.project(projection));
+                }
+                place.project(Projection::Field(FieldName::Named(
+                    variant_access.field.clone(),
+                )))
+            }
+            _ => self.place(base)?,
+        };
+        let place = self.autoderef(place)?;
+        Ok(place.project(Projection::Field(field)))
+    }
+
+    fn autoderef(&self, mut place: Place) -> Result<Place, CompilationError> {
+        while let Value::Ref(pointee) = self.read(&place)? {
+            place = pointee;
+        }
+        Ok(place)
+    }
+
+    fn read(&self, place: &Place) -> Result<Value, CompilationError> {
+        let slot = &self.slots[place.slot];
+        let Some(mut value) = slot.value.as_ref() else {
+            return Err(match &slot.name {
+                Some(name) => runtime_error(format!("use of uninitialized local `{name}`")),
+                None => runtime_error("use of an uninitialized temporary"),
+            });
+        };
+        for projection in &place.projections {
+            value = match projection {
+                Projection::Field(name) => value.field(name)?,
+                Projection::VariantField(variant, name) => value.variant_field(variant, name)?,
+                Projection::Discriminant => {
+                    let Value::Enum(path, _) = value else {
+                        return Err(runtime_error(format!(
+                            "cannot read the discriminant of {value:?}"
+                        )));
+                    };
+                    return Ok(Value::Int(Int::signed(self.items.discriminant(path)?)));
+                }
+            };
+        }
+        Ok(value.clone())
+    }
+
+    fn write(&mut self, place: &Place, value: Value) -> Result<(), CompilationError> {
+        let slot = &mut self.slots[place.slot];
+        let value = match (value, slot.int_type) {
+            (Value::Int(int), Some(ty)) if place.projections.is_empty() && int.ty.is_none() => {
+                Value::Int(int.wrap(ty))
+            }
+            (value, _) => value,
+        };
+        let Some((last, projections)) = place.projections.split_last() else {
+            slot.value = Some(value);
+            return Ok(());
+        };
+        let Some(mut target) = slot.value.as_mut() else {
+            return Err(match &slot.name {
+                Some(name) => runtime_error(format!(
+                    "assignment to a part of the uninitialized local `{name}`"
+                )),
+                None => runtime_error("assignment to a part of an uninitialized temporary"),
+            });
+        };
+        for projection in projections {
+            target = match projection {
+                Projection::Field(name) => target.field_mut(name)?,
+                Projection::VariantField(variant, name) => {
+                    target.variant_field_mut(variant, name)?
+                }
+                Projection::Discriminant => {
+                    return Err(runtime_error("a discriminant has no fields"));
+                }
+            };
+        }
+        match last {
+            Projection::Field(name) => *target.field_mut(name)? = value,
+            //@ Writing a field of a variant initializes it, whatever the current variant is.
+            Projection::VariantField(variant, name) => {
+                let Value::Enum(_, fields) = target else {
+                    return Err(runtime_error(format!(
+                        "cannot project {target:?} to variant `{variant}`, it is not an enum"
+                    )));
+                };
+                let key = (variant.clone(), name.clone());
+                fields.retain(|(field, _)| *field != key);
+                fields.push((key, value));
+            }
+            Projection::Discriminant => {
+                let (Value::Enum(path, _), Value::Int(discriminant)) = (&mut *target, &value)
+                else {
+                    return Err(runtime_error(format!(
+                        "cannot set the discriminant of {target:?} to {value:?}"
+                    )));
+                };
+                path.variant = self.items.variant_name(&path.enum_name, *discriminant)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Place {
+    fn from_slot(slot: usize) -> Self {
+        Place {
+            slot,
+            projections: vec![],
+        }
+    }
+}
+
+impl Interrupt {
+    fn with_span(self, span: Span) -> Self {
+        match self {
+            Interrupt::Error(error) => Interrupt::Error(error.with_span(span)),
+            interrupt => interrupt,
+        }
+    }
+}
+
+fn matches_pattern<'p>(
+    pattern: &'p Pattern,
+    value: &Value,
+    bindings: &mut Vec<(Identifier, &'p Origin, Value)>,
+) -> bool {
+    match (&pattern.kind, value) {
+        (PatternKind::Identifier(name), _) => {
+            bindings.push((name.clone(), &pattern.origin, value.clone()));
+            true
+        }
+        (PatternKind::Wildcard, _) => true,
+        (PatternKind::Literal(literal), _) => {
+            literal_value(literal).is_ok_and(|literal| literal == *value)
+        }
+        (PatternKind::Tuple(patterns), Value::Tuple(elements)) => {
+            patterns.len() == elements.len()
+                && patterns
+                    .iter()
+                    .zip(elements)
+                    .all(|(pattern, element)| matches_pattern(pattern, element, bindings))
+        }
+        (PatternKind::Tuple(_), _) => false,
+        (PatternKind::Variant(variant), Value::Enum(path, _)) => {
+            *path == variant.path
+                && variant.fields.iter().all(|field| {
+                    value
+                        .variant_field(&path.variant, &field.name)
+                        .is_ok_and(|value| matches_pattern(&field.pattern, value, bindings))
+                })
+        }
+        (PatternKind::Variant(_), _) => false,
+    }
+}
+
+fn literal_value(literal: &LiteralExpression) -> Result<Value, CompilationError> {
+    Ok(match literal {
+        LiteralExpression::Bool(value) => Value::Bool(*value),
+        LiteralExpression::String(string) => Value::Str(string.clone()),
+        LiteralExpression::Integer(IntegerLiteral { value, suffix }) => {
+            Value::Int(Int::unsigned(*value).with_type(*suffix))
+        }
+    })
+}
+
+//@ ## Operators
+
+fn negate(operator: NegationOperator, operand: Value) -> Result<Value, CompilationError> {
+    match (operator, operand) {
+        (NegationOperator::Not, Value::Bool(value)) => Ok(Value::Bool(!value)),
+        (NegationOperator::Not, Value::Int(int)) => {
+            let value = match int.ty {
+                Some(ty) => Some(int.with_bits(!int.bits).wrap(ty)),
+                None => int.to_i128().map(|value| Int::signed(!value)),
+            };
+            value
+                .map(Value::Int)
+                .ok_or_else(|| runtime_error(format!("arithmetic overflow computing !{int}")))
+        }
+        (NegationOperator::Neg, Value::Int(int)) => {
+            let value = match int.ty {
+                Some(ty) => Some(int.with_bits(int.bits.wrapping_neg()).wrap(ty)),
+                None if int.is_negative() => (int.bits as i128).checked_neg().map(Int::signed),
+                // `-(1 << 127)` is `i128::MIN`.
+                None => {
+                    (int.bits <= 1 << 127).then(|| Int::signed((int.bits as i128).wrapping_neg()))
+                }
+            };
+            value
+                .map(Value::Int)
+                .ok_or_else(|| runtime_error(format!("arithmetic overflow computing -{int}")))
+        }
+        (operator, operand) => Err(runtime_error(format!(
+            "cannot apply `{}` to {operand:?}",
+            operator.symbol()
+        ))),
+    }
+}
+
+fn binary(operator: BinaryOperator, left: Value, right: Value) -> Result<Value, CompilationError> {
+    let (left, right) = match (left, right) {
+        (Value::Bool(left), Value::Bool(right)) => {
+            return match operator {
+                BinaryOperator::BitAnd => Ok(Value::Bool(left & right)),
+                BinaryOperator::BitOr => Ok(Value::Bool(left | right)),
+                BinaryOperator::BitXor => Ok(Value::Bool(left ^ right)),
+                _ => Err(runtime_error(format!(
+                    "cannot apply `{}` to booleans",
+                    operator.symbol()
+                ))),
+            };
+        }
+        (Value::Int(left), Value::Int(right)) => (left, right),
+        (left, right) => {
+            return Err(runtime_error(format!(
+                "cannot apply `{}` to {left:?} and {right:?}",
+                operator.symbol()
+            )));
+        }
+    };
+    let is_shift = matches!(operator, BinaryOperator::Shl | BinaryOperator::Shr);
+    // The type of a shift is the type of its left operand.
+    let ty = if is_shift {
+        left.ty
+    } else {
+        left.ty.or(right.ty)
+    };
+    let (left, right) = match ty {
+        Some(ty) if !is_shift => (left.wrap(ty), right.wrap(ty)),
+        _ => (left, right),
+    };
+    if matches!(operator, BinaryOperator::Div | BinaryOperator::Rem) && right.bits == 0 {
+        return Err(runtime_error(format!(
+            "attempt to compute the remainder or divide {left} by zero"
+        )));
+    }
+    let value = if is_shift {
+        shift(operator, left, right)
+    } else if ty.is_some() {
+        wrapping(operator, left, right)
+    } else {
+        unbounded(operator, left, right)
+    };
+    let value = value.ok_or_else(|| {
+        runtime_error(format!(
+            "arithmetic overflow computing {left} {} {right}",
+            operator.symbol()
+        ))
+    })?;
+    Ok(Value::Int(value.with_type(ty)))
+}
+
+/// `$left << $right` or `$left >> $right`, if the shift is less than the width of `$left`. The
+/// bits shifted out are lost, whether we know the type or not.
+fn shift(operator: BinaryOperator, left: Int, right: Int) -> Option<Int> {
+    let width = left.ty.map_or(128, |ty| u32::from(ty.size_in_bytes()) * 8);
+    let shift = u32::try_from(right.to_u128()?)
+        .ok()
+        .filter(|shift| *shift < width)?;
+    let bits = match operator {
+        BinaryOperator::Shl => left.bits << shift,
+        _ if left.is_negative() => ((left.bits as i128) >> shift) as u128,
+        _ => left.bits >> shift,
+    };
+    Some(left.with_bits(bits))
+}
+
+/// `$left $operator $right` for two integers of the same type, which wraps around on overflow.
+/// Dividing the smallest signed integer by `-1` still overflows.
+fn wrapping(operator: BinaryOperator, left: Int, right: Int) -> Option<Int> {
+    let (a, b) = (left.bits, right.bits);
+    let bits = match operator {
+        BinaryOperator::Add => a.wrapping_add(b),
+        BinaryOperator::Sub => a.wrapping_sub(b),
+        BinaryOperator::Mul => a.wrapping_mul(b),
+        BinaryOperator::Div | BinaryOperator::Rem if left.signed => {
+            let (a, b) = (a as i128, b as i128);
+            let quotient = a.checked_div(b)?;
+            if Int::signed(quotient).with_type(left.ty) != Int::signed(quotient) {
+                return None;
+            }
+            match operator {
+                BinaryOperator::Div => quotient as u128,
+                _ => (a % b) as u128,
+            }
+        }
+        BinaryOperator::Div => a / b,
+        BinaryOperator::Rem => a % b,
+        BinaryOperator::BitAnd => a & b,
+        BinaryOperator::BitOr => a | b,
+        BinaryOperator::BitXor => a ^ b,
+        BinaryOperator::Shl | BinaryOperator::Shr => unreachable!("shifts don't wrap"),
+    };
+    Some(left.with_bits(bits))
+}
+
+/// `$left $operator $right` for integers whose type we don't know, if the result fits in an
+/// `i128` or a `u128`.
+fn unbounded(operator: BinaryOperator, left: Int, right: Int) -> Option<Int> {
+    let signed = || {
+        let (a, b) = (left.to_i128()?, right.to_i128()?);
+        match operator {
+            BinaryOperator::Add => a.checked_add(b),
+            BinaryOperator::Sub => a.checked_sub(b),
+            BinaryOperator::Mul => a.checked_mul(b),
+            BinaryOperator::Div => a.checked_div(b),
+            BinaryOperator::Rem => a.checked_rem(b),
+            BinaryOperator::BitAnd => Some(a & b),
+            BinaryOperator::BitOr => Some(a | b),
+            BinaryOperator::BitXor => Some(a ^ b),
+            BinaryOperator::Shl | BinaryOperator::Shr => unreachable!("shifts are bounded"),
+        }
+    };
+    let unsigned = || {
+        let (a, b) = (left.to_u128()?, right.to_u128()?);
+        match operator {
+            BinaryOperator::Add => a.checked_add(b),
+            BinaryOperator::Sub => a.checked_sub(b),
+            BinaryOperator::Mul => a.checked_mul(b),
+            BinaryOperator::Div => a.checked_div(b),
+            BinaryOperator::Rem => a.checked_rem(b),
+            BinaryOperator::BitAnd => Some(a & b),
+            BinaryOperator::BitOr => Some(a | b),
+            BinaryOperator::BitXor => Some(a ^ b),
+            BinaryOperator::Shl | BinaryOperator::Shr => unreachable!("shifts are bounded"),
+        }
+    };
+    signed()
+        .map(Int::signed)
+        .or_else(|| unsigned().map(Int::unsigned))
+}
+
+fn compare(
+    operator: ComparisonOperator,
+    left: &Value,
+    right: &Value,
+) -> Result<bool, CompilationError> {
+    let ordering = match (left, right) {
+        (Value::Int(left), Value::Int(right)) => left.cmp_value(*right),
+        (Value::Bool(left), Value::Bool(right)) => left.cmp(right),
+        _ => {
+            return match operator {
+                ComparisonOperator::Eq => Ok(left == right),
+                ComparisonOperator::Ne => Ok(left != right),
+                _ => Err(runtime_error(format!(
+                    "cannot compare {left:?} and {right:?} with `{}`",
+                    operator.symbol()
+                ))),
+            };
+        }
+    };
+    Ok(match operator {
+        ComparisonOperator::Eq => ordering.is_eq(),
+        ComparisonOperator::Ne => ordering.is_ne(),
+        ComparisonOperator::Lt => ordering.is_lt(),
+        ComparisonOperator::Gt => ordering.is_gt(),
+        ComparisonOperator::Le => ordering.is_le(),
+        ComparisonOperator::Ge => ordering.is_ge(),
+    })
+}
@@ -0,0 +1,109 @@
+//@ # Loop Desugaring
+//@
+//@ `for` and `while` loops are desugared into a conditionless `loop`:
+//@ ```rust,example
+//@ for $pat in $iter {
+//@     $loop_body
+//@ }
+//@
+//@ // becomes
+//@ {
+//@     let mut iter = IntoIterator::into_iter($iter);
+//@     while let Some($pat) = iter.next() {
+//@         $loop_body
+//@     }
+//@ }
+//@ ```
+//@
+//@ And then:
+//@ ```rust,example
+//@ while $condition {
+//@     $loop_body
+//@ }
+//@
+//@ // becomes
+//@ loop {
+//@     if $condition {
+//@         $loop_body
+//@     } else {
+//@         break;
+//@     }
+//@ }
+//@ ```
+//@
+//@ > The rest of this section is a work-in-progress experiment about making the book executable.
+//@
+//@ We don't support `for` loops yet, so only the second step is implemented here. It also handles
+//@ `while let` and let chains, since they are just conditions of the resulting `if`.
+//@ The loop keeps its label, if any: a `break` in the loop body refers to the same loop before and
+//@ after the desugaring, and the `break` we add exits the innermost loop, which is this one.
+use crate::desugarings::*; //#
+use crate::interactive_example; //#
+
+interactive_example! {
+    desugar_loops,
+    fn main() {
+        let x: bool = true;
+        'outer: while x {
+            x = false;
+        }
+    }
+}
+pub fn desugar_loops(program: &mut Program) -> Result<(), CompilationError> {
+    program.visit_all_mut_infallible(|expression: &mut Expression| {
+        if let ExpressionKind::Loop(loop_expression) = &mut expression.kind
+            && desugar_while(loop_expression, &expression.origin)
+        {
+            expression.origin = expression.origin.derive();
+        }
+    });
+    Ok(())
+}
+
+/// Desugar this loop if it is a `while` loop, making the new nodes from the node with origin
+/// `origin`. Returns whether it was one.
+fn desugar_while(loop_expression: &mut LoopExpression, origin: &Origin) -> bool {
+    let LoopKind::While { condition, body } = &mut loop_expression.kind else {
+        return false;
+    };
+    let condition = std::mem::replace(
+        &mut **condition,
+        Expression::new(ExpressionKind::Tuple(vec![])),
+    );
+    let body = std::mem::replace(body, empty_block(origin));
+    let body = Expression {
+        origin: body.origin.derive(),
+        ..body.into()
+    };
+    let exit = BlockExpression {
+        statements: vec![Statement::Expr(Expression {
+            origin: origin.derive(),
+            ..Expression::new(ExpressionKind::Break(BreakExpression {
+                label: None,
+                value: None,
+            }))
+        })],
+        ..empty_block(origin)
+    };
+    let if_expression = Expression {
+        origin: origin.derive(),
+        ..Expression::new(ExpressionKind::If(IfExpression {
+            condition: Box::new(condition),
+            then_branch: Box::new(body),
+            else_branch: Some(Box::new(Expression {
+                origin: origin.derive(),
+                ..exit.into()
+            })),
+        }))
+    };
+    loop_expression.kind = LoopKind::Infinite(BlockExpression {
+        statements: vec![Statement::Expr(if_expression)],
+        ..empty_block(origin)
+    });
+    true
+}
+
+/// An empty block made from the node with origin `source`.
+fn empty_block(source: &Origin) -> BlockExpression {
+    BlockExpression::derived_from(source, vec![], None)
+}
@@ -0,0 +1,152 @@
+//@ # Match Desugaring
+//@
+//@ We can now simply transform:
+//@ ```rust,example
+//@ match $place {
+//@     $pat1 if $guard1 => $arm1,
+//@     $pat2 if $guard2 => $arm2,
+//@     $pat3 => $arm3,
+//@ }
+//@ ```
+//@ into:
+//@ ```rust,example
+//@ if let $pat1 = $place && $guard1 {
+//@     $arm1
+//@ } else if let $pat2 = $place && $guard2 {
+//@     $arm2
+//@ } else if let $pat3 = $place {
+//@     $arm3
+//@ } else {
+//@     unsafe { core::hint::unreachable_unchecked() }
+//@ }
+//@ ```
+//@
+//@ This is valid because 1. the scrutinee of the match has been turned into a side-effect-less place
+//@ expression, and 2. we've dealt with any trickiness around guards, either related to or-patterns
+//@ or to bindings.
+//@
+//@ If there are no arms, we emit:
+//@ ```rust,example
+//@ let _ = $place;
+//@ unsafe { core::hint::unreachable_unchecked() }
+//@ ```
+//@
+//@ At the end of this step the only remaining branching and pattern construct is `if let else`.
+//@
+//@ > The rest of this section is a work-in-progress experiment about making the book executable.
+//@
+//@ None of the previous pattern steps are implemented yet, so patterns and guards are copied
+//@ unchanged, and [Pattern Unnesting](pattern-unnesting.md.rs) then turns the `if let`s into
+//@ discriminant and literal comparisons. We run this step right after [Expression
+//@ Unnesting](expr-unnesting.md.rs), which already turned the scrutinee into a side-effect-less
+//@ place expression. We can't express
+//@ `unsafe { core::hint::unreachable_unchecked() }` yet, so we use the `unreachable_unchecked!()`
+//@ [virtual expression](../language/expressions/virtual-exprs.md.rs) instead.
+use crate::desugarings::*; //#
+use crate::interactive_example; //#
+use std::collections::HashMap; //#
+
+interactive_example! {
+    desugar_matches,
+    fn main() {
+        let x = (true, false);
+        match x {
+            (true, b) if b => print(1),
+            (_, false) => print(2),
+            _ => {}
+        }
+    }
+}
+pub fn desugar_matches(program: &mut Program) -> Result<(), CompilationError> {
+    let types = type_checking::node_types(program)?;
+    program.visit_all_mut_infallible(|expression: &mut Expression| {
+        if let ExpressionKind::Match(match_expression) = &mut expression.kind {
+            let match_expression = std::mem::replace(
+                match_expression,
+                MatchExpression {
+                    scrutinee: Box::new(Expression::new(ExpressionKind::Tuple(vec![]))),
+                    arms: vec![],
+                },
+            );
+            expression.kind = desugar_match(match_expression, &expression.origin, &types);
+            expression.origin = expression.origin.derive();
+        }
+    });
+    Ok(())
+}
+
+/// Desugar the `match` with origin `origin`. Each mention of the scrutinee is a copy of it.
+/// `types` are the types of the nodes of the program.
+fn desugar_match(
+    match_expression: MatchExpression,
+    origin: &Origin,
+    types: &HashMap<NodeId, Type>,
+) -> ExpressionKind {
+    let place = *match_expression.scrutinee;
+    let unreachable = Expression {
+        origin: origin.derive(),
+        ..Expression::new(ExpressionKind::Virtual(VirtualExpression::Unreachable))
+    };
+    if match_expression.arms.is_empty() {
+        let mut ty = types.get(&place.origin.id).cloned();
+        ty.visit_all_mut_infallible(|ty: &mut Type| ty.origin = origin.derive());
+        let statement = Statement::Let {
+            attrs: vec![],
+            scope: None,
+            pattern: Pattern::derived_from(origin, PatternKind::Wildcard),
+            ty,
+            initial_value: Some(place),
+            else_branch: None,
+            span: Span::dummy(),
+            origin: origin.derive(),
+        };
+        return ExpressionKind::Block(BlockExpression {
+            statements: vec![statement],
+            ..block_with_tail(unreachable, origin)
+        });
+    }
+    let mut desugared = Expression {
+        origin: origin.derive(),
+        ..block_with_tail(unreachable, origin).into()
+    };
+    for arm in match_expression.arms.into_iter().rev() {
+        let mut condition = Expression {
+            origin: origin.derive(),
+            ..Expression::new(ExpressionKind::Let(LetExpression {
+                pattern: arm.pattern,
+                scrutinee: Box::new(place.derived_copy()),
+            }))
+        };
+        if let Some(guard) = arm.guard {
+            condition = Expression {
+                origin: origin.derive(),
+                ..LetExpression::and(condition, *guard)
+            };
+        }
+        let then_branch = match arm.body.kind {
+            ExpressionKind::Block(_) if arm.body.attrs.is_empty() => *arm.body,
+            _ => {
+                let (span, body_origin) = (arm.body.span, arm.body.origin.clone());
+                Expression {
+                    span,
+                    origin: body_origin.derive(),
+                    ..block_with_tail(*arm.body, &body_origin).into()
+                }
+            }
+        };
+        desugared = Expression {
+            origin: origin.derive(),
+            ..Expression::new(ExpressionKind::If(IfExpression {
+                condition: Box::new(condition),
+                then_branch: Box::new(then_branch),
+                else_branch: Some(Box::new(desugared)),
+            }))
+        };
+    }
+    desugared.kind
+}
+
+/// A block made from the node with origin `source`.
+fn block_with_tail(tail: Expression, source: &Origin) -> BlockExpression {
+    BlockExpression::derived_from(source, vec![], Some(tail))
+}
@@ -15,7 +15,7 @@ use std::{
     rc::Rc,
 };
 
-use crate::{CompilationError, language};
+use crate::{CompilationError, Diagnostic, language};
 use minirust_rs::{
     lang as mini,
     libspecr::{
@@ -34,18 +34,24 @@ type MiniMemory = memory::BasicMemory<x86_64>;
 pub fn translate_to_minirust(
     program: &language::Program,
 ) -> Result<mini::Program, CompilationError> {
+    let adts = collect_adts(program)?;
     let function_names = collect_function_names(program)?;
     let main_name = *function_names
-        .get("main")
+        .get(&language::Identifier::new("main"))
         .ok_or_else(|| minirust_error("MiniRust runner needs a `main` function"))?;
     let mut globals = Map::new();
     let mut next_global = 0;
     let mut functions = Map::new();
     for item in &program.items {
-        let language::ItemKind::Function(function) = &item.kind;
+        let language::ItemKind::Function(function) = &item.kind else {
+            continue;
+        };
         let name = function_names[&function.name];
-        let mut translator = Translator::new(&function_names, &mut globals, &mut next_global);
-        let function = translator.translate_function(function, name == main_name)?;
+        let mut translator =
+            Translator::new(&function_names, &adts, &mut globals, &mut next_global);
+        let function = translator
+            .translate_function(function, name == main_name)
+            .map_err(|error| error.with_span(item.span))?;
         functions.insert(name, function);
     }
 
@@ -116,11 +122,12 @@ impl GcCompat for SharedOutput {
 
 struct Translator<'a> {
     globals: &'a mut Map<mini::GlobalName, mini::Global>,
-    function_names: &'a BTreeMap<String, mini::FnName>,
+    function_names: &'a BTreeMap<language::Identifier, mini::FnName>,
+    adts: &'a Adts,
     locals: Map<mini::LocalName, mini::Type>,
     args: Vec<mini::LocalName>,
-    local_names: BTreeMap<String, mini::LocalName>,
-    source_local_types: BTreeMap<String, language::Type>,
+    local_names: BTreeMap<language::Identifier, mini::LocalName>,
+    source_local_types: BTreeMap<language::Identifier, language::Type>,
     blocks: Map<mini::BbName, mini::BasicBlock>,
     current_block: mini::BbName,
     current_statements: Vec<mini::Statement>,
@@ -132,7 +139,8 @@ struct Translator<'a> {
 
 impl<'a> Translator<'a> {
     fn new(
-        function_names: &'a BTreeMap<String, mini::FnName>,
+        function_names: &'a BTreeMap<language::Identifier, mini::FnName>,
+        adts: &'a Adts,
         globals: &'a mut Map<mini::GlobalName, mini::Global>,
         next_global: &'a mut u32,
     ) -> Self {
@@ -143,6 +151,7 @@ impl<'a> Translator<'a> {
         Self {
             globals,
             function_names,
+            adts,
             locals,
             args: Vec::new(),
             local_names: BTreeMap::new(),
@@ -205,7 +214,7 @@ impl<'a> Translator<'a> {
     ) -> Result<(), CompilationError> {
         match return_type {
             None => Ok(()),
-            Some(language::Type::Tuple(types)) if types.is_empty() => Ok(()),
+            Some(ty) if *ty == language::Type::mk_unit() => Ok(()),
             Some(ty) => Err(minirust_error(format!(
                 "MiniRust runner only supports functions returning `()`, got `{ty}`"
             ))),
@@ -241,10 +250,10 @@ impl<'a> Translator<'a> {
         }
         let local = mini::LocalName(Name::from_internal(self.next_local));
         self.next_local += 1;
-        self.locals.insert(local, translate_type(ty)?);
+        self.locals.insert(local, translate_type(ty, self.adts)?);
         self.args.push(local);
-        self.local_names.insert(name.to_owned(), local);
-        self.source_local_types.insert(name.to_owned(), ty.clone());
+        self.local_names.insert(name.clone(), local);
+        self.source_local_types.insert(name.clone(), ty.clone());
         Ok(())
     }
 
@@ -253,10 +262,12 @@ impl<'a> Translator<'a> {
         block: &language::BlockExpression,
     ) -> Result<(), CompilationError> {
         for statement in &block.statements {
-            self.translate_statement(statement)?;
+            self.translate_statement(statement)
+                .map_err(|error| error.with_span(statement.span()))?;
         }
         if let Some(tail) = &block.tail {
-            self.translate_tail_expression(tail)?;
+            self.translate_tail_expression(tail)
+                .map_err(|error| error.with_span(tail.span))?;
         }
         Ok(())
     }
@@ -320,9 +331,18 @@ impl<'a> Translator<'a> {
                 language::OperatorExpression::Dereference(_) => Err(minirust_error(
                     "MiniRust runner does not yet support dereference expressions as statements",
                 )),
-                language::OperatorExpression::Add(..) => Err(minirust_error(
-                    "MiniRust runner does not yet support `+` as a statement",
-                )),
+                language::OperatorExpression::CompoundAssignment(operator, target, value) => {
+                    self.translate_compound_assignment(*operator, target, value)
+                }
+                language::OperatorExpression::Negation(..)
+                | language::OperatorExpression::Binary(..)
+                | language::OperatorExpression::Comparison(..) => Err(minirust_error(format!(
+                    "MiniRust runner does not yet support operator expression `{operator}` as a statement"
+                ))),
+                language::OperatorExpression::LazyAnd(..)
+                | language::OperatorExpression::LazyOr(..) => Err(minirust_error(format!(
+                    "MiniRust runner expects lazy boolean operators to be desugared, got `{operator}`"
+                ))),
             },
             language::ExpressionKind::Tuple(elements) if elements.is_empty() => Ok(()),
             language::ExpressionKind::Virtual(virtual_expression) => {
@@ -360,7 +380,17 @@ impl<'a> Translator<'a> {
         &mut self,
         if_expression: &language::IfExpression,
     ) -> Result<(), CompilationError> {
-        let condition = self.translate_bool_value(&if_expression.condition)?;
+        self.translate_if(if_expression, None)
+    }
+
+    /// Translate an `if`. If `destination` is set, the `if` is used as a value and each branch
+    /// ends by assigning its value to `destination`.
+    fn translate_if(
+        &mut self,
+        if_expression: &language::IfExpression,
+        destination: Option<(mini::PlaceExpr, mini::Type)>,
+    ) -> Result<(), CompilationError> {
+        let condition = self.translate_condition(&if_expression.condition)?;
         let then_block = self.fresh_block();
         let else_block = self.fresh_block();
         let join_block = self.fresh_block();
@@ -375,12 +405,12 @@ impl<'a> Translator<'a> {
 
         self.current_block = then_block;
         self.current_statements.clear();
-        self.translate_if_branch(&if_expression.then_branch)?;
+        self.translate_if_branch(&if_expression.then_branch, destination)?;
         self.finish_current_block(mini::Terminator::Goto(join_block));
 
         self.current_block = else_block;
         self.current_statements.clear();
-        self.translate_if_else_branch(if_expression.else_branch.as_deref())?;
+        self.translate_if_else_branch(if_expression.else_branch.as_deref(), destination)?;
         self.finish_current_block(mini::Terminator::Goto(join_block));
 
         self.current_block = join_block;
@@ -388,28 +418,57 @@ impl<'a> Translator<'a> {
         Ok(())
     }
 
+    /// The condition of an `if`. Conditions that are themselves `if`s or blocks, e.g. from the
+    /// lazy boolean desugaring, are computed into a temporary first.
+    fn translate_condition(
+        &mut self,
+        condition: &language::Expression,
+    ) -> Result<mini::ValueExpr, CompilationError> {
+        match &condition.kind {
+            language::ExpressionKind::If(_) | language::ExpressionKind::Block(_) => {
+                let temporary = mini::PlaceExpr::Local(self.fresh_temporary(mini::Type::Bool));
+                self.translate_assignment_to_place(temporary, mini::Type::Bool, condition)?;
+                Ok(mini::ValueExpr::Load {
+                    source: GcCow::new(temporary),
+                })
+            }
+            _ => self.translate_bool_value(condition),
+        }
+    }
+
     fn translate_if_else_branch(
         &mut self,
         else_branch: Option<&language::Expression>,
+        destination: Option<(mini::PlaceExpr, mini::Type)>,
     ) -> Result<(), CompilationError> {
         match else_branch {
             None => Err(minirust_error(
                 "MiniRust runner expects `if` expressions without `else` to be desugared",
             )),
-            Some(branch) => self.translate_if_branch(branch),
+            Some(branch) => self.translate_if_branch(branch, destination),
         }
     }
 
     fn translate_if_branch(
         &mut self,
         branch: &language::Expression,
+        destination: Option<(mini::PlaceExpr, mini::Type)>,
     ) -> Result<(), CompilationError> {
         match &branch.kind {
             language::ExpressionKind::Block(block) if branch.attrs.is_empty() => {
-                self.translate_block(block)
+                let Some((destination, destination_ty)) = destination else {
+                    return self.translate_block(block);
+                };
+                for statement in &block.statements {
+                    self.translate_statement(statement)?;
+                }
+                let tail = block.tail.as_deref().ok_or_else(|| {
+                    minirust_error("MiniRust runner expects a block used as a value to have a tail")
+                })?;
+                self.translate_assignment_to_place(destination, destination_ty, tail)
             }
             language::ExpressionKind::If(if_expression) if branch.attrs.is_empty() => {
-                self.translate_if_statement(if_expression)
+                self.translate_if(if_expression, destination)
             }
             _ => Err(minirust_error(format!(
                 "MiniRust runner expected an `if` branch, got `{branch:?}`"
@@ -417,16 +476,29 @@ impl<'a> Translator<'a> {
         }
     }
 
-    fn translate_let(&mut self, name: &str, ty: &language::Type) -> Result<(), CompilationError> {
+    fn fresh_temporary(&mut self, ty: mini::Type) -> mini::LocalName {
+        let local = mini::LocalName(Name::from_internal(self.next_local));
+        self.next_local += 1;
+        self.locals.insert(local, ty);
+        self.current_statements
+            .push(mini::Statement::StorageLive(local));
+        local
+    }
+
+    fn translate_let(
+        &mut self,
+        name: &language::Identifier,
+        ty: &language::Type,
+    ) -> Result<(), CompilationError> {
         if self.local_names.contains_key(name) {
             return Err(minirust_error(format!("duplicate local `{name}`")));
         }
         let local = mini::LocalName(Name::from_internal(self.next_local));
         self.next_local += 1;
-        let mini_ty = translate_type(ty)?;
+        let mini_ty = translate_type(ty, self.adts)?;
         self.locals.insert(local, mini_ty);
-        self.local_names.insert(name.to_owned(), local);
-        self.source_local_types.insert(name.to_owned(), ty.clone());
+        self.local_names.insert(name.clone(), local);
+        self.source_local_types.insert(name.clone(), ty.clone());
         self.current_statements
             .push(mini::Statement::StorageLive(local));
         Ok(())
@@ -437,16 +509,71 @@ impl<'a> Translator<'a> {
         target: &language::Expression,
         value: &language::Expression,
     ) -> Result<(), CompilationError> {
+        if let language::ExpressionKind::Discriminant(discriminant) = &target.kind {
+            return self.translate_set_discriminant(discriminant, value);
+        }
         let (destination, destination_ty) = self.translate_place(target)?;
         self.translate_assignment_to_place(destination, destination_ty, value)
     }
 
+    /// `a op= b` becomes `a = a op b`. Our places have no side effects so reading `a` again is
+    /// fine.
+    fn translate_compound_assignment(
+        &mut self,
+        operator: language::BinaryOperator,
+        target: &language::Expression,
+        value: &language::Expression,
+    ) -> Result<(), CompilationError> {
+        let (destination, destination_ty) = self.translate_place(target)?;
+        let expected_int = match (operator.is_shift(), destination_ty) {
+            (false, mini::Type::Int(ty)) => Some(ty),
+            _ => None,
+        };
+        let (right, _ty) = self.translate_value_and_type_as(value, expected_int)?;
+        let left = mini::ValueExpr::Load {
+            source: GcCow::new(destination),
+        };
+        let source = mini::ValueExpr::BinOp {
+            operator: translate_binary_operator(operator, destination_ty, target)?,
+            left: GcCow::new(left),
+            right: GcCow::new(right),
+        };
+        self.current_statements.push(mini::Statement::Assign {
+            destination,
+            source,
+        });
+        Ok(())
+    }
+
+    fn translate_set_discriminant(
+        &mut self,
+        discriminant: &language::DiscriminantExpression,
+        value: &language::Expression,
+    ) -> Result<(), CompilationError> {
+        let language::ExpressionKind::DiscriminantOf(path) = &value.kind else {
+            return Err(minirust_error(format!(
+                "MiniRust runner can only set a discriminant to `discriminant_of!(..)`, got `{value}`"
+            )));
+        };
+        let (destination, _ty) = self.translate_place(&discriminant.expression)?;
+        let (value, _variant) = self.variant_layout(path)?;
+        self.current_statements
+            .push(mini::Statement::SetDiscriminant {
+                destination,
+                value: Int::from(value),
+            });
+        Ok(())
+    }
+
     fn translate_assignment_to_place(
         &mut self,
         destination: mini::PlaceExpr,
         destination_ty: mini::Type,
         value: &language::Expression,
     ) -> Result<(), CompilationError> {
+        if let language::ExpressionKind::If(_) | language::ExpressionKind::Block(_) = &value.kind {
+            return self.translate_if_branch(value, Some((destination, destination_ty)));
+        }
         let source = self.translate_value_with_expected_type(value, destination_ty)?;
         self.current_statements.push(mini::Statement::Assign {
             destination,
@@ -483,7 +610,7 @@ impl<'a> Translator<'a> {
 
     fn translate_user_function_call(
         &mut self,
-        name: &str,
+        name: &language::Identifier,
         call: &language::CallExpression,
     ) -> Result<(), CompilationError> {
         let callee = self.function(name)?;
@@ -525,6 +652,28 @@ impl<'a> Translator<'a> {
         expected_ty: mini::Type,
     ) -> Result<mini::ValueExpr, CompilationError> {
         match &expression.kind {
+            language::ExpressionKind::Literal(language::LiteralExpression::Integer(literal))
+                if literal.suffix.is_none() =>
+            {
+                let mini::Type::Int(ty) = expected_ty else {
+                    return self.translate_value(expression);
+                };
+                translate_integer_literal(literal, ty).map(|(value, _ty)| value)
+            }
+            language::ExpressionKind::Operator(operator)
+                if matches!(
+                    **operator,
+                    language::OperatorExpression::Negation(..)
+                        | language::OperatorExpression::Binary(..)
+                ) =>
+            {
+                let expected_int = match expected_ty {
+                    mini::Type::Int(ty) => Some(ty),
+                    _ => None,
+                };
+                self.translate_operator_value_and_type(operator, expected_int)
+                    .map(|(value, _ty)| value)
+            }
             language::ExpressionKind::Tuple(elements) if !elements.is_empty() => {
                 let field_values = elements
                     .iter()
@@ -552,19 +701,10 @@ impl<'a> Translator<'a> {
                 self.translate_string_literal(value),
                 mini::Type::Ptr(str_ref_ptr_type()),
             )),
-            language::ExpressionKind::Literal(language::LiteralExpression::Integer(value)) => {
-                let ty = mini::IntType::usize_ty::<x86_64>();
-                let value = Int::from(*value);
-                if !ty.can_represent(value) {
-                    return Err(minirust_error(format!(
-                        "MiniRust runner only supports integer literals that fit in `usize`, got `{value}`"
-                    )));
-                }
-                let ty = mini::Type::Int(ty);
-                Ok((
-                    mini::ValueExpr::Constant(mini::Constant::Int(value), ty),
-                    ty,
-                ))
+            language::ExpressionKind::Literal(language::LiteralExpression::Integer(literal)) => {
+                // Unsuffixed literals default to `usize` unless we know the expected type.
+                let ty = literal.suffix.unwrap_or(language::IntType::Usize);
+                translate_integer_literal(literal, translate_int_type(ty))
             }
             language::ExpressionKind::Tuple(elements) => {
                 let fields = elements
@@ -604,6 +744,14 @@ impl<'a> Translator<'a> {
             language::ExpressionKind::If(_) => Err(minirust_error(
                 "MiniRust runner does not yet support `if` as a value",
             )),
+            language::ExpressionKind::Loop(_)
+            | language::ExpressionKind::Break(_)
+            | language::ExpressionKind::Continue(_) => {
+                Err(minirust_error("MiniRust runner does not yet support loops"))
+            }
+            language::ExpressionKind::Let(_) | language::ExpressionKind::Match(_) => Err(
+                minirust_error("MiniRust runner does not yet support pattern matching"),
+            ),
             language::ExpressionKind::TupleIndexing(tuple_indexing) => {
                 let (source, ty) = self.translate_tuple_indexing_place(tuple_indexing)?;
                 Ok((
@@ -613,6 +761,48 @@ impl<'a> Translator<'a> {
                     ty,
                 ))
             }
+            language::ExpressionKind::Struct(adt) => self.translate_struct_value_and_type(adt),
+            language::ExpressionKind::VariantPath(path) => Err(minirust_error(format!(
+                "MiniRust runner does not yet support variant constructors as values, got `{path}`"
+            ))),
+            language::ExpressionKind::EnumVariant(variant) => {
+                self.translate_enum_variant_value_and_type(variant)
+            }
+            language::ExpressionKind::Field(field) => {
+                let (source, ty) = self.translate_field_place(field)?;
+                Ok((
+                    mini::ValueExpr::Load {
+                        source: GcCow::new(source),
+                    },
+                    ty,
+                ))
+            }
+            language::ExpressionKind::EnumProjection(projection) => {
+                let (source, ty) = self.translate_enum_projection_place(projection)?;
+                Ok((
+                    mini::ValueExpr::Load {
+                        source: GcCow::new(source),
+                    },
+                    ty,
+                ))
+            }
+            language::ExpressionKind::Discriminant(discriminant) => {
+                let (place, _ty) = self.translate_place(&discriminant.expression)?;
+                Ok((
+                    mini::ValueExpr::GetDiscriminant {
+                        place: GcCow::new(place),
+                    },
+                    mini::Type::Int(discriminant_type()),
+                ))
+            }
+            language::ExpressionKind::DiscriminantOf(path) => {
+                let (discriminant, _variant) = self.variant_layout(path)?;
+                let ty = mini::Type::Int(discriminant_type());
+                Ok((
+                    mini::ValueExpr::Constant(mini::Constant::Int(Int::from(discriminant)), ty),
+                    ty,
+                ))
+            }
             language::ExpressionKind::Operator(operator) => match &**operator {
                 language::OperatorExpression::Borrow(borrow) => {
                     self.translate_borrow_value_and_type(borrow)
@@ -626,8 +816,15 @@ impl<'a> Translator<'a> {
                         ty,
                     ))
                 }
-                language::OperatorExpression::Add(..)
-                | language::OperatorExpression::Assignment(..) => Err(minirust_error(format!(
+                language::OperatorExpression::Negation(..)
+                | language::OperatorExpression::Binary(..)
+                | language::OperatorExpression::Comparison(..) => {
+                    self.translate_operator_value_and_type(operator, None)
+                }
+                language::OperatorExpression::Assignment(..)
+                | language::OperatorExpression::CompoundAssignment(..)
+                | language::OperatorExpression::LazyAnd(..)
+                | language::OperatorExpression::LazyOr(..) => Err(minirust_error(format!(
                     "MiniRust runner does not yet support operator expression `{operator}` as a value"
                 ))),
             },
@@ -637,11 +834,207 @@ impl<'a> Translator<'a> {
         }
     }
 
+    /// Like `translate_value_and_type`, but unsuffixed integer literals get type `expected_int`
+    /// when it is known.
+    fn translate_value_and_type_as(
+        &mut self,
+        expression: &language::Expression,
+        expected_int: Option<mini::IntType>,
+    ) -> Result<(mini::ValueExpr, mini::Type), CompilationError> {
+        match (&expression.kind, expected_int) {
+            (
+                language::ExpressionKind::Literal(language::LiteralExpression::Integer(literal)),
+                Some(ty),
+            ) if literal.suffix.is_none() => translate_integer_literal(literal, ty),
+            (language::ExpressionKind::Operator(operator), _)
+                if matches!(
+                    **operator,
+                    language::OperatorExpression::Negation(..)
+                        | language::OperatorExpression::Binary(..)
+                ) =>
+            {
+                self.translate_operator_value_and_type(operator, expected_int)
+            }
+            _ => self.translate_value_and_type(expression),
+        }
+    }
+
+    /// Arithmetic wraps on overflow, like a release build of rustc would.
+    fn translate_operator_value_and_type(
+        &mut self,
+        operator: &language::OperatorExpression,
+        expected_int: Option<mini::IntType>,
+    ) -> Result<(mini::ValueExpr, mini::Type), CompilationError> {
+        match operator {
+            language::OperatorExpression::Negation(negation, operand_expression) => {
+                // `-128i8` is in range even though `128i8` isn't, so we negate the literal
+                // directly.
+                if let language::NegationOperator::Neg = negation
+                    && let language::ExpressionKind::Literal(language::LiteralExpression::Integer(
+                        literal,
+                    )) = &operand_expression.kind
+                {
+                    let ty = literal
+                        .suffix
+                        .map(translate_int_type)
+                        .or(expected_int)
+                        .unwrap_or_else(|| translate_int_type(language::IntType::Usize));
+                    return translate_negated_integer_literal(literal, ty);
+                }
+                let (operand, ty) =
+                    self.translate_value_and_type_as(operand_expression, expected_int)?;
+                let operator = match (negation, ty) {
+                    (language::NegationOperator::Neg, mini::Type::Int(ty))
+                        if ty.signed == Signedness::Signed =>
+                    {
+                        mini::UnOp::Int(mini::IntUnOp::Neg)
+                    }
+                    (language::NegationOperator::Not, mini::Type::Int(_)) => {
+                        mini::UnOp::Int(mini::IntUnOp::BitNot)
+                    }
+                    (language::NegationOperator::Not, mini::Type::Bool) => {
+                        mini::UnOp::Bool(mini::BoolUnOp::Not)
+                    }
+                    _ => {
+                        return Err(minirust_error(format!(
+                            "MiniRust runner cannot apply `{}` to `{operand_expression}`",
+                            negation.symbol()
+                        )));
+                    }
+                };
+                Ok((
+                    mini::ValueExpr::UnOp {
+                        operator,
+                        operand: GcCow::new(operand),
+                    },
+                    ty,
+                ))
+            }
+            language::OperatorExpression::Binary(binary, left_expression, right_expression) => {
+                let (left, right, ty) = if binary.is_shift() {
+                    // The right operand of a shift can have any integer type.
+                    let (left, ty) =
+                        self.translate_value_and_type_as(left_expression, expected_int)?;
+                    let (right, _ty) = self.translate_value_and_type(right_expression)?;
+                    (left, right, ty)
+                } else {
+                    self.translate_operands(left_expression, right_expression, expected_int)?
+                };
+                Ok((
+                    mini::ValueExpr::BinOp {
+                        operator: translate_binary_operator(*binary, ty, left_expression)?,
+                        left: GcCow::new(left),
+                        right: GcCow::new(right),
+                    },
+                    ty,
+                ))
+            }
+            language::OperatorExpression::Comparison(comparison, left, right) => {
+                let (left, right, _ty) = self.translate_operands(left, right, None)?;
+                Ok((
+                    mini::ValueExpr::BinOp {
+                        operator: mini::BinOp::Rel(translate_comparison_operator(*comparison)),
+                        left: GcCow::new(left),
+                        right: GcCow::new(right),
+                    },
+                    mini::Type::Bool,
+                ))
+            }
+            other => Err(internal_error(format!(
+                "`{other}` is not an arithmetic or comparison expression"
+            ))),
+        }
+    }
+
+    /// Translate two operands that must have the same type. An unsuffixed integer literal takes
+    /// the type of the other operand.
+    fn translate_operands(
+        &mut self,
+        left: &language::Expression,
+        right: &language::Expression,
+        expected_int: Option<mini::IntType>,
+    ) -> Result<(mini::ValueExpr, mini::ValueExpr, mini::Type), CompilationError> {
+        if expected_int.is_none() && is_unsuffixed_integer_literal(left) {
+            let (right, ty) = self.translate_value_and_type(right)?;
+            let (left, _ty) = self.translate_value_and_type_as(left, int_type_of(ty))?;
+            Ok((left, right, ty))
+        } else {
+            let (left, ty) = self.translate_value_and_type_as(left, expected_int)?;
+            let (right, _ty) = self.translate_value_and_type_as(right, int_type_of(ty))?;
+            Ok((left, right, ty))
+        }
+    }
+
+    fn translate_struct_value_and_type(
+        &mut self,
+        adt: &language::StructExpression,
+    ) -> Result<(mini::ValueExpr, mini::Type), CompilationError> {
+        let layout = self.struct_layout(&adt.path)?;
+        let value = self.translate_field_values(&adt.path.to_string(), layout, &adt.fields)?;
+        Ok((value, layout.ty))
+    }
+
+    fn translate_enum_variant_value_and_type(
+        &mut self,
+        variant: &language::EnumVariantExpression,
+    ) -> Result<(mini::ValueExpr, mini::Type), CompilationError> {
+        let enum_ty = self.enum_layout(&variant.path.enum_name)?.ty;
+        let (discriminant, layout) = self.variant_layout(&variant.path)?;
+        let data =
+            self.translate_field_values(&variant.path.to_string(), layout, &variant.fields)?;
+        Ok((
+            mini::ValueExpr::Variant {
+                discriminant: Int::from(discriminant),
+                data: GcCow::new(data),
+                enum_ty,
+            },
+            enum_ty,
+        ))
+    }
+
+    /// The value of the struct or enum variant `name` with the given layout, built from the
+    /// fields of a struct expression.
+    fn translate_field_values(
+        &mut self,
+        name: &str,
+        layout: &StructLayout,
+        fields: &[language::StructExprField],
+    ) -> Result<mini::ValueExpr, CompilationError> {
+        for field in fields {
+            if !layout
+                .fields
+                .iter()
+                .any(|(candidate, _)| *candidate == field.name)
+            {
+                return Err(minirust_error(format!(
+                    "`{name}` has no field named `{}`",
+                    field.name
+                )));
+            }
+        }
+        // MiniRust evaluates the fields in declaration order. That's fine since our operands
+        // have no side effects by the time they get here.
+        let mut field_values = Vec::new();
+        for (field_name, field_ty) in &layout.fields {
+            let mut values = fields.iter().filter(|field| field.name == *field_name);
+            let (Some(field), None) = (values.next(), values.next()) else {
+                return Err(minirust_error(format!(
+                    "struct expression `{name}` must initialize field `{field_name}` exactly once"
+                )));
+            };
+            field_values.push(self.translate_value_with_expected_type(&field.value, *field_ty)?);
+        }
+        Ok(mini::ValueExpr::Tuple(
+            field_values.into_iter().collect(),
+            layout.ty,
+        ))
+    }
+
     fn translate_virtual_expression_statement(
         &mut self,
         virtual_expression: &language::VirtualExpression,
     ) -> Result<(), CompilationError> {
-        self.translate_value(Self::virtual_expression_inner(virtual_expression))?;
+        self.translate_value(Self::virtual_expression_inner(virtual_expression)?)?;
         Ok(())
     }
 
@@ -649,29 +1042,32 @@ impl<'a> Translator<'a> {
         &mut self,
         virtual_expression: &language::VirtualExpression,
     ) -> Result<(mini::ValueExpr, mini::Type), CompilationError> {
-        self.translate_value_and_type(Self::virtual_expression_inner(virtual_expression))
+        self.translate_value_and_type(Self::virtual_expression_inner(virtual_expression)?)
     }
 
     fn translate_virtual_place(
         &mut self,
         virtual_expression: &language::VirtualExpression,
     ) -> Result<(mini::PlaceExpr, mini::Type), CompilationError> {
-        self.translate_place(Self::virtual_expression_inner(virtual_expression))
+        self.translate_place(Self::virtual_expression_inner(virtual_expression)?)
     }
 
     fn translate_virtual_pointer_value(
         &mut self,
         virtual_expression: &language::VirtualExpression,
     ) -> Result<(mini::ValueExpr, mini::Type), CompilationError> {
-        self.translate_pointer_value(Self::virtual_expression_inner(virtual_expression))
+        self.translate_pointer_value(Self::virtual_expression_inner(virtual_expression)?)
     }
 
     fn virtual_expression_inner(
         virtual_expression: &language::VirtualExpression,
-    ) -> &language::Expression {
+    ) -> Result<&language::Expression, CompilationError> {
         match virtual_expression {
             language::VirtualExpression::ValueToPlaceCoercion(expression)
-            | language::VirtualExpression::PlaceToValueCoercion(expression) => expression,
+            | language::VirtualExpression::PlaceToValueCoercion(expression) => Ok(expression),
+            language::VirtualExpression::Unreachable => Err(minirust_error(
+                "MiniRust runner does not yet support `unreachable_unchecked!()`",
+            )),
         }
     }
 
@@ -730,6 +1126,10 @@ impl<'a> Translator<'a> {
             language::ExpressionKind::TupleIndexing(tuple_indexing) => {
                 self.translate_tuple_indexing_place(tuple_indexing)
             }
+            language::ExpressionKind::Field(field) => self.translate_field_place(field),
+            language::ExpressionKind::EnumProjection(projection) => {
+                self.translate_enum_projection_place(projection)
+            }
             language::ExpressionKind::Operator(operator) => match &**operator {
                 language::OperatorExpression::Dereference(dereference) => {
                     self.translate_dereference_place(dereference)
@@ -792,6 +1192,158 @@ impl<'a> Translator<'a> {
         ))
     }
 
+    fn translate_field_place(
+        &mut self,
+        field: &language::FieldExpression,
+    ) -> Result<(mini::PlaceExpr, mini::Type), CompilationError> {
+        let language::TypeKind::Path(path, _) = self.source_type(&field.expression)?.kind else {
+            return Err(minirust_error(format!(
+                "MiniRust runner can only access fields of structs, got `{}`",
+                field.expression
+            )));
+        };
+        let layout = self.struct_layout(&path)?;
+        let name = language::FieldName::Named(field.field.clone());
+        let Some(index) = layout
+            .fields
+            .iter()
+            .position(|(candidate, _)| *candidate == name)
+        else {
+            return Err(minirust_error(format!(
+                "struct `{path}` has no field named `{name}`"
+            )));
+        };
+        let field_ty = layout.fields[index].1;
+        let (root, _root_ty) = self.translate_place(&field.expression)?;
+        Ok((
+            mini::PlaceExpr::Field {
+                root: GcCow::new(root),
+                field: Int::from(index),
+            },
+            field_ty,
+        ))
+    }
+
+    fn translate_enum_projection_place(
+        &mut self,
+        projection: &language::EnumProjectionExpression,
+    ) -> Result<(mini::PlaceExpr, mini::Type), CompilationError> {
+        let (discriminant, layout) = self.variant_layout(&projection.variant)?;
+        let Some(index) = layout
+            .fields
+            .iter()
+            .position(|(candidate, _)| *candidate == projection.field)
+        else {
+            return Err(minirust_error(format!(
+                "variant `{}` has no field named `{}`",
+                projection.variant, projection.field
+            )));
+        };
+        let field_ty = layout.fields[index].1;
+        let (root, _root_ty) = self.translate_place(&projection.expression)?;
+        let variant = mini::PlaceExpr::Downcast {
+            root: GcCow::new(root),
+            discriminant: Int::from(discriminant),
+        };
+        Ok((
+            mini::PlaceExpr::Field {
+                root: GcCow::new(variant),
+                field: Int::from(index),
+            },
+            field_ty,
+        ))
+    }
+
+    /// The source-level type of an expression, for the expressions whose type matters to the
+    /// translation (e.g. to know which struct a field access refers to).
+    fn source_type(
+        &self,
+        expression: &language::Expression,
+    ) -> Result<language::Type, CompilationError> {
+        match &expression.kind {
+            language::ExpressionKind::Path(path) => self
+                .source_local_type(Self::simple_path_name(path)?)
+                .cloned(),
+            language::ExpressionKind::Struct(adt) => Ok(language::Type::new(
+                language::TypeKind::Path(adt.path.clone(), language::Span::dummy()),
+            )),
+            language::ExpressionKind::EnumVariant(variant) => Ok(language::Type::new(
+                language::TypeKind::Path(variant.path.enum_name.clone(), language::Span::dummy()),
+            )),
+            language::ExpressionKind::EnumProjection(projection) => {
+                let (_discriminant, layout) = self.variant_layout(&projection.variant)?;
+                layout
+                    .source_field_type(&projection.field)
+                    .ok_or_else(|| {
+                        minirust_error(format!(
+                            "variant `{}` has no field named `{}`",
+                            projection.variant, projection.field
+                        ))
+                    })
+                    .cloned()
+            }
+            language::ExpressionKind::Field(field) => {
+                let language::TypeKind::Path(path, _) = self.source_type(&field.expression)?.kind
+                else {
+                    return Err(minirust_error(format!(
+                        "MiniRust runner can only access fields of structs, got `{}`",
+                        field.expression
+                    )));
+                };
+                let adt = self.struct_layout(&path)?;
+                let name = language::FieldName::Named(field.field.clone());
+                adt.source_field_type(&name)
+                    .ok_or_else(|| {
+                        minirust_error(format!("struct `{path}` has no field named `{name}`"))
+                    })
+                    .cloned()
+            }
+            language::ExpressionKind::TupleIndexing(tuple_indexing) => {
+                let index = tuple_indexing.index;
+                let field_ty = match self.source_type(&tuple_indexing.expression)?.kind {
+                    language::TypeKind::Tuple(types) => types.get(index).cloned(),
+                    language::TypeKind::Path(path, _) => self
+                        .struct_layout(&path)?
+                        .source_field_type(&language::FieldName::Index(index))
+                        .cloned(),
+                    _ => None,
+                };
+                field_ty.ok_or_else(|| {
+                    minirust_error(format!("tuple index `{index}` is out of bounds"))
+                })
+            }
+            language::ExpressionKind::Operator(operator) => match &**operator {
+                language::OperatorExpression::Borrow(borrow) => {
+                    Ok(language::Type::new(language::TypeKind::Ref(
+                        None,
+                        borrow.mutability,
+                        Box::new(self.source_type(&borrow.expression)?),
+                    )))
+                }
+                language::OperatorExpression::Dereference(dereference) => {
+                    match self.source_type(&dereference.expression)? {
+                        language::Type {
+                            kind: language::TypeKind::Ref(_, _, pointee_ty),
+                            ..
+                        } => Ok(*pointee_ty),
+                        ty => Err(minirust_error(format!(
+                            "MiniRust runner can only dereference references, got `{ty}`"
+                        ))),
+                    }
+                }
+                other => Err(minirust_error(format!(
+                    "MiniRust runner cannot compute the type of `{other}`"
+                ))),
+            },
+            language::ExpressionKind::Virtual(virtual_expression) => {
+                self.source_type(Self::virtual_expression_inner(virtual_expression)?)
+            }
+            other => Err(minirust_error(format!(
+                "MiniRust runner cannot compute the type of `{other:?}`"
+            ))),
+        }
+    }
+
     fn translate_pointer_value(
         &mut self,
         expression: &language::Expression,
@@ -802,7 +1354,8 @@ impl<'a> Translator<'a> {
             )),
             language::ExpressionKind::Path(path) => {
                 let name = Self::simple_path_name(path)?;
-                let language::Type::Ref(_, _, pointee_ty) = self.source_local_type(name)? else {
+                let language::TypeKind::Ref(_, _, pointee_ty) = &self.source_local_type(name)?.kind
+                else {
                     return Err(minirust_error(format!(
                         "MiniRust runner can only dereference references, got `{name}`"
                     )));
@@ -811,7 +1364,24 @@ impl<'a> Translator<'a> {
                     mini::ValueExpr::Load {
                         source: GcCow::new(mini::PlaceExpr::Local(self.local(name)?)),
                     },
-                    translate_type(pointee_ty)?,
+                    translate_type(pointee_ty, self.adts)?,
+                ))
+            }
+            language::ExpressionKind::Field(_)
+            | language::ExpressionKind::TupleIndexing(_)
+            | language::ExpressionKind::EnumProjection(_) => {
+                let language::TypeKind::Ref(_, _, pointee_ty) = self.source_type(expression)?.kind
+                else {
+                    return Err(minirust_error(format!(
+                        "MiniRust runner can only dereference references, got `{expression}`"
+                    )));
+                };
+                let (source, _ty) = self.translate_place(expression)?;
+                Ok((
+                    mini::ValueExpr::Load {
+                        source: GcCow::new(source),
+                    },
+                    translate_type(&pointee_ty, self.adts)?,
                 ))
             }
             language::ExpressionKind::Operator(operator) => match &**operator {
@@ -831,7 +1401,9 @@ impl<'a> Translator<'a> {
         }
     }
 
-    fn expression_path(expression: &language::Expression) -> Result<&str, CompilationError> {
+    fn expression_path(
+        expression: &language::Expression,
+    ) -> Result<&language::Identifier, CompilationError> {
         match &expression.kind {
             language::ExpressionKind::Grouped(_) => Err(minirust_error(
                 "MiniRust runner expects grouped expressions to be desugared",
@@ -843,14 +1415,21 @@ impl<'a> Translator<'a> {
         }
     }
 
-    fn simple_path_name(path: &language::PathExpression) -> Result<&str, CompilationError> {
+    fn simple_path_name(
+        path: &language::PathExpression,
+    ) -> Result<&language::Identifier, CompilationError> {
         Ok(path)
     }
 
-    fn pattern_name(pattern: &language::Pattern) -> Result<&str, CompilationError> {
-        match pattern {
-            language::Pattern::Identifier(name) => Ok(name),
-            language::Pattern::Wildcard => {
+    fn pattern_name(
+        pattern: &language::Pattern,
+    ) -> Result<&language::Identifier, CompilationError> {
+        match &pattern.kind {
+            language::PatternKind::Identifier(name) => Ok(name),
+            language::PatternKind::Wildcard
+            | language::PatternKind::Literal(_)
+            | language::PatternKind::Tuple(_)
+            | language::PatternKind::Variant(_) => {
                 Err(minirust_error("MiniRust runner needs named `let` bindings"))
             }
         }
@@ -908,7 +1487,7 @@ impl<'a> Translator<'a> {
         );
     }
 
-    fn local(&self, name: &str) -> Result<mini::LocalName, CompilationError> {
+    fn local(&self, name: &language::Identifier) -> Result<mini::LocalName, CompilationError> {
         self.local_names
             .get(name)
             .copied()
@@ -921,13 +1500,43 @@ impl<'a> Translator<'a> {
             .ok_or_else(|| minirust_error(format!("unknown MiniRust local `{local:?}`")))
     }
 
-    fn source_local_type(&self, name: &str) -> Result<&language::Type, CompilationError> {
+    fn source_local_type(
+        &self,
+        name: &language::Identifier,
+    ) -> Result<&language::Type, CompilationError> {
         self.source_local_types
             .get(name)
             .ok_or_else(|| minirust_error(format!("unknown local `{name}`")))
     }
 
-    fn function(&self, name: &str) -> Result<mini::FnName, CompilationError> {
+    fn struct_layout(
+        &self,
+        name: &language::Identifier,
+    ) -> Result<&'a StructLayout, CompilationError> {
+        self.adts
+            .structs
+            .get(name)
+            .ok_or_else(|| minirust_error(format!("unknown struct `{name}`")))
+    }
+
+    fn enum_layout(&self, name: &language::Identifier) -> Result<&'a EnumLayout, CompilationError> {
+        self.adts
+            .enums
+            .get(name)
+            .ok_or_else(|| minirust_error(format!("unknown enum `{name}`")))
+    }
+
+    /// The layout of a variant, along with its discriminant.
+    fn variant_layout(
+        &self,
+        path: &language::VariantPath,
+    ) -> Result<(usize, &'a StructLayout), CompilationError> {
+        self.enum_layout(&path.enum_name)?
+            .variant(&path.variant)
+            .ok_or_else(|| minirust_error(format!("unknown variant `{path}`")))
+    }
+
+    fn function(&self, name: &language::Identifier) -> Result<mini::FnName, CompilationError> {
         self.function_names
             .get(name)
             .copied()
@@ -937,10 +1546,12 @@ impl<'a> Translator<'a> {
 
 fn collect_function_names(
     program: &language::Program,
-) -> Result<BTreeMap<String, mini::FnName>, CompilationError> {
+) -> Result<BTreeMap<language::Identifier, mini::FnName>, CompilationError> {
     let mut names = BTreeMap::new();
     for (index, item) in program.items.iter().enumerate() {
-        let language::ItemKind::Function(function) = &item.kind;
+        let language::ItemKind::Function(function) = &item.kind else {
+            continue;
+        };
         if names
             .insert(
                 function.name.clone(),
@@ -957,17 +1568,326 @@ fn collect_function_names(
     Ok(names)
 }
 
-fn translate_type(ty: &language::Type) -> Result<mini::Type, CompilationError> {
+/// The struct and enum definitions of the program, by name.
+#[derive(Default)]
+struct Adts {
+    structs: BTreeMap<language::Identifier, StructLayout>,
+    enums: BTreeMap<language::Identifier, EnumLayout>,
+}
+
+impl Adts {
+    fn ty(&self, name: &language::Identifier) -> Option<mini::Type> {
+        let struct_ty = self.structs.get(name).map(|adt| adt.ty);
+        struct_ty.or_else(|| self.enums.get(name).map(|adt| adt.ty))
+    }
+}
+
+struct StructLayout {
+    /// The fields in declaration order, with their MiniRust type.
+    fields: Vec<(language::FieldName, mini::Type)>,
+    /// The source type of each field, in the same order.
+    source_fields: Vec<language::Type>,
+    /// Structs are laid out like tuples.
+    ty: mini::Type,
+}
+
+impl StructLayout {
+    fn source_field_type(&self, name: &language::FieldName) -> Option<&language::Type> {
+        let index = self.fields.iter().position(|(field, _)| field == name)?;
+        Some(&self.source_fields[index])
+    }
+}
+
+struct EnumLayout {
+    /// The variants in declaration order, so that the index of a variant is its discriminant.
+    variants: Vec<(language::Identifier, StructLayout)>,
+    ty: mini::Type,
+}
+
+impl EnumLayout {
+    fn variant(&self, name: &language::Identifier) -> Option<(usize, &StructLayout)> {
+        self.variants
+            .iter()
+            .enumerate()
+            .find(|(_, (variant, _))| variant == name)
+            .map(|(discriminant, (_, layout))| (discriminant, layout))
+    }
+}
+
+/// Compute the layout of every struct and enum. A type may only mention types declared before it,
+/// which rules out recursive types.
+fn collect_adts(program: &language::Program) -> Result<Adts, CompilationError> {
+    let mut adts = Adts::default();
+    for item in &program.items {
+        let name = match &item.kind {
+            language::ItemKind::Function(_) => continue,
+            language::ItemKind::Struct(adt) => &adt.name,
+            language::ItemKind::Enum(adt) => &adt.name,
+        };
+        if adts.ty(name).is_some() {
+            return Err(minirust_error(format!("duplicate type `{name}`")));
+        }
+        match &item.kind {
+            language::ItemKind::Function(_) => {}
+            language::ItemKind::Struct(adt) => {
+                let layout = struct_layout(&adt.fields, Size::ZERO, &adts)?;
+                adts.structs.insert(adt.name.clone(), layout);
+            }
+            language::ItemKind::Enum(adt) => {
+                let layout = enum_layout(adt, &adts)?;
+                adts.enums.insert(adt.name.clone(), layout);
+            }
+        }
+    }
+    Ok(adts)
+}
+
+/// Lay out these fields like a tuple whose fields start at offset `start`.
+fn struct_layout(
+    fields: &language::StructFields,
+    start: Size,
+    adts: &Adts,
+) -> Result<StructLayout, CompilationError> {
+    let mut field_tys = Vec::new();
+    let mut source_fields = Vec::new();
+    for (name, ty) in fields.field_list() {
+        field_tys.push((name, translate_type(ty, adts)?));
+        source_fields.push(ty.clone());
+    }
+    let ty = tuple_type_from_fields_after(start, field_tys.iter().map(|(_, ty)| *ty))?;
+    Ok(StructLayout {
+        fields: field_tys,
+        source_fields,
+        ty,
+    })
+}
+
+/// An enum is laid out as a one-byte tag that stores the discriminant, followed by the fields of
+/// the active variant. Each variant is laid out like a struct whose fields start after the tag,
+/// padded to the size of the whole enum.
+fn enum_layout(adt: &language::Enum, adts: &Adts) -> Result<EnumLayout, CompilationError> {
+    if adt.variants.len() > 256 {
+        return Err(minirust_error(format!(
+            "MiniRust runner only supports enums with at most 256 variants, got `{}`",
+            adt.name
+        )));
+    }
+    let tag_ty = enum_tag_type();
+    let tag_size = Size::from_bytes_const(1);
+    let mut variants = Vec::new();
+    let mut end = tag_size.bytes();
+    let mut align = Align::ONE;
+    for variant in &adt.variants {
+        let layout = struct_layout(&variant.fields, tag_size, adts)?;
+        let (variant_size, variant_align) = type_size_align(&layout.ty)?;
+        if variant_size.bytes() > end {
+            end = variant_size.bytes();
+        }
+        if variant_align.bytes() > align.bytes() {
+            align = variant_align;
+        }
+        variants.push((variant.name.clone(), layout));
+    }
+    let align_bytes = align.bytes();
+    let size = Size::from_bytes((end + align_bytes - 1) / align_bytes * align_bytes)
+        .ok_or_else(|| minirust_error("enum size overflowed"))?;
+
+    let mut mini_variants = Map::new();
+    let mut children = Map::new();
+    for (discriminant, (_, layout)) in variants.iter_mut().enumerate() {
+        layout.ty = with_head_layout(layout.ty, size, align);
+        let mut tagger = Map::new();
+        tagger.insert(Size::ZERO, (tag_ty, Int::from(discriminant)));
+        mini_variants.insert(
+            Int::from(discriminant),
+            mini::Variant {
+                ty: layout.ty,
+                tagger,
+            },
+        );
+        children.insert(
+            (Int::from(discriminant), Int::from(discriminant + 1)),
+            mini::Discriminator::Known(Int::from(discriminant)),
+        );
+    }
+    let ty = mini::Type::Enum {
+        variants: mini_variants,
+        discriminant_ty: discriminant_type(),
+        discriminator: mini::Discriminator::Branch {
+            offset: Size::ZERO,
+            value_type: tag_ty,
+            fallback: GcCow::new(mini::Discriminator::Invalid),
+            children,
+        },
+        size,
+        align,
+    };
+    Ok(EnumLayout { variants, ty })
+}
+
+fn translate_int_type(ty: language::IntType) -> mini::IntType {
+    mini::IntType {
+        signed: if ty.is_signed() {
+            Signedness::Signed
+        } else {
+            Signedness::Unsigned
+        },
+        size: Size::from_bytes_const(u64::from(ty.size_in_bytes())),
+    }
+}
+
+fn translate_integer_literal(
+    literal: &language::IntegerLiteral,
+    ty: mini::IntType,
+) -> Result<(mini::ValueExpr, mini::Type), CompilationError> {
+    translate_int_constant(Int::from(literal.value), ty, literal)
+}
+
+fn translate_negated_integer_literal(
+    literal: &language::IntegerLiteral,
+    ty: mini::IntType,
+) -> Result<(mini::ValueExpr, mini::Type), CompilationError> {
+    translate_int_constant(
+        Int::from(0) - Int::from(literal.value),
+        ty,
+        format_args!("-{literal}"),
+    )
+}
+
+fn translate_int_constant(
+    value: Int,
+    ty: mini::IntType,
+    literal: impl std::fmt::Display,
+) -> Result<(mini::ValueExpr, mini::Type), CompilationError> {
+    if !ty.can_represent(value) {
+        return Err(minirust_error(format!(
+            "integer literal `{literal}` does not fit in its type"
+        )));
+    }
+    let ty = mini::Type::Int(ty);
+    Ok((
+        mini::ValueExpr::Constant(mini::Constant::Int(value), ty),
+        ty,
+    ))
+}
+
+fn is_unsuffixed_integer_literal(expression: &language::Expression) -> bool {
+    matches!(
+        &expression.kind,
+        language::ExpressionKind::Literal(language::LiteralExpression::Integer(literal))
+            if literal.suffix.is_none()
+    )
+}
+
+fn int_type_of(ty: mini::Type) -> Option<mini::IntType> {
     match ty {
-        language::Type::Tuple(types) => {
+        mini::Type::Int(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+/// `&`, `|` and `^` also apply to booleans; everything else needs integers.
+fn translate_binary_operator(
+    operator: language::BinaryOperator,
+    ty: mini::Type,
+    operand: &language::Expression,
+) -> Result<mini::BinOp, CompilationError> {
+    Ok(match (operator, ty) {
+        (language::BinaryOperator::BitAnd, mini::Type::Bool) => {
+            mini::BinOp::Bool(mini::BoolBinOp::BitAnd)
+        }
+        (language::BinaryOperator::BitOr, mini::Type::Bool) => {
+            mini::BinOp::Bool(mini::BoolBinOp::BitOr)
+        }
+        (language::BinaryOperator::BitXor, mini::Type::Bool) => {
+            mini::BinOp::Bool(mini::BoolBinOp::BitXor)
+        }
+        (_, mini::Type::Int(_)) => mini::BinOp::Int(match operator {
+            language::BinaryOperator::Add => mini::IntBinOp::Add,
+            language::BinaryOperator::Sub => mini::IntBinOp::Sub,
+            language::BinaryOperator::Mul => mini::IntBinOp::Mul,
+            language::BinaryOperator::Div => mini::IntBinOp::Div,
+            language::BinaryOperator::Rem => mini::IntBinOp::Rem,
+            language::BinaryOperator::BitAnd => mini::IntBinOp::BitAnd,
+            language::BinaryOperator::BitOr => mini::IntBinOp::BitOr,
+            language::BinaryOperator::BitXor => mini::IntBinOp::BitXor,
+            language::BinaryOperator::Shl => mini::IntBinOp::Shl,
+            language::BinaryOperator::Shr => mini::IntBinOp::Shr,
+        }),
+        _ => {
+            return Err(minirust_error(format!(
+                "MiniRust runner cannot apply `{}` to `{operand}`",
+                operator.symbol()
+            )));
+        }
+    })
+}
+
+fn translate_comparison_operator(operator: language::ComparisonOperator) -> mini::RelOp {
+    match operator {
+        language::ComparisonOperator::Eq => mini::RelOp::Eq,
+        language::ComparisonOperator::Ne => mini::RelOp::Ne,
+        language::ComparisonOperator::Lt => mini::RelOp::Lt,
+        language::ComparisonOperator::Gt => mini::RelOp::Gt,
+        language::ComparisonOperator::Le => mini::RelOp::Le,
+        language::ComparisonOperator::Ge => mini::RelOp::Ge,
+    }
+}
+
+/// The type of the tag that stores the discriminant of an enum in memory.
+fn enum_tag_type() -> mini::IntType {
+    mini::IntType {
+        signed: Signedness::Unsigned,
+        size: Size::from_bytes_const(1),
+    }
+}
+
+/// The type of discriminant values, which is `isize` by default
+/// [ref:items.enum.discriminant.repr-rust].
+fn discriminant_type() -> mini::IntType {
+    translate_int_type(language::IntType::Isize)
+}
+
+/// Give this tuple type another size and alignment, e.g. to pad it.
+fn with_head_layout(ty: mini::Type, end: Size, align: Align) -> mini::Type {
+    let mini::Type::Tuple {
+        sized_fields,
+        unsized_field,
+        ..
+    } = ty
+    else {
+        return ty;
+    };
+    mini::Type::Tuple {
+        sized_fields,
+        sized_head_layout: memory::TupleHeadLayout {
+            end,
+            align,
+            packed_align: None,
+        },
+        unsized_field,
+    }
+}
+
+fn translate_type(ty: &language::Type, adts: &Adts) -> Result<mini::Type, CompilationError> {
+    match &ty.kind {
+        language::TypeKind::Tuple(types) => {
             let fields = types
                 .iter()
-                .map(translate_type)
+                .map(|ty| translate_type(ty, adts))
                 .collect::<Result<Vec<_>, _>>()?;
             tuple_type_from_fields(fields)
         }
-        language::Type::Bool => Ok(mini::Type::Bool),
-        language::Type::Ref(_, mutability, inner) if matches!(**inner, language::Type::Str) => {
+        language::TypeKind::Path(path, _) => adts.ty(path).ok_or_else(|| {
+            minirust_error(format!(
+                "MiniRust runner needs type `{path}` to be declared before it is used"
+            ))
+        }),
+        language::TypeKind::Bool => Ok(mini::Type::Bool),
+        language::TypeKind::Int(ty) => Ok(mini::Type::Int(translate_int_type(*ty))),
+        language::TypeKind::Ref(_, mutability, inner)
+            if matches!(inner.kind, language::TypeKind::Str) =>
+        {
             match mutability {
                 language::Mutability::Immutable => Ok(mini::Type::Ptr(str_ref_ptr_type())),
                 language::Mutability::Mutable => Err(minirust_error(
@@ -975,14 +1895,14 @@ fn translate_type(ty: &language::Type) -> Result<mini::Type, CompilationError> {
                 )),
             }
         }
-        language::Type::Ref(_, mutability, inner) => {
-            let pointee_ty = translate_type(inner)?;
+        language::TypeKind::Ref(_, mutability, inner) => {
+            let pointee_ty = translate_type(inner, adts)?;
             Ok(mini::Type::Ptr(ref_ptr_type(*mutability, pointee_ty)?))
         }
-        language::Type::Str => Err(minirust_error(
+        language::TypeKind::Str => Err(minirust_error(
             "MiniRust runner only supports `str` behind a reference",
         )),
-        language::Type::TraitSelf => Err(minirust_error(format!(
+        language::TypeKind::TraitSelf => Err(minirust_error(format!(
             "MiniRust runner does not yet support type `{ty}`"
         ))),
     }
@@ -991,7 +1911,15 @@ fn translate_type(ty: &language::Type) -> Result<mini::Type, CompilationError> {
 fn tuple_type_from_fields(
     fields: impl IntoIterator<Item = mini::Type>,
 ) -> Result<mini::Type, CompilationError> {
-    let mut offset = Size::ZERO;
+    tuple_type_from_fields_after(Size::ZERO, fields)
+}
+
+/// A tuple type whose fields are laid out starting at offset `start`.
+fn tuple_type_from_fields_after(
+    start: Size,
+    fields: impl IntoIterator<Item = mini::Type>,
+) -> Result<mini::Type, CompilationError> {
+    let mut offset = start;
     let mut max_align = Align::ONE;
     let mut sized_fields = Vec::new();
     for field_ty in fields {
@@ -1096,9 +2024,9 @@ fn minirust_runtime_error(error: TerminationInfo) -> CompilationError {
 }
 
 fn minirust_error(message: impl Into<String>) -> CompilationError {
-    CompilationError::MiniRust(message.into())
+    CompilationError::MiniRust(Diagnostic::new(message))
 }
 
 fn internal_error(message: impl Into<String>) -> CompilationError {
-    CompilationError::Internal(message.into())
+    CompilationError::Internal(Diagnostic::new(message))
 }
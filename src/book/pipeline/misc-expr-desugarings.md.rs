@@ -4,12 +4,16 @@
 //@
 //@ This step handles some small initial desugarings of expressions.
 //@
+use std::collections::HashSet;
+
 use crate::desugarings::*;
 
 pub fn misc_expr_desugarings(program: &mut Program) -> Result<(), CompilationError> {
+    let constructors = StructConstructors::collect(program);
     program.visit_all_mut(|expression: &mut Expression| {
         flatten_grouped_expression(expression);
         add_missing_else_branch(expression);
+        constructors.make_struct_expression(expression);
         Ok(())
     })
 }
@@ -20,19 +24,17 @@ pub fn misc_expr_desugarings(program: &mut Program) -> Result<(), CompilationErr
 fn add_missing_else_branch(expression: &mut Expression) {
     if let ExpressionKind::If(if_expression) = &mut expression.kind {
         if if_expression.else_branch.is_none() {
-            if_expression.else_branch = Some(Box::new(empty_block_expression()));
+            if_expression.else_branch = Some(Box::new(empty_block_expression(&expression.origin)));
         }
     }
 }
 
-fn empty_block_expression() -> Expression {
-    BlockExpression {
-        label: None,
-        inner_attrs: vec![],
-        statements: vec![],
-        tail: None,
+/// An empty block made from the node with origin `source`.
+fn empty_block_expression(source: &Origin) -> Expression {
+    Expression {
+        origin: source.derive(),
+        ..BlockExpression::derived_from(source, vec![], None).into()
     }
-    .into()
 }
 
 //@ ## Grouped expressions
@@ -44,3 +46,113 @@ fn flatten_grouped_expression(expression: &mut Expression) {
         expression.kind = inner.kind.clone();
     }
 }
+
+//@ ## Struct constructors
+//@
+//@ A tuple struct also defines a constructor function of the same name, and a unit struct a
+//@ constant of the same name [ref:items.struct.tuple] [ref:items.struct.unit]. We turn uses of
+//@ these into plain struct expressions: `Foo(a, b)` becomes `Foo { 0: a, 1: b }` and `Foo`
+//@ becomes `Foo {}`.
+//@
+//@ Likewise for enum variants [ref:items.enum.constructor-names]: `Enum::Variant(a, b)` becomes
+//@ `Enum::Variant { 0: a, 1: b }` and `Enum::Variant` becomes `Enum::Variant {}`.
+struct StructConstructors {
+    tuple_structs: HashSet<Identifier>,
+    unit_structs: HashSet<Identifier>,
+    tuple_variants: HashSet<VariantPath>,
+    unit_variants: HashSet<VariantPath>,
+}
+
+impl StructConstructors {
+    fn collect(program: &Program) -> Self {
+        let mut constructors = Self {
+            tuple_structs: HashSet::new(),
+            unit_structs: HashSet::new(),
+            tuple_variants: HashSet::new(),
+            unit_variants: HashSet::new(),
+        };
+        program.visit_all_infallible(|adt: &Struct| match &adt.fields {
+            StructFields::Tuple(_) => {
+                constructors.tuple_structs.insert(adt.name.clone());
+            }
+            StructFields::Unit => {
+                constructors.unit_structs.insert(adt.name.clone());
+            }
+            StructFields::Named(_) => {}
+        });
+        program.visit_all_infallible(|adt: &Enum| {
+            for variant in &adt.variants {
+                let path = VariantPath {
+                    enum_name: adt.name.clone(),
+                    variant: variant.name.clone(),
+                };
+                match &variant.fields {
+                    StructFields::Tuple(_) => {
+                        constructors.tuple_variants.insert(path);
+                    }
+                    StructFields::Unit => {
+                        constructors.unit_variants.insert(path);
+                    }
+                    StructFields::Named(_) => {}
+                }
+            }
+        });
+        constructors
+    }
+
+    fn make_struct_expression(&self, expression: &mut Expression) {
+        let kind = match &mut expression.kind {
+            ExpressionKind::Call(CallExpression {
+                callee:
+                    Expression {
+                        kind: ExpressionKind::Path(path),
+                        ..
+                    },
+                args,
+            }) if self.tuple_structs.contains(path) => ExpressionKind::Struct(StructExpression {
+                path: path.clone(),
+                fields: positional_fields(std::mem::take(args)),
+            }),
+            ExpressionKind::Call(CallExpression {
+                callee:
+                    Expression {
+                        kind: ExpressionKind::VariantPath(path),
+                        ..
+                    },
+                args,
+            }) if self.tuple_variants.contains(path) => {
+                ExpressionKind::EnumVariant(EnumVariantExpression {
+                    path: path.clone(),
+                    fields: positional_fields(std::mem::take(args)),
+                })
+            }
+            ExpressionKind::Path(path) if self.unit_structs.contains(path) => {
+                ExpressionKind::Struct(StructExpression {
+                    path: path.clone(),
+                    fields: vec![],
+                })
+            }
+            ExpressionKind::VariantPath(path) if self.unit_variants.contains(path) => {
+                ExpressionKind::EnumVariant(EnumVariantExpression {
+                    path: path.clone(),
+                    fields: vec![],
+                })
+            }
+            _ => return,
+        };
+        expression.kind = kind;
+        expression.origin = expression.origin.derive();
+    }
+}
+
+fn positional_fields(values: Vec<Expression>) -> Vec<StructExprField> {
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| StructExprField {
+            attrs: vec![],
+            name: FieldName::Index(index),
+            value,
+        })
+        .collect()
+}
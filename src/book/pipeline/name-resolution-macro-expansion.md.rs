@@ -0,0 +1,415 @@
+//@ # Name Resolution & Macro Expansion
+//@
+//@ Name Resolution is the process by which Rust figures out what every identifier (local variable,
+//@ function call, module, import, etc) refers to. Macro expansion is the process of running a macro and
+//@ replacing the macro call with its output.
+//@
+//@ The two are intertwined: a macro may emit new macros, which will affect the
+//@ state of name resolution for macros. For instance:
+//@
+//@ ```rust,example
+//@ macro_rules! define_bump {
+//@     ($name:ident) => {
+//@         macro_rules! $name { ($x:expr) => { $x + 1 } }
+//@     };
+//@ }
+//@
+//@ // Name resolution figures out that this points to the macro above.
+//@ define_bump!(bump);
+//@
+//@ fn main() {
+//@     // We can't resolve this name until after expanding the `define_bump!` call above.
+//@     let _ = bump!(3);
+//@ }
+//@ ```
+//@
+//@ In fact the whole process is stateful: we must expand items in declaration order and I think
+//@ even the order in which we explore modules can have consequences.
+//@
+//@ To represent the output of name resolution and hygiene, as part of our desugaring we expand all
+//@ names to full paths, for any name where there could be ambiguity we rename identifiers to make them
+//@ unique, and we insert [Explicit Hygiene Markers](../features/hygiene-markers.md) as appropriate.
+//@
+//@ For example:
+//@ ```rust,example
+//@ mod foo {
+//@     fn bar(x: u32) {}
+//@ }
+//@ use foo::*;
+//@ fn main() {
+//@     let x = 4;
+//@     let x = x + 1;
+//@     bar(x);
+//@ }
+//@
+//@ // becomes:
+//@ mod foo {
+//@     fn bar() {}
+//@ }
+//@ fn main() {
+//@     let x1 = 4;
+//@     let x2 = x1 + 1;
+//@     crate::foo::bar(x2);
+//@ }
+//@ ```
+//@
+//@ This also deals with macro hygiene:
+//@
+//@ ```rust,example
+//@ fn foo() -> u32 {
+//@     let x = 1;
+//@     macro_rules! check {
+//@         () => { x == 1 }; // Uses `x` from the definition site.
+//@     }
+//@     let x = 2;
+//@     if check!() {
+//@         x
+//@     } else {
+//@         0
+//@     }
+//@ }
+//@
+//@ // becomes:
+//@ fn foo() -> u32 {
+//@     let x1 = 1;
+//@     let x2 = 2;
+//@     if x1 == 1 {
+//@         x2
+//@     } else {
+//@         0
+//@     }
+//@ }
+//@ ```
+//@
+//@ See the Reference for details on macro expansion [ref:macro.decl] and name resolution [ref:names].
+//@
+//@ At the end of this step, there are no macros left, no `use` statements, every item is referred to by
+//@ its full path, and all variables have unique names.
+//@
+//@ > The rest of this section is a work-in-progress experiment about making the book executable.
+//@
+//@ We have neither macros nor modules yet, so all that is left to do is find what each path refers
+//@ to. A path expression is either a local variable, the innermost one of that name in scope, or an
+//@ item. Items may be declared anywhere in the program, since we have no modules to tell them apart.
+//@ A path that refers to nothing is an error.
+//@
+//@ To give local variables unique names, the first binding of a given name in a function keeps it,
+//@ and later ones get a [hygiene marker](../language/names.md.rs):
+//@
+//@ ```rust,example
+//@ let x = 1;
+//@ let x = x + 1;
+//@ // becomes
+//@ let x = 1;
+//@ let x#1 = x + 1;
+//@ ```
+use crate::desugarings::*; //#
+use std::collections::{HashMap, HashSet}; //#
+
+pub fn resolve_names(program: &mut Program) -> Result<(), CompilationError> {
+    let items = Items::collect(program);
+    let mut names = NameSupply::new(program);
+    program.visit_all_mut(|function: &mut Function| {
+        Resolver::new(&items, &mut names).resolve_function(function)
+    })
+}
+
+/// The names of the items of the program.
+#[derive(Default)]
+struct Items {
+    /// The items a path expression can refer to: functions, and the constructors of structs.
+    values: HashSet<Identifier>,
+    structs: HashSet<Identifier>,
+    enums: HashSet<Identifier>,
+    variants: HashSet<VariantPath>,
+}
+
+impl Items {
+    fn collect(program: &Program) -> Self {
+        let mut items = Items::default();
+        program.visit_all_infallible(|function: &Function| {
+            items.values.insert(function.name.clone());
+        });
+        program.visit_all_infallible(|adt: &Struct| {
+            items.values.insert(adt.name.clone());
+            items.structs.insert(adt.name.clone());
+        });
+        program.visit_all_infallible(|adt: &Enum| {
+            items.enums.insert(adt.name.clone());
+            for variant in &adt.variants {
+                items.variants.insert(VariantPath {
+                    enum_name: adt.name.clone(),
+                    variant: variant.name.clone(),
+                });
+            }
+        });
+        items
+    }
+
+    fn check_variant(&self, path: &VariantPath, span: Span) -> Result<(), CompilationError> {
+        if !self.enums.contains(&path.enum_name) {
+            desugaring_error!(
+                span,
+                format!("cannot find enum `{}` in this scope", path.enum_name)
+            )
+        }
+        if !self.variants.contains(path) {
+            desugaring_error!(
+                span,
+                format!(
+                    "no variant named `{}` in enum `{}`",
+                    path.variant, path.enum_name
+                )
+            )
+        }
+        Ok(())
+    }
+}
+
+/// Resolves the names of a function body.
+struct Resolver<'a> {
+    items: &'a Items,
+    names: &'a mut NameSupply,
+    /// The names of the bindings of the function so far, to rename the ones that are bound again.
+    bound: HashSet<Identifier>,
+    scopes: Vec<Scope>,
+}
+
+#[derive(Default)]
+struct Scope {
+    label: Option<String>,
+    /// The unique name of each local variable of this scope.
+    locals: HashMap<Identifier, Identifier>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(items: &'a Items, names: &'a mut NameSupply) -> Self {
+        Resolver {
+            items,
+            names,
+            bound: HashSet::new(),
+            scopes: vec![],
+        }
+    }
+
+    fn resolve_function(mut self, function: &mut Function) -> Result<(), CompilationError> {
+        let FunctionBody::Block(body) = &mut function.body else {
+            return Ok(());
+        };
+        self.scopes.push(Scope::default());
+        for parameter in &mut function.parameters {
+            match &mut parameter.kind {
+                FunctionParamKind::Regular {
+                    pattern: Some(pattern),
+                    ..
+                } => self.bind_pattern(pattern, 0),
+                FunctionParamKind::Regular { pattern: None, .. } => {}
+                FunctionParamKind::SelfParam { .. }
+                | FunctionParamKind::RefSelfShorthand { .. } => {
+                    let name = Identifier::new("self");
+                    self.bound.insert(name.clone());
+                    self.scopes[0].locals.insert(name.clone(), name);
+                }
+            }
+        }
+        self.resolve_block(body)
+    }
+
+    /// Bring the bindings of this pattern in scope, giving them unique names.
+    fn bind_pattern(&mut self, pattern: &mut Pattern, scope: usize) {
+        pattern.visit_all_mut_infallible(|pattern: &mut Pattern| {
+            if let PatternKind::Identifier(name) = &mut pattern.kind {
+                let unique = if self.bound.insert(name.clone()) {
+                    name.clone()
+                } else {
+                    self.names.fresh(&name.name)
+                };
+                self.scopes[scope]
+                    .locals
+                    .insert(std::mem::replace(name, unique.clone()), unique);
+            }
+        });
+    }
+
+    fn resolve_block(&mut self, block: &mut BlockExpression) -> Result<(), CompilationError> {
+        self.scopes.push(Scope {
+            label: block.label.clone(),
+            locals: HashMap::new(),
+        });
+        for statement in &mut block.statements {
+            self.resolve_statement(statement)?;
+        }
+        if let Some(tail) = &mut block.tail {
+            self.resolve_expression(tail)?;
+        }
+        self.scopes.pop();
+        Ok(())
+    }
+
+    fn resolve_statement(&mut self, statement: &mut Statement) -> Result<(), CompilationError> {
+        match statement {
+            // Nested functions get resolved on their own: they can't see our locals.
+            Statement::Empty | Statement::Item(_) => Ok(()),
+            //@ The bindings of a `let` are only in scope after it, not in its initializer nor in
+            //@ its `else` block.
+            Statement::Let {
+                scope,
+                pattern,
+                initial_value,
+                else_branch,
+                span,
+                ..
+            } => {
+                if let Some(value) = initial_value {
+                    self.resolve_expression(value)?;
+                }
+                if let Some(else_branch) = else_branch {
+                    self.resolve_block(else_branch)?;
+                }
+                let scope = match scope.as_deref() {
+                    Some(label) => match self
+                        .scopes
+                        .iter()
+                        .rposition(|scope| scope.label.as_deref() == Some(label))
+                    {
+                        Some(scope) => scope,
+                        None => {
+                            desugaring_error!(*span, format!("use of undeclared label `{label}`"))
+                        }
+                    },
+                    None => self.scopes.len() - 1,
+                };
+                self.bind_pattern(pattern, scope);
+                Ok(())
+            }
+            Statement::Expr(expression) => self.resolve_expression(expression),
+        }
+    }
+
+    fn resolve_expression(&mut self, expression: &mut Expression) -> Result<(), CompilationError> {
+        let span = expression.span;
+        match &mut expression.kind {
+            ExpressionKind::Path(name) => {
+                if let Some(unique) = self
+                    .scopes
+                    .iter()
+                    .rev()
+                    .find_map(|scope| scope.locals.get(name))
+                {
+                    *name = unique.clone();
+                } else if !self.items.values.contains(name) && *name != "print" {
+                    desugaring_error!(span, format!("cannot find value `{name}` in this scope"))
+                }
+            }
+            ExpressionKind::Literal(_) | ExpressionKind::Continue(_) => {}
+            ExpressionKind::VariantPath(path) | ExpressionKind::DiscriminantOf(path) => {
+                self.items.check_variant(path, span)?
+            }
+            ExpressionKind::Operator(operator) => match &mut **operator {
+                OperatorExpression::Borrow(BorrowExpression { expression, .. })
+                | OperatorExpression::Dereference(DereferenceExpression { expression }) => {
+                    self.resolve_expression(expression)?
+                }
+                OperatorExpression::Negation(_, operand) => self.resolve_expression(operand)?,
+                OperatorExpression::Binary(_, left, right)
+                | OperatorExpression::Comparison(_, left, right)
+                | OperatorExpression::Assignment(left, right)
+                | OperatorExpression::CompoundAssignment(_, left, right)
+                | OperatorExpression::LazyAnd(left, right)
+                | OperatorExpression::LazyOr(left, right) => {
+                    self.resolve_expression(left)?;
+                    self.resolve_expression(right)?;
+                }
+            },
+            ExpressionKind::Grouped(inner) => self.resolve_expression(inner)?,
+            ExpressionKind::Block(block) => self.resolve_block(block)?,
+            //@ The bindings of the `let`s in the condition of an `if` are in scope in its `then`
+            //@ branch [ref:expr.if.chains].
+            ExpressionKind::If(if_expression) => {
+                self.scopes.push(Scope::default());
+                self.resolve_expression(&mut if_expression.condition)?;
+                self.resolve_expression(&mut if_expression.then_branch)?;
+                self.scopes.pop();
+                if let Some(else_branch) = &mut if_expression.else_branch {
+                    self.resolve_expression(else_branch)?;
+                }
+            }
+            ExpressionKind::Let(let_expression) => {
+                self.resolve_expression(&mut let_expression.scrutinee)?;
+                let scope = self.scopes.len() - 1;
+                self.bind_pattern(&mut let_expression.pattern, scope);
+            }
+            ExpressionKind::Loop(loop_expression) => match &mut loop_expression.kind {
+                LoopKind::Infinite(body) => self.resolve_block(body)?,
+                LoopKind::While { condition, body } => {
+                    self.scopes.push(Scope::default());
+                    self.resolve_expression(condition)?;
+                    self.resolve_block(body)?;
+                    self.scopes.pop();
+                }
+            },
+            ExpressionKind::Match(match_expression) => {
+                self.resolve_expression(&mut match_expression.scrutinee)?;
+                for arm in &mut match_expression.arms {
+                    self.scopes.push(Scope::default());
+                    let scope = self.scopes.len() - 1;
+                    self.bind_pattern(&mut arm.pattern, scope);
+                    if let Some(guard) = &mut arm.guard {
+                        self.resolve_expression(guard)?;
+                    }
+                    self.resolve_expression(&mut arm.body)?;
+                    self.scopes.pop();
+                }
+            }
+            ExpressionKind::Break(break_expression) => {
+                if let Some(value) = &mut break_expression.value {
+                    self.resolve_expression(value)?;
+                }
+            }
+            ExpressionKind::Tuple(elements) => {
+                for element in elements {
+                    self.resolve_expression(element)?;
+                }
+            }
+            ExpressionKind::TupleIndexing(tuple_indexing) => {
+                self.resolve_expression(&mut tuple_indexing.expression)?
+            }
+            ExpressionKind::Struct(adt) => {
+                if !self.items.structs.contains(&adt.path) {
+                    desugaring_error!(
+                        span,
+                        format!("cannot find struct `{}` in this scope", adt.path)
+                    )
+                }
+                for field in &mut adt.fields {
+                    self.resolve_expression(&mut field.value)?;
+                }
+            }
+            ExpressionKind::EnumVariant(variant) => {
+                self.items.check_variant(&variant.path, span)?;
+                for field in &mut variant.fields {
+                    self.resolve_expression(&mut field.value)?;
+                }
+            }
+            ExpressionKind::EnumProjection(projection) => {
+                self.resolve_expression(&mut projection.expression)?
+            }
+            ExpressionKind::Field(field) => self.resolve_expression(&mut field.expression)?,
+            ExpressionKind::Discriminant(discriminant) => {
+                self.resolve_expression(&mut discriminant.expression)?
+            }
+            ExpressionKind::Call(call) => {
+                self.resolve_expression(&mut call.callee)?;
+                for argument in &mut call.args {
+                    self.resolve_expression(argument)?;
+                }
+            }
+            ExpressionKind::Virtual(
+                VirtualExpression::ValueToPlaceCoercion(inner)
+                | VirtualExpression::PlaceToValueCoercion(inner),
+            ) => self.resolve_expression(inner)?,
+            ExpressionKind::Virtual(VirtualExpression::Unreachable) => {}
+        }
+        Ok(())
+    }
+}
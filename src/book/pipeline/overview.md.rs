@@ -16,38 +16,145 @@
 //@ Some of the desugaring steps fails to enforce that; this is noted in their Discussion section.
 //@
 //@ At the end of all that, we get a program in a very limited and precise subset of Rust.
-//@ See [The Final Language](final-language.md) for details and discussion.
+//@ See [The Final Language](final-language.md.rs) for details and discussion.
 //@
 //@ > The rest of this section is a work-in-progress experiment about making the book executable.
 use crate::language::*;
 use crate::*;
 
 macro_rules! desugaring_error {
-    ($msg:expr) => {{
-        return Err(crate::CompilationError::Desugaring($msg.to_string()));
+    ($span:expr, $msg:expr) => {{
+        return Err(crate::CompilationError::Desugaring(
+            crate::Diagnostic::new($msg).with_span($span),
+        ));
     }};
 }
 
-pub fn desugar(mut program: Program) -> Result<Program, CompilationError> {
-    funsig::desugar_fun_sigs(&mut program)?;
-    misc_expr_desugarings::misc_expr_desugarings(&mut program)?;
-    expr_unnesting::desugar_nested_exprs(&mut program)?;
-    final_desugarings::desugar_final(&mut program)?;
+//@ ## Passes
+//@
+//@ The pipeline is a list of named passes, run in order. The names are what the command line
+//@ accepts to stop after a given pass or to look at the program it produced.
+pub struct Pass {
+    pub name: &'static str,
+    pub run: fn(&mut Program) -> Result<(), CompilationError>,
+}
+
+pub const PASSES: &[Pass] = &[
+    Pass {
+        name: "resolve-names",
+        run: name_resolution::resolve_names,
+    },
+    Pass {
+        name: "funsig",
+        run: funsig::desugar_fun_sigs,
+    },
+    Pass {
+        name: "misc-expr",
+        run: misc_expr_desugarings::misc_expr_desugarings,
+    },
+    Pass {
+        name: "type-check",
+        run: type_checking::check_types,
+    },
+    Pass {
+        name: "binding-types",
+        run: patterns::desugaring_bindings::explicit_binding_types,
+    },
+    Pass {
+        name: "loops",
+        run: control_flow::loop_desugaring::desugar_loops,
+    },
+    Pass {
+        name: "lazy-booleans",
+        run: control_flow::boolean_operators::desugar_lazy_booleans,
+    },
+    Pass {
+        name: "place-coercions",
+        run: expr_unnesting::explicit_value_place::make_place_coercions_explicit,
+    },
+    Pass {
+        name: "value-to-place",
+        run: expr_unnesting::value_to_place::desugar_value_to_place,
+    },
+    Pass {
+        name: "matches",
+        run: patterns::match_desugaring::desugar_matches,
+    },
+    Pass {
+        name: "patterns",
+        run: patterns::pattern_unnesting::unnest_patterns,
+    },
+    Pass {
+        name: "subexpressions",
+        run: expr_unnesting::subexpr_elim::eliminate_subexpressions,
+    },
+    Pass {
+        name: "final",
+        run: final_desugarings::desugar_final,
+    },
+];
+
+pub fn pass_by_name(name: &str) -> Option<&'static Pass> {
+    PASSES.iter().find(|pass| pass.name == name)
+}
+
+pub fn desugar(program: Program) -> Result<Program, CompilationError> {
+    run_passes(program, None, |_, _| {})
+}
+
+/// Run the passes in order, up to and including the one named `stop_after` if any. `after_pass`
+/// sees the program after each pass. In debug builds, a program that went through every pass is
+/// checked to be in the final language.
+pub fn run_passes(
+    mut program: Program,
+    stop_after: Option<&str>,
+    mut after_pass: impl FnMut(&Pass, &Program),
+) -> Result<Program, CompilationError> {
+    // The ids of a program built by hand may not be assigned yet.
+    let mut ids = NodeIds::new(&program);
+    ids.assign(&mut program);
+    for pass in PASSES {
+        (pass.run)(&mut program)?;
+        // The nodes this pass made don't have ids yet, nor know which pass made them.
+        ids.assign(&mut program);
+        program.visit_all_mut_infallible(|origin: &mut Origin| origin.attribute_to(pass.name));
+        after_pass(pass, &program);
+        if stop_after == Some(pass.name) {
+            break;
+        }
+    }
+    // Unless we stopped early, we should have reached the final language.
+    let stopped_early = stop_after.is_some_and(|name| name != PASSES[PASSES.len() - 1].name);
+    if cfg!(debug_assertions) && !stopped_early {
+        final_language::check_final_language(&program)?;
+    }
     Ok(program)
 }
 
 //@ ## Submodules
+#[path = "control-flow.md.rs"]
+pub mod control_flow;
 #[path = "expr-unnesting.md.rs"]
 pub mod expr_unnesting;
 #[path = "final-desugarings.md.rs"]
 pub mod final_desugarings;
+#[path = "final-language.md.rs"]
+pub mod final_language;
 #[cfg(not(target_arch = "wasm32"))]
 #[path = "formality.md.rs"]
 pub mod formality;
 #[path = "funsig.md.rs"]
 pub mod funsig;
+#[path = "interpreter.md.rs"]
+pub mod interpreter;
 #[cfg(not(target_arch = "wasm32"))]
 #[path = "minirust.md.rs"]
 pub mod minirust;
 #[path = "misc-expr-desugarings.md.rs"]
 pub mod misc_expr_desugarings;
+#[path = "name-resolution-macro-expansion.md.rs"]
+pub mod name_resolution;
+#[path = "patterns.md.rs"]
+pub mod patterns;
+#[path = "type-checking.md.rs"]
+pub mod type_checking;
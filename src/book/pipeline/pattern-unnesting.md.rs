@@ -0,0 +1,442 @@
+//@ # Pattern Unnesting
+//@
+//@ Operationally, pattern matching expressions stand for a series of comparisons of discriminants or
+//@ integers.
+//@ In this step we'll compile each `if let` expression down to built-in comparisons by recursively
+//@ simplifying the expressions.
+//@
+//@ By way of example:
+//@ - `let _ = $x` => `true`;
+//@ - `let 42u32 = $x` => `$x == 42u32`;
+//@ - `let 42u32..=73u32 = $x` => `42u32 <= $x && $x <= 73u32`;
+//@ - `let &$p = $x` => `let $p = *$x`;
+//@ - `let ($p0, $p1) = $x` => `let $p0 = $x.0 && let $p1 = $x.1`;
+//@ - `let Struct { a: $pa, b: $pb } = $x` => `let $pa = $x.a && let $pa = $x.b`;
+//@ - `let Enum::Variant { a: $pa, b: $pb } = $x` => `$x.enum#discriminant == discriminant_of!(Enum, Variant) &&
+//@   let $pa = $x.Variant.a && let $pb = $x.Variant.b`;
+//@ - `let [$pa, .., $pz] = $x` => `let len = core::slice::len(&raw const $x) && len >= 2 && let $pa = $x[0] && let $pz = $x[len - 1]`;
+//@ - `let "string_literal" = $x` => `"string_literal" == $x`;
+//@ - `let CONSTANT = $x` => `CONSTANT == $x`.
+//@
+//@ Note that we use [Enum Projections](../features/enum-projections.md) and [Enum Discriminant
+//@ Access](../features/enum-discriminant.md) for enums. Note also that we don't deal with or-patterns
+//@ because they've been dealt with already.
+//@
+//@ The left-to-right order is important here; these are lazy boolean operators.
+//@
+//@ At the end of this step, the only remaining patterns are `x`/`ref x`/`ref mut x`/`place x` bindings.
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+//@ We support identifier, wildcard, literal, tuple and enum variant patterns. The places we read
+//@ from are wrapped in explicit place-to-value coercions, like the ones of [Place-to-Value and
+//@ Value-to-Place Coercions](explicit-value-place.md.rs). Since the [Lazy Boolean
+//@ Operators](boolean-operators.md.rs) step only leaves `&&` in `let`-chains, a pattern that binds
+//@ nothing is tested with `if`s instead: leaving out the coercions, `let (true, false) = $x`
+//@ becomes `if $x.0 == true { $x.1 == false } else { false }`.
+//@
+//@ A destructuring `let` statement is unnested the same way: its tests become an `if` that runs
+//@ the `else` branch of the `let` when they fail, and each of its bindings becomes a `let`
+//@ statement of its own:
+//@ ```rust,example
+//@ let (Some(a), b) = $x else { return };
+//@ // becomes, leaving out the coercions:
+//@ if $x.0.enum#discriminant == discriminant_of!(Option, Some) {} else { return };
+//@ let a = $x.0.Some.0;
+//@ let b = $x.1;
+//@ ```
+//@ A `let` without `else` must have an irrefutable pattern, so the `else` branch we make for its
+//@ tests, if any, is unreachable.
+use crate::desugarings::control_flow::boolean_operators::is_let_chain; //#
+use crate::desugarings::*; //#
+use crate::interactive_example; //#
+use std::collections::HashMap; //#
+
+interactive_example! {
+    unnest_patterns,
+    fn main() {
+        let x = (true, false);
+        if let (true, b) = x && b {
+            print(1);
+        }
+        if let (_, false) = x {
+            print(2);
+        }
+        let (a, (c, _)) = (1, x);
+        print(a);
+    }
+}
+pub fn unnest_patterns(program: &mut Program) -> Result<(), CompilationError> {
+    let types = type_checking::node_types(program)?;
+    program.visit_all_mut_infallible(|if_expression: &mut IfExpression| {
+        let condition = &mut *if_expression.condition;
+        if !has_patterns(condition) {
+            return;
+        }
+        let (span, origin) = (condition.span, condition.origin.clone());
+        let mut operands = vec![];
+        chain_operands(take(condition), &mut operands);
+        let mut conditions = vec![];
+        for operand in operands {
+            match operand.kind {
+                ExpressionKind::Let(LetExpression { pattern, scrutinee })
+                    if !matches!(pattern.kind, PatternKind::Identifier(_)) =>
+                {
+                    let mut unnester = Unnester {
+                        scrutinee: &scrutinee,
+                        conditions: vec![],
+                    };
+                    unnester.unnest(pattern, &mut vec![]);
+                    conditions.extend(unnester.conditions);
+                }
+                _ => conditions.push(operand),
+            }
+        }
+        *condition = Expression {
+            span,
+            ..conjunction(conditions, &origin)
+        };
+    });
+    program.visit_all_mut_infallible(|block: &mut BlockExpression| {
+        let statements = std::mem::take(&mut block.statements);
+        for statement in statements {
+            unnest_let_statement(statement, &types, &mut block.statements);
+        }
+    });
+    Ok(())
+}
+
+/// Unnest the pattern of this statement if it is a destructuring `let`, and push the result to
+/// `statements`. `types` are the types of the nodes of the program, which give the types of the
+/// bindings.
+fn unnest_let_statement(
+    statement: Statement,
+    types: &HashMap<NodeId, Type>,
+    statements: &mut Vec<Statement>,
+) {
+    let Statement::Let {
+        attrs,
+        scope,
+        pattern,
+        ty,
+        initial_value: Some(scrutinee),
+        else_branch,
+        span,
+        origin,
+    } = statement
+    else {
+        statements.push(statement);
+        return;
+    };
+    if let PatternKind::Identifier(_) = pattern.kind
+        && else_branch.is_none()
+    {
+        statements.push(Statement::Let {
+            attrs,
+            scope,
+            pattern,
+            ty,
+            initial_value: Some(scrutinee),
+            else_branch,
+            span,
+            origin,
+        });
+        return;
+    }
+    let mut unnester = Unnester {
+        scrutinee: &scrutinee,
+        conditions: vec![],
+    };
+    unnester.unnest(pattern, &mut vec![]);
+    let (bindings, tests): (Vec<_>, Vec<_>) = unnester
+        .conditions
+        .into_iter()
+        .partition(|condition| matches!(condition.kind, ExpressionKind::Let(_)));
+    if !tests.is_empty() {
+        let else_branch = else_branch.unwrap_or_else(|| {
+            let unreachable = derived(
+                &origin,
+                ExpressionKind::Virtual(VirtualExpression::Unreachable),
+            );
+            BlockExpression::derived_from(&origin, vec![], Some(unreachable))
+        });
+        let test = ExpressionKind::If(IfExpression {
+            condition: Box::new(conjunction(tests, &origin)),
+            then_branch: Box::new(Expression {
+                origin: origin.derive(),
+                ..BlockExpression::derived_from(&origin, vec![], None).into()
+            }),
+            else_branch: Some(Box::new(Expression {
+                origin: origin.derive(),
+                ..else_branch.into()
+            })),
+        });
+        statements.push(Statement::Expr(Expression {
+            span,
+            ..derived(&origin, test)
+        }));
+    }
+    for binding in bindings {
+        let ExpressionKind::Let(LetExpression { pattern, scrutinee }) = binding.kind else {
+            unreachable!()
+        };
+        let mut ty = types.get(&pattern.origin.id).cloned();
+        ty.visit_all_mut_infallible(|ty: &mut Type| ty.origin = pattern.origin.derive());
+        statements.push(Statement::Let {
+            attrs: attrs.clone(),
+            scope: scope.clone(),
+            pattern,
+            ty,
+            initial_value: Some(*scrutinee),
+            else_branch: None,
+            span,
+            origin: binding.origin,
+        });
+    }
+}
+
+/// Whether the `let`-chain `condition` has a `let` with a pattern that isn't just a binding.
+fn has_patterns(condition: &Expression) -> bool {
+    match &condition.kind {
+        ExpressionKind::Let(let_expression) => {
+            !matches!(let_expression.pattern.kind, PatternKind::Identifier(_))
+        }
+        ExpressionKind::Operator(operator) => match &**operator {
+            OperatorExpression::LazyAnd(left, right) => has_patterns(left) || has_patterns(right),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// The operands of the `let`-chain `condition`, from left to right. We join them back together
+/// once we've unnested the patterns, so that the `&&`s still nest to the left.
+fn chain_operands(mut condition: Expression, operands: &mut Vec<Expression>) {
+    if let ExpressionKind::Operator(operator) = &mut condition.kind
+        && let OperatorExpression::LazyAnd(left, right) = &mut **operator
+    {
+        chain_operands(take(left), operands);
+        chain_operands(take(right), operands);
+    } else {
+        operands.push(condition);
+    }
+}
+
+fn take(expression: &mut Expression) -> Expression {
+    std::mem::replace(expression, Expression::new(ExpressionKind::Tuple(vec![])))
+}
+
+/// A projection from the place of a pattern to the place of one of its subpatterns.
+enum Projection {
+    Tuple(usize),
+    Variant(VariantPath, FieldName),
+}
+
+struct Unnester<'a> {
+    scrutinee: &'a Expression,
+    /// The conditions we've produced so far, in the order they must be tested.
+    conditions: Vec<Expression>,
+}
+
+impl Unnester<'_> {
+    /// Add the conditions for the place at the end of `projections` to match `pattern`. Each
+    /// projection is made from the subpattern that needs it.
+    fn unnest(&mut self, pattern: Pattern, projections: &mut Vec<(Origin, Projection)>) {
+        let origin = pattern.origin;
+        match pattern.kind {
+            PatternKind::Identifier(name) => {
+                let value = place_to_value(self.place(projections), &origin);
+                let binding = ExpressionKind::Let(LetExpression {
+                    pattern: Pattern {
+                        kind: PatternKind::Identifier(name),
+                        origin: origin.clone(),
+                    },
+                    scrutinee: Box::new(value),
+                });
+                self.conditions.push(derived(&origin, binding));
+            }
+            PatternKind::Wildcard => {}
+            PatternKind::Literal(literal) => {
+                let value = place_to_value(self.place(projections), &origin);
+                let literal = derived(&origin, ExpressionKind::Literal(literal));
+                self.conditions.push(equals(value, literal, &origin));
+            }
+            PatternKind::Tuple(patterns) => {
+                for (index, pattern) in patterns.into_iter().enumerate() {
+                    projections.push((origin.clone(), Projection::Tuple(index)));
+                    self.unnest(pattern, projections);
+                    projections.pop();
+                }
+            }
+            PatternKind::Variant(variant) => {
+                let discriminant = ExpressionKind::Discriminant(DiscriminantExpression {
+                    expression: Box::new(self.place(projections)),
+                });
+                let discriminant = place_to_value(derived(&origin, discriminant), &origin);
+                let expected = ExpressionKind::DiscriminantOf(variant.path.clone());
+                let test = equals(discriminant, derived(&origin, expected), &origin);
+                self.conditions.push(test);
+                for field in variant.fields {
+                    let projection = Projection::Variant(variant.path.clone(), field.name);
+                    projections.push((origin.clone(), projection));
+                    self.unnest(field.pattern, projections);
+                    projections.pop();
+                }
+            }
+        }
+    }
+
+    /// A copy of the scrutinee, projected to a subpattern.
+    fn place(&self, projections: &[(Origin, Projection)]) -> Expression {
+        let mut place = self.scrutinee.derived_copy();
+        for (origin, projection) in projections {
+            let expression = Box::new(place);
+            let kind = match projection {
+                Projection::Tuple(index) => {
+                    ExpressionKind::TupleIndexing(TupleIndexingExpression {
+                        expression,
+                        index: *index,
+                    })
+                }
+                Projection::Variant(variant, field) => {
+                    ExpressionKind::EnumProjection(EnumProjectionExpression {
+                        expression,
+                        variant: variant.clone(),
+                        field: field.clone(),
+                    })
+                }
+            };
+            place = derived(origin, kind);
+        }
+        place
+    }
+}
+
+/// The conditions, tested from left to right. A pattern without bindings doesn't make a
+/// `let`-chain, so we can't join its conditions with `&&`.
+fn conjunction(conditions: Vec<Expression>, origin: &Origin) -> Expression {
+    if conditions.iter().any(is_let_chain) {
+        return conditions
+            .into_iter()
+            .reduce(|left, right| Expression {
+                origin: origin.derive(),
+                ..LetExpression::and(left, right)
+            })
+            .unwrap();
+    }
+    let literal = |value| {
+        derived(
+            origin,
+            ExpressionKind::Literal(LiteralExpression::Bool(value)),
+        )
+    };
+    conditions
+        .into_iter()
+        .rev()
+        .reduce(|rest, condition| {
+            let block = |value| Expression {
+                origin: origin.derive(),
+                ..BlockExpression::derived_from(origin, vec![], Some(value)).into()
+            };
+            derived(
+                origin,
+                ExpressionKind::If(IfExpression {
+                    condition: Box::new(condition),
+                    then_branch: Box::new(block(rest)),
+                    else_branch: Some(Box::new(block(literal(false)))),
+                }),
+            )
+        })
+        .unwrap_or_else(|| literal(true))
+}
+
+/// `$left == $right`, made from the node with origin `source`.
+fn equals(left: Expression, right: Expression, source: &Origin) -> Expression {
+    let comparison = OperatorExpression::Comparison(ComparisonOperator::Eq, left, right);
+    derived(source, ExpressionKind::Operator(Box::new(comparison)))
+}
+
+/// `place_to_value!($place)`, made from the node with origin `source`.
+fn place_to_value(place: Expression, source: &Origin) -> Expression {
+    let coercion = VirtualExpression::PlaceToValueCoercion(Box::new(place));
+    derived(source, ExpressionKind::Virtual(coercion))
+}
+
+/// A new expression made from the node with origin `source`.
+fn derived(source: &Origin, kind: ExpressionKind) -> Expression {
+    Expression {
+        origin: source.derive(),
+        ..Expression::new(kind)
+    }
+}
+//@ ---
+//@
+//@ ## Discussion
+//@
+//@ ### Evaluation order
+//@
+//@ The exact semantics of patterns are not decided yet. What's presented in this section is actually
+//@ a proposal I'm putting forward, that happens to mostly[^1] be compatible with what's implemented in
+//@ rustc today.
+//@
+//@ This proposal has the benefit and drawback of setting in stone a particular order of evaluation.
+//@ This is useful for unsafe code that may want to know exactly what is accessed in which order, and
+//@ detrimental to optimizations.
+//@
+//@ The kind of `unsafe` code that may motivate such a rigid order is manually-implemented tagged unions:
+//@ ```rust,example
+//@ struct MyOption<T> {
+//@     is_some: bool,
+//@     contents: MyOptionContents<T>,
+//@ }
+//@ union MyOptionContents<T> {
+//@     uninit: (),
+//@     init: T,
+//@ }
+//@
+//@ impl<T> MyOption<T> {
+//@     fn as_ref(&self) -> Option<&T> {
+//@         unsafe {
+//@             match *self {
+//@                 MyOption { is_some: true, contents: MyOptionContents { ref init } } => Some(init),
+//@                 MyOption { is_some: false, .. } => None,
+//@             }
+//@         }
+//@     }
+//@ }
+//@ ```
+//@
+//@ I learned very recently that this is in fact suggested by the Reference
+//@ [ref:items.union.pattern.subpattern], hence basically guaranteed by the language.
+//@
+//@ Beyond this however, the language doesn't currently guarantee any order.
+//@ A reasonable alternative would be to add non-determinism: for the tuple, struct,
+//@ enum variant and slice cases above,
+//@ if the pattern doesn't contain a union subpattern,
+//@ we non-deterministically shuffle the list of `let $subpattern
+//@ = ...` expressions in the output.
+//@ This would allow compilation to choose the order it wants for maximal runtime performance.
+//@
+//@ I don't know exactly the extent of optimization wins we could get with this.
+//@
+//@
+//@ ### Precise semantics
+//@
+//@ There are tiny discrepancies between this proposed desugaring and what the lang team has decided to
+//@ be true today. For example, non-`#[non_exhaustive]` enums with a single variant don't incur
+//@ a discriminant read today but do in this desugaring.
+//@ These topics are in flux so I'll keep the simple desugaring for now.
+//@
+//@ ### Slice patterns
+//@
+//@ Our desugaring of slice patterns is incorrect wrt borrow-checking, because the borrow-checker can
+//@ actually track the disjointness of borrows such as `let [ref mut x, ref mut y] = $place`.
+//@ To express this, we'd need a feature to represent indexing with indices that the borrow-checker is
+//@ allowed to inspect (it normally does not inspect indices).
+//@ MIR has such an operation.
+//@
+//@ [^1]: At least one difference is that rustc tests or-pattern alternatives after other patterns to
+//@ reduce duplicate work. So `matches!($x, (true|true, false))` is actually compiled to `matches!($x.1,
+//@ false) && matches!($x.0, true|true)`. There are also details around enums with only one variant.
+//@ Also constants in patterns get turned into patterns, which may behave differently than plain `==`
+//@ comparison does in terms of exact UB.
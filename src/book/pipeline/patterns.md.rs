@@ -0,0 +1,14 @@
+//@ # Pattern Desugarings
+//@
+//@ At the end of this series of steps no patterns remain and all bindings are declared uninitialized
+//@ (`let x;`/`let mut x;`).
+//@
+//@ > The rest of this section is a work-in-progress experiment about making the book executable.
+//@
+//@ ## Submodules
+#[path = "desugaring-bindings.md.rs"]
+pub mod desugaring_bindings;
+#[path = "match-desugaring.md.rs"]
+pub mod match_desugaring;
+#[path = "pattern-unnesting.md.rs"]
+pub mod pattern_unnesting;
@@ -0,0 +1,422 @@
+//@ # Intermediate Subexpression Elimination
+//@
+//@ In this step we add intermediate variables for every subexpression.
+//@
+//@ Specifically, if an expression that isn't a simple binding can be written `expr!($subexpr)`,
+//@ we rewrite it.
+//@ If `$subexpr` is a [value expression](https://nadrieril.github.io/blog/2025/12/06/on-places-and-their-magic.html),
+//@ we rewrite it to `{ let tmp = $subexpr; expr!(tmp) }`;
+//@ if it is a place expression,
+//@ we rewrite it to `{ let place tmp = $subexpr; expr!(tmp) }`;
+//@
+//@ We do this in an order that preserves normal left-to-right evaluation order.
+//@ We skip subexpressions that are constants.
+//@
+//@ ```rust,example
+//@ let mut vec = Vec::new();
+//@ vec.push(42);
+//@ vec[0] += 1;
+//@
+//@ // becomes before this step:
+//@ (*<Vec<_> as DerefMut>::deref_mut(&mut vec))[0] += 1;
+//@
+//@ // becomes after this step:
+//@ {
+//@     let tmp1 = &mut vec;
+//@     let tmp2 = <Vec<_> as DerefMut>::deref_mut(tmp1);
+//@     *tmp2 += 1;
+//@ }
+//@ ```
+//@
+//@ ```rust,example
+//@ let x = 1 + 2 + Some(3).as_ref().unwrap();
+//@
+//@ // becomes, before this step:
+//@ let x = {
+//@     let tmp1 = Some(3);
+//@     1 + <u32 as Add<&u32>>::add(2, Option::unwrap(Option::as_ref(&tmp1)))
+//@ };
+//@
+//@ // becomes, after this step:
+//@ let x = {
+//@     let tmp1 = Some(3);
+//@     let tmp2 = &tmp1;
+//@     let tmp3 = Option::as_ref(tmp2);
+//@     let tmp4 = Option::unwrap(tmp3);
+//@     let tmp5 = <u32 as Add<&u32>>::add(2, tmp4);
+//@     1 + tmp5
+//@ };
+//@ ```
+//@
+//@ An example that will be important for [Bounds Checks](./bound-checks.md):
+//@ ```rust,example
+//@ expr!($place[$i][$j])
+//@
+//@ // becomes
+//@ {
+//@     let place p = $place;
+//@     let i = $i;
+//@     let place q = p[i]; // order is important because the bound check happens here
+//@     let j = $j;
+//@     let place r = q[j];
+//@     expr!(r)
+//@ }
+//@ ```
+//@
+//@ At the end of this step, every [value
+//@ context](https://nadrieril.github.io/blog/2025/12/06/on-places-and-their-magic.html)
+//@ contains either a constant or a variable and every place expression is non-nested.
+//@
+//@ > The rest of this section is a work-in-progress experiment about making the book executable.
+//@
+//@ We only store in temporaries the subexpressions that the [Final Language](final-language.md.rs)
+//@ doesn't allow: a value expression in an operand position that isn't already an operand, i.e. a
+//@ constant or a place read `place_to_value!($place)`. The operand positions are the operands of
+//@ built-in operations, the arguments of calls, the fields of tuple, struct and enum variant
+//@ expressions, and `if` conditions. The subexpression `$subexpr` becomes `let tmp: $ty = $subexpr;`
+//@ and the operand becomes `place_to_value!(tmp)`. The value of `tmp` gets moved out by the operand,
+//@ so unlike in [Temporaries and Lifetime Extension](value-to-place.md.rs) its scope doesn't matter:
+//@ we declare it right before the statement or tail expression that contains the subexpression.
+//@
+//@ To preserve evaluation order:
+//@ - Places are read where they appear. When an operand needs temporaries, we read the places to its
+//@   left into temporaries too, before those;
+//@ - The branches of an `if` get their own temporaries. An `else if` gets wrapped in a block if its
+//@   condition needs some;
+//@ - The operands of a `let`-chain after the first one are only evaluated if the previous ones
+//@   succeed. We bind their temporaries with extra `let` conditions right before them, the way
+//@   [Temporaries and Lifetime Extension](value-to-place.md.rs) does for `let` scrutinees:
+//@   ```rust,example
+//@   if let x = $place && f(place_to_value!(x)) == 1 { .. }
+//@   // becomes
+//@   if let x = $place && let tmp1 = f(place_to_value!(x)) && let tmp2 = place_to_value!(tmp1) == 1
+//@       && place_to_value!(tmp2) { .. }
+//@   ```
+//@
+//@ The pattern desugarings introduce comparisons of their own, so we run this step after them.
+use crate::desugarings::control_flow::boolean_operators::is_let_chain; //#
+use crate::desugarings::*; //#
+use crate::interactive_example; //#
+use std::collections::HashMap; //#
+
+interactive_example! {
+    eliminate_subexpressions,
+    fn main() {
+        let x = 1;
+        let y = x + 2 * x;
+        print(y);
+        if let z = x && z + 1 == y {
+            print(z);
+        }
+    }
+}
+pub fn eliminate_subexpressions(program: &mut Program) -> Result<(), CompilationError> {
+    let mut eliminator = Eliminator {
+        names: NameSupply::new(program),
+        types: type_checking::node_types(program)?,
+    };
+    program.visit_all_mut_infallible(|function: &mut Function| {
+        if let FunctionBody::Block(body) = &mut function.body {
+            eliminator.block(body);
+        }
+    });
+    Ok(())
+}
+
+/// Stores the nested subexpressions of function bodies in temporaries.
+struct Eliminator {
+    names: NameSupply,
+    /// The types of the nodes of the program, to write the types of the temporaries.
+    types: HashMap<NodeId, Type>,
+}
+
+/// A temporary that holds `value`, materialized as `let $name: $ty = $value;`.
+struct Temporary {
+    name: Identifier,
+    ty: Option<Type>,
+    value: Expression,
+}
+
+impl Eliminator {
+    fn block(&mut self, block: &mut BlockExpression) {
+        let statements = std::mem::take(&mut block.statements);
+        for mut statement in statements {
+            let mut temporaries = Vec::new();
+            match &mut statement {
+                Statement::Let {
+                    initial_value,
+                    else_branch,
+                    ..
+                } => {
+                    if let Some(value) = initial_value {
+                        self.value(value, &mut temporaries);
+                    }
+                    if let Some(else_branch) = else_branch {
+                        self.block(else_branch);
+                    }
+                }
+                Statement::Expr(expression) => self.value(expression, &mut temporaries),
+                Statement::Empty | Statement::Item(_) => {}
+            }
+            block.statements.extend(declarations(temporaries));
+            block.statements.push(statement);
+        }
+        if let Some(tail) = &mut block.tail {
+            let mut temporaries = Vec::new();
+            self.value(tail, &mut temporaries);
+            block.statements.extend(declarations(temporaries));
+        }
+    }
+
+    /// Make the subexpressions of this expression, which is in a value context, into operands. The
+    /// temporaries this needs are pushed to `temporaries`, in evaluation order.
+    fn value(&mut self, expression: &mut Expression, temporaries: &mut Vec<Temporary>) {
+        match &mut expression.kind {
+            ExpressionKind::Operator(operator) => match &mut **operator {
+                OperatorExpression::Negation(_, operand)
+                | OperatorExpression::CompoundAssignment(_, _, operand) => {
+                    self.operand(operand, temporaries)
+                }
+                OperatorExpression::Binary(_, left, right)
+                | OperatorExpression::Comparison(_, left, right) => {
+                    self.operands([left, right], temporaries)
+                }
+                OperatorExpression::Assignment(_, value) => self.value(value, temporaries),
+                // Only places here.
+                OperatorExpression::Borrow(_) | OperatorExpression::Dereference(_) => {}
+                // Only left in `let`-chains, which we handle with the conditions.
+                OperatorExpression::LazyAnd(..) | OperatorExpression::LazyOr(..) => {}
+            },
+            ExpressionKind::Grouped(inner) => self.value(inner, temporaries),
+            ExpressionKind::Tuple(elements) => self.operands(elements, temporaries),
+            ExpressionKind::Struct(adt) => self.operands(
+                adt.fields.iter_mut().map(|field| &mut field.value),
+                temporaries,
+            ),
+            ExpressionKind::EnumVariant(variant) => self.operands(
+                variant.fields.iter_mut().map(|field| &mut field.value),
+                temporaries,
+            ),
+            ExpressionKind::Call(call) => self.operands(&mut call.args, temporaries),
+            ExpressionKind::Block(block) => self.block(block),
+            ExpressionKind::If(if_expression) => {
+                self.condition(&mut if_expression.condition, temporaries);
+                self.branch(&mut if_expression.then_branch);
+                if let Some(else_branch) = &mut if_expression.else_branch {
+                    self.branch(else_branch);
+                }
+            }
+            ExpressionKind::Loop(loop_expression) => match &mut loop_expression.kind {
+                LoopKind::Infinite(body) | LoopKind::While { body, .. } => self.block(body),
+            },
+            ExpressionKind::Break(break_expression) => {
+                if let Some(value) = &mut break_expression.value {
+                    self.value(value, temporaries);
+                }
+            }
+            // Desugared already, or only places and constants.
+            ExpressionKind::Match(_)
+            | ExpressionKind::Let(_)
+            | ExpressionKind::Literal(_)
+            | ExpressionKind::Path(_)
+            | ExpressionKind::Continue(_)
+            | ExpressionKind::TupleIndexing(_)
+            | ExpressionKind::Field(_)
+            | ExpressionKind::EnumProjection(_)
+            | ExpressionKind::Discriminant(_)
+            | ExpressionKind::VariantPath(_)
+            | ExpressionKind::DiscriminantOf(_)
+            | ExpressionKind::Virtual(_) => {}
+        }
+    }
+
+    /// Make this subexpression into an operand, storing it in a temporary if it isn't one
+    /// already.
+    fn operand(&mut self, operand: &mut Expression, temporaries: &mut Vec<Temporary>) {
+        self.value(operand, temporaries);
+        if !is_operand(operand) {
+            let temporary = self.temporary(operand);
+            temporaries.push(temporary);
+        }
+    }
+
+    /// Make these subexpressions, evaluated from left to right, into operands. Once an operand
+    /// needs temporaries, the places read to its left must be read before these, so we store them
+    /// in temporaries too.
+    fn operands<'a>(
+        &mut self,
+        operands: impl IntoIterator<Item = &'a mut Expression>,
+        temporaries: &mut Vec<Temporary>,
+    ) {
+        // The place reads we haven't stored yet, with the number of temporaries before them.
+        let mut reads = Vec::new();
+        for operand in operands {
+            let before = temporaries.len();
+            self.operand(operand, temporaries);
+            if temporaries.len() == before {
+                if let ExpressionKind::Virtual(VirtualExpression::PlaceToValueCoercion(_)) =
+                    operand.kind
+                {
+                    reads.push((operand, before));
+                }
+            } else {
+                for (read, position) in reads.drain(..).rev() {
+                    let temporary = self.temporary(read);
+                    temporaries.insert(position, temporary);
+                }
+            }
+        }
+    }
+
+    /// Make the subexpressions of an `if` condition into operands. Its temporaries go to
+    /// `temporaries`, apart from those of a `let`-chain.
+    fn condition(&mut self, condition: &mut Expression, temporaries: &mut Vec<Temporary>) {
+        if is_let_chain(condition) {
+            let first = self.chain(condition);
+            bind_before(condition, first);
+        } else {
+            self.operand(condition, temporaries);
+        }
+    }
+
+    /// Make the subexpressions of the `let`-chain `condition` into operands, binding their
+    /// temporaries with `let` conditions right before the operand they come from. The chain nests
+    /// to the left, so we return the temporaries of its first operand for the caller to bind.
+    fn chain(&mut self, condition: &mut Expression) -> Vec<Temporary> {
+        let origin = condition.origin.clone();
+        let mut temporaries = Vec::new();
+        if let ExpressionKind::Operator(operator) = &mut condition.kind
+            && let OperatorExpression::LazyAnd(left, right) = &mut **operator
+        {
+            let first = self.chain(left);
+            bind_before(left, first);
+            for binding in bindings(self.chain(right)) {
+                *left = Expression {
+                    origin: origin.derive(),
+                    ..LetExpression::and(take(left), binding)
+                };
+            }
+        } else if let ExpressionKind::Let(let_expression) = &mut condition.kind {
+            self.value(&mut let_expression.scrutinee, &mut temporaries);
+        } else {
+            self.operand(condition, &mut temporaries);
+        }
+        temporaries
+    }
+
+    /// Make the subexpressions of an `if` branch into operands. It is only evaluated if the
+    /// condition says so, so it gets its own temporaries.
+    fn branch(&mut self, branch: &mut Expression) {
+        let mut temporaries = Vec::new();
+        self.value(branch, &mut temporaries);
+        if !temporaries.is_empty() {
+            let (span, origin) = (branch.span, branch.origin.clone());
+            let value = take(branch);
+            *branch = Expression {
+                span,
+                origin: origin.derive(),
+                ..BlockExpression::derived_from(&origin, declarations(temporaries), Some(value))
+                    .into()
+            };
+        }
+    }
+
+    /// Move `expression` to a new temporary, and read the temporary in its place.
+    fn temporary(&mut self, expression: &mut Expression) -> Temporary {
+        let name = self.names.fresh("tmp");
+        let (span, origin) = (expression.span, expression.origin.clone());
+        let path = Expression {
+            origin: origin.derive(),
+            ..Expression::new(ExpressionKind::Path(name.clone()))
+        };
+        let coercion = VirtualExpression::PlaceToValueCoercion(Box::new(path));
+        let read = Expression {
+            span,
+            origin: origin.derive(),
+            ..Expression::new(ExpressionKind::Virtual(coercion))
+        };
+        let value = std::mem::replace(expression, read);
+        Temporary {
+            name,
+            ty: self.types.get(&value.origin.id).cloned(),
+            value,
+        }
+    }
+}
+
+/// Whether this expression is an operand of the final language: a place read or a constant.
+pub(crate) fn is_operand(expression: &Expression) -> bool {
+    matches!(
+        expression.kind,
+        ExpressionKind::Literal(_)
+            | ExpressionKind::VariantPath(_)
+            | ExpressionKind::DiscriminantOf(_)
+            | ExpressionKind::Virtual(VirtualExpression::PlaceToValueCoercion(_))
+    )
+}
+
+/// The `let` statements that declare these temporaries.
+fn declarations(temporaries: Vec<Temporary>) -> Vec<Statement> {
+    temporaries.into_iter().map(declaration).collect()
+}
+
+fn declaration(temporary: Temporary) -> Statement {
+    let Temporary {
+        name,
+        mut ty,
+        value,
+    } = temporary;
+    let origin = value.origin.clone();
+    ty.visit_all_mut_infallible(|ty: &mut Type| ty.origin = origin.derive());
+    Statement::Let {
+        attrs: vec![],
+        scope: None,
+        pattern: Pattern::derived_from(&origin, PatternKind::Identifier(name)),
+        ty,
+        initial_value: Some(value),
+        else_branch: None,
+        span: Span::dummy(),
+        origin: origin.derive(),
+    }
+}
+
+/// Bind these temporaries with `let` conditions before the `let`-chain operand `condition`.
+fn bind_before(condition: &mut Expression, temporaries: Vec<Temporary>) {
+    if temporaries.is_empty() {
+        return;
+    }
+    let origin = condition.origin.clone();
+    *condition = bindings(temporaries)
+        .chain([take(condition)])
+        .reduce(|left, right| Expression {
+            origin: origin.derive(),
+            ..LetExpression::and(left, right)
+        })
+        .unwrap();
+}
+
+/// The `let` conditions that bind these temporaries.
+fn bindings(temporaries: Vec<Temporary>) -> impl Iterator<Item = Expression> {
+    temporaries
+        .into_iter()
+        .map(|Temporary { name, value, .. }| {
+            let origin = value.origin.clone();
+            Expression {
+                origin: origin.derive(),
+                ..Expression::new(ExpressionKind::Let(LetExpression {
+                    pattern: Pattern::derived_from(&origin, PatternKind::Identifier(name)),
+                    scrutinee: Box::new(value),
+                }))
+            }
+        })
+}
+
+fn take(expression: &mut Expression) -> Expression {
+    std::mem::replace(expression, Expression::new(ExpressionKind::Tuple(vec![])))
+}
+//@ ---
+//@
+//@ ## Discussion
+//@
+//@ We don't really have to skip constants. I just did it that way because MIR allows constants
+//@ a operands. I think it's a practical matter of not ending up with billions of variables.
@@ -0,0 +1,1168 @@
+//@ # Type Checking
+//@
+//@ > This section is a work-in-progress experiment about making the book executable.
+//@
+//@ Some desugarings need to know the types of expressions: [Desugaring
+//@ Bindings](desugaring-bindings.md.rs) writes the type of every binding explicitly, and the
+//@ backends need types to lay out their locals. This step computes them, and rejects ill-typed
+//@ programs along the way.
+//@
+//@ We run it right after the [Misc Expression Desugarings](misc-expr-desugarings.md.rs), so that type
+//@ errors are reported on something close to the source program. The later steps preserve types,
+//@ which lets us run it again on the desugared program to learn the types of the temporaries they
+//@ introduced.
+//@
+//@ Type checking is also what tells an [enum projection](../language/expressions/enum-exprs.md.rs)
+//@ `$place.Variant.field` apart from two nested field accesses: once it knows the type of
+//@ `$place`, it rewrites the projections it finds into `EnumProjection` nodes that record the
+//@ full path of their variant.
+//@
+//@ Our subset has no generics, traits or coercions, so inference is plain unification, one function
+//@ at a time: a type we don't know yet is an inference variable, and every use of a value at a given
+//@ type unifies the two. Two kinds of variables are special:
+//@ - the type of an unsuffixed integer literal can only be an integer type, and defaults to `i32`
+//@   [ref:expr.literal.int.infer];
+//@ - the type of a diverging expression (`break`, `continue`, a `loop` that is never exited, ...)
+//@   can be anything, and defaults to `()`.
+//@
+//@ Any other binding whose type is still unknown at the end of its function is an error.
+//@
+//@ Errors point at the innermost expression, statement or item being checked.
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::desugarings::*;
+
+pub fn check_types(program: &mut Program) -> Result<(), CompilationError> {
+    let types = node_types(program)?;
+    program.resolve_enum_projections(&types);
+    Ok(())
+}
+
+/// The types of the nodes of the program, by node id: the type of every `let` statement, of every
+/// variable by the id of the identifier pattern that binds it, and of every expression whose type is
+/// fully known. The program must have gotten its node ids.
+pub fn node_types(program: &Program) -> Result<HashMap<NodeId, Type>, CompilationError> {
+    let items = Items::collect(program);
+    let mut types = HashMap::new();
+    for item in &program.items {
+        if let ItemKind::Function(function) = &item.kind {
+            FunctionChecker::new(&items, &mut types)
+                .check_function(function)
+                .map_err(|error| error.with_span(item.span))?;
+        }
+    }
+    Ok(types)
+}
+
+fn type_error(message: impl Into<String>) -> CompilationError {
+    CompilationError::Type(Diagnostic::new(message))
+}
+
+//@ ## Types
+//@
+//@ During inference we use our own representation of types, which has inference variables and
+//@ forgets lifetimes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Ty {
+    Bool,
+    Str,
+    Int(IntType),
+    Tuple(Vec<Ty>),
+    SelfTy,
+    Ref(Mutability, Box<Ty>),
+    /// A struct or an enum.
+    Adt(Identifier),
+    Var(usize),
+}
+
+impl Ty {
+    fn unit() -> Ty {
+        Ty::Tuple(vec![])
+    }
+}
+
+enum Variable {
+    Unknown(VariableKind),
+    Known(Ty),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VariableKind {
+    /// Can be any type.
+    General,
+    /// Can only be an integer type.
+    Integer,
+    /// The type of a diverging expression.
+    Diverging,
+}
+
+impl VariableKind {
+    /// The kind of the variable we get by unifying two variables.
+    fn merge(self, other: VariableKind) -> VariableKind {
+        match (self, other) {
+            (VariableKind::Integer, _) | (_, VariableKind::Integer) => VariableKind::Integer,
+            (VariableKind::General, _) | (_, VariableKind::General) => VariableKind::General,
+            (VariableKind::Diverging, VariableKind::Diverging) => VariableKind::Diverging,
+        }
+    }
+}
+
+//@ ## Items
+//@
+//@ Functions, structs and enums can be used anywhere in the program, including before their
+//@ definition or from a nested function, so we collect them all first.
+struct Items {
+    functions: HashMap<Identifier, Signature>,
+    structs: HashMap<Identifier, StructFields>,
+    enums: HashMap<Identifier, Enum>,
+}
+
+struct Signature {
+    parameters: Vec<Type>,
+    /// Whether the function takes extra arguments after `parameters`, like `extern` functions
+    /// ending in `...`.
+    variadic: bool,
+    return_type: Type,
+}
+
+impl Items {
+    fn collect(program: &Program) -> Self {
+        let mut items = Items {
+            functions: HashMap::new(),
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+        };
+        program.visit_all_infallible(|function: &Function| {
+            let signature = Signature {
+                parameters: function
+                    .parameters
+                    .iter()
+                    .filter_map(parameter_type)
+                    .collect(),
+                variadic: function.parameters.iter().any(|parameter| {
+                    matches!(
+                        parameter.kind,
+                        FunctionParamKind::Regular {
+                            ty: FunctionParamType::Variadic,
+                            ..
+                        }
+                    )
+                }),
+                return_type: function.return_type.clone().unwrap_or_else(Type::mk_unit),
+            };
+            items.functions.insert(function.name.clone(), signature);
+        });
+        program.visit_all_infallible(|adt: &Struct| {
+            items.structs.insert(adt.name.clone(), adt.fields.clone());
+        });
+        program.visit_all_infallible(|adt: &Enum| {
+            items.enums.insert(adt.name.clone(), adt.clone());
+        });
+        items
+    }
+
+    fn lower_type(&self, ty: &Type) -> Result<Ty, CompilationError> {
+        Ok(match &ty.kind {
+            TypeKind::Bool => Ty::Bool,
+            TypeKind::Str => Ty::Str,
+            TypeKind::Int(ty) => Ty::Int(*ty),
+            TypeKind::Tuple(types) => Ty::Tuple(
+                types
+                    .iter()
+                    .map(|ty| self.lower_type(ty))
+                    .collect::<Result<_, _>>()?,
+            ),
+            TypeKind::TraitSelf => Ty::SelfTy,
+            TypeKind::Ref(_, mutability, ty) => {
+                Ty::Ref(*mutability, Box::new(self.lower_type(ty)?))
+            }
+            TypeKind::Path(name, _)
+                if self.structs.contains_key(name) || self.enums.contains_key(name) =>
+            {
+                Ty::Adt(name.clone())
+            }
+            TypeKind::Path(name, span) => {
+                return Err(
+                    type_error(format!("cannot find type `{name}` in this scope")).with_span(*span),
+                );
+            }
+        })
+    }
+
+    fn variant_fields(&self, path: &VariantPath) -> Result<&StructFields, CompilationError> {
+        let Some(adt) = self.enums.get(&path.enum_name) else {
+            return Err(type_error(format!(
+                "cannot find enum `{}` in this scope",
+                path.enum_name
+            )));
+        };
+        match adt.variant(&path.variant) {
+            Some((_, variant)) => Ok(&variant.fields),
+            None => Err(type_error(format!(
+                "no variant named `{}` in enum `{}`",
+                path.variant, path.enum_name
+            ))),
+        }
+    }
+}
+
+/// The type of this parameter, or `None` for the `...` of a variadic function.
+fn parameter_type(parameter: &FunctionParam) -> Option<Type> {
+    match &parameter.kind {
+        FunctionParamKind::Regular {
+            ty: FunctionParamType::Type(ty),
+            ..
+        } => Some(ty.clone()),
+        FunctionParamKind::Regular {
+            ty: FunctionParamType::Variadic,
+            ..
+        } => None,
+        FunctionParamKind::SelfParam { ty, .. } => {
+            Some(ty.clone().unwrap_or(Type::new(TypeKind::TraitSelf)))
+        }
+        FunctionParamKind::RefSelfShorthand {
+            lifetime,
+            mutability,
+        } => Some(Type::new(TypeKind::Ref(
+            lifetime.clone(),
+            *mutability,
+            Box::new(Type::new(TypeKind::TraitSelf)),
+        ))),
+    }
+}
+
+//@ ## Functions
+struct FunctionChecker<'a> {
+    items: &'a Items,
+    /// The types of the nodes of the program, which we fill once the function is done.
+    types: &'a mut HashMap<NodeId, Type>,
+    /// The `let` statements of this function.
+    bindings: Vec<Binding>,
+    /// The other nodes of this function whose type we record.
+    typed_nodes: Vec<(NodeId, Ty)>,
+    variables: Vec<Variable>,
+    scopes: Vec<Scope>,
+    /// The enclosing loops and labeled blocks, innermost last.
+    breakables: Vec<Breakable>,
+}
+
+#[derive(Default)]
+struct Scope {
+    label: Option<String>,
+    locals: HashMap<Identifier, Ty>,
+}
+
+struct Binding {
+    /// The id of this `let`.
+    id: NodeId,
+    pattern: Pattern,
+    ty: Ty,
+    span: Span,
+}
+
+struct Breakable {
+    label: Option<String>,
+    is_loop: bool,
+    /// The type of the loop or block, which the values of `break` must have.
+    ty: Ty,
+}
+
+impl<'a> FunctionChecker<'a> {
+    fn new(items: &'a Items, types: &'a mut HashMap<NodeId, Type>) -> Self {
+        FunctionChecker {
+            items,
+            types,
+            bindings: vec![],
+            typed_nodes: vec![],
+            variables: vec![],
+            scopes: vec![],
+            breakables: vec![],
+        }
+    }
+
+    fn check_function(mut self, function: &Function) -> Result<(), CompilationError> {
+        let FunctionBody::Block(body) = &function.body else {
+            return Ok(());
+        };
+        self.scopes.push(Scope::default());
+        for parameter in &function.parameters {
+            let Some(ty) = parameter_type(parameter) else {
+                continue;
+            };
+            let ty = self.items.lower_type(&ty)?;
+            match &parameter.kind {
+                FunctionParamKind::Regular {
+                    pattern: Some(pattern),
+                    ..
+                } => self.bind_pattern(pattern, &ty, 0)?,
+                FunctionParamKind::Regular { pattern: None, .. } => {}
+                FunctionParamKind::SelfParam { .. }
+                | FunctionParamKind::RefSelfShorthand { .. } => {
+                    self.scopes[0].locals.insert(Identifier::new("self"), ty);
+                }
+            }
+        }
+        let return_type = match &function.return_type {
+            Some(ty) => self.items.lower_type(ty)?,
+            None => Ty::unit(),
+        };
+        let tail_span = body.tail.as_ref().map_or(Span::dummy(), |tail| tail.span);
+        let body = self.check_block(body)?;
+        self.unify(&return_type, &body)
+            .map_err(|error| error.with_span(tail_span))?;
+        self.finish()
+    }
+
+    /// Apply the defaults to the remaining inference variables, and record the node types.
+    fn finish(mut self) -> Result<(), CompilationError> {
+        for variable in &mut self.variables {
+            match variable {
+                Variable::Unknown(VariableKind::Integer) => {
+                    *variable = Variable::Known(Ty::Int(IntType::I32))
+                }
+                Variable::Unknown(VariableKind::Diverging) => {
+                    *variable = Variable::Known(Ty::unit())
+                }
+                Variable::Unknown(VariableKind::General) | Variable::Known(_) => {}
+            }
+        }
+        for binding in &self.bindings {
+            let Some(ty) = self.to_type(&binding.ty) else {
+                return Err(type_error(format!(
+                    "type annotations needed for `{}`",
+                    binding.pattern
+                ))
+                .with_span(binding.span));
+            };
+            self.types.insert(binding.id, ty);
+        }
+        for (id, ty) in &self.typed_nodes {
+            if let Some(ty) = self.to_type(ty) {
+                self.types.insert(*id, ty);
+            }
+        }
+        Ok(())
+    }
+
+    fn check_block(&mut self, block: &BlockExpression) -> Result<Ty, CompilationError> {
+        let Some(label) = &block.label else {
+            return self.check_block_contents(block);
+        };
+        let ty = self.fresh(VariableKind::Diverging);
+        self.breakables.push(Breakable {
+            label: Some(label.clone()),
+            is_loop: false,
+            ty: ty.clone(),
+        });
+        let contents = self.check_block_contents(block)?;
+        self.breakables.pop();
+        self.unify(&ty, &contents)?;
+        Ok(ty)
+    }
+
+    //@ A block has the type of its tail expression, or `()` if it has none. A block without tail
+    //@ that contains a diverging expression statement diverges too.
+    fn check_block_contents(&mut self, block: &BlockExpression) -> Result<Ty, CompilationError> {
+        self.scopes.push(Scope {
+            label: block.label.clone(),
+            locals: HashMap::new(),
+        });
+        let mut diverges = false;
+        for statement in &block.statements {
+            diverges |= self.check_statement(statement)?;
+        }
+        let ty = match &block.tail {
+            Some(tail) => self.check_expression(tail)?,
+            None if diverges => self.fresh(VariableKind::Diverging),
+            None => Ty::unit(),
+        };
+        self.scopes.pop();
+        Ok(ty)
+    }
+
+    /// Check this statement, and return whether it diverges.
+    fn check_statement(&mut self, statement: &Statement) -> Result<bool, CompilationError> {
+        self.check_statement_kind(statement)
+            .map_err(|error| error.with_span(statement.span()))
+    }
+
+    fn check_statement_kind(&mut self, statement: &Statement) -> Result<bool, CompilationError> {
+        match statement {
+            Statement::Empty => Ok(false),
+            Statement::Item(Item {
+                kind: ItemKind::Function(function),
+                ..
+            }) => {
+                // Nested functions can't see the locals of the enclosing function.
+                FunctionChecker::new(self.items, self.types).check_function(function)?;
+                Ok(false)
+            }
+            Statement::Item(_) => Ok(false),
+            Statement::Let {
+                scope,
+                pattern,
+                ty,
+                initial_value,
+                else_branch,
+                span,
+                origin,
+                ..
+            } => {
+                let binding_ty = match ty {
+                    Some(ty) => self.items.lower_type(ty)?,
+                    None => self.fresh(VariableKind::General),
+                };
+                if let Some(value) = initial_value {
+                    let value = self.check_expression(value)?;
+                    self.unify(&binding_ty, &value)?;
+                }
+                if let Some(else_branch) = else_branch {
+                    let else_ty = self.check_block(else_branch)?;
+                    if !self.is_diverging(&else_ty) {
+                        return Err(type_error("`else` clause of `let...else` does not diverge"));
+                    }
+                }
+                let scope = match scope {
+                    Some(label) => self.scope_index(label)?,
+                    None => self.scopes.len() - 1,
+                };
+                self.bind_pattern(pattern, &binding_ty, scope)?;
+                self.bindings.push(Binding {
+                    id: origin.id,
+                    pattern: pattern.clone(),
+                    ty: binding_ty,
+                    span: *span,
+                });
+                Ok(false)
+            }
+            Statement::Expr(expression) => {
+                let ty = self.check_expression(expression)?;
+                Ok(self.is_diverging(&ty))
+            }
+        }
+    }
+
+    /// The index of the scope of the block with this label, for scoped `let`s.
+    fn scope_index(&self, label: &str) -> Result<usize, CompilationError> {
+        self.scopes
+            .iter()
+            .rposition(|scope| scope.label.as_deref() == Some(label))
+            .ok_or_else(|| type_error(format!("use of undeclared label `{label}`")))
+    }
+
+    //@ ## Patterns
+    //@
+    //@ Checking a pattern against the type of the matched value declares the variables it binds
+    //@ in the given scope.
+    fn bind_pattern(
+        &mut self,
+        pattern: &Pattern,
+        ty: &Ty,
+        scope: usize,
+    ) -> Result<(), CompilationError> {
+        match &pattern.kind {
+            PatternKind::Identifier(name) => {
+                self.scopes[scope].locals.insert(name.clone(), ty.clone());
+                self.typed_nodes.push((pattern.origin.id, ty.clone()));
+            }
+            PatternKind::Wildcard => {}
+            PatternKind::Literal(literal) => {
+                let literal = self.literal_type(literal);
+                self.unify(ty, &literal)?;
+            }
+            PatternKind::Tuple(patterns) => {
+                let elements: Vec<Ty> = patterns
+                    .iter()
+                    .map(|_| self.fresh(VariableKind::General))
+                    .collect();
+                self.unify(ty, &Ty::Tuple(elements.clone()))?;
+                for (pattern, ty) in patterns.iter().zip(&elements) {
+                    self.bind_pattern(pattern, ty, scope)?;
+                }
+            }
+            PatternKind::Variant(variant) => {
+                let path = &variant.path;
+                let declared = self.items.variant_fields(path)?.field_list();
+                self.unify(ty, &Ty::Adt(path.enum_name.clone()))?;
+                let mut provided = vec![];
+                for field in &variant.fields {
+                    let Some((_, ty)) = declared
+                        .iter()
+                        .find(|(declared_name, _)| *declared_name == field.name)
+                    else {
+                        return Err(type_error(format!(
+                            "variant `{path}` does not have a field named `{}`",
+                            field.name
+                        )));
+                    };
+                    if provided.contains(&&field.name) {
+                        return Err(type_error(format!(
+                            "field `{}` bound more than once in the same pattern",
+                            field.name
+                        )));
+                    }
+                    provided.push(&field.name);
+                    let ty = self.items.lower_type(ty)?;
+                    self.bind_pattern(&field.pattern, &ty, scope)?;
+                }
+                if let Some((missing, _)) = declared
+                    .iter()
+                    .find(|(field, _)| !provided.contains(&field))
+                {
+                    return Err(type_error(format!(
+                        "pattern does not mention field `{missing}`"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    //@ ## Expressions
+    fn check_expression(&mut self, expression: &Expression) -> Result<Ty, CompilationError> {
+        let ty = self
+            .check_expression_kind(&expression.kind)
+            .map_err(|error| error.with_span(expression.span))?;
+        self.typed_nodes.push((expression.origin.id, ty.clone()));
+        Ok(ty)
+    }
+
+    fn check_expression_kind(&mut self, kind: &ExpressionKind) -> Result<Ty, CompilationError> {
+        match kind {
+            ExpressionKind::Literal(literal) => Ok(self.literal_type(literal)),
+            ExpressionKind::Path(name) => self.check_path(name),
+            ExpressionKind::Operator(operator) => self.check_operator(operator),
+            ExpressionKind::Grouped(inner) => self.check_expression(inner),
+            ExpressionKind::Block(block) => self.check_block(block),
+            //@ The bindings of the `let`s in the condition of an `if` are in scope in its `then`
+            //@ branch [ref:expr.if.chains].
+            ExpressionKind::If(if_expression) => {
+                self.scopes.push(Scope::default());
+                let condition = self.check_expression(&if_expression.condition)?;
+                self.unify(&Ty::Bool, &condition)?;
+                let then_ty = self.check_expression(&if_expression.then_branch)?;
+                self.scopes.pop();
+                let else_ty = match &if_expression.else_branch {
+                    Some(else_branch) => self.check_expression(else_branch)?,
+                    None => Ty::unit(),
+                };
+                self.unify(&then_ty, &else_ty)?;
+                Ok(then_ty)
+            }
+            ExpressionKind::Let(let_expression) => {
+                let scrutinee = self.check_expression(&let_expression.scrutinee)?;
+                let scope = self.scopes.len() - 1;
+                self.bind_pattern(&let_expression.pattern, &scrutinee, scope)?;
+                Ok(Ty::Bool)
+            }
+            ExpressionKind::Loop(loop_expression) => self.check_loop(loop_expression),
+            ExpressionKind::Match(match_expression) => {
+                let scrutinee = self.check_expression(&match_expression.scrutinee)?;
+                let ty = self.fresh(VariableKind::Diverging);
+                for arm in &match_expression.arms {
+                    self.scopes.push(Scope::default());
+                    let scope = self.scopes.len() - 1;
+                    self.bind_pattern(&arm.pattern, &scrutinee, scope)?;
+                    if let Some(guard) = &arm.guard {
+                        let guard = self.check_expression(guard)?;
+                        self.unify(&Ty::Bool, &guard)?;
+                    }
+                    let body = self.check_expression(&arm.body)?;
+                    self.unify(&ty, &body)?;
+                    self.scopes.pop();
+                }
+                Ok(ty)
+            }
+            ExpressionKind::Break(break_expression) => {
+                let target = match &break_expression.label {
+                    Some(label) => self.labeled_breakable(label)?,
+                    None => self.innermost_loop("break")?,
+                };
+                let target = self.breakables[target].ty.clone();
+                let value = match &break_expression.value {
+                    Some(value) => self.check_expression(value)?,
+                    None => Ty::unit(),
+                };
+                self.unify(&target, &value)?;
+                Ok(self.fresh(VariableKind::Diverging))
+            }
+            ExpressionKind::Continue(continue_expression) => {
+                if let Some(label) = &continue_expression.label {
+                    let target = self.labeled_breakable(label)?;
+                    if !self.breakables[target].is_loop {
+                        return Err(type_error(format!(
+                            "`continue` pointing to a labeled block `{label}`"
+                        )));
+                    }
+                } else {
+                    self.innermost_loop("continue")?;
+                }
+                Ok(self.fresh(VariableKind::Diverging))
+            }
+            ExpressionKind::Tuple(elements) => Ok(Ty::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.check_expression(element))
+                    .collect::<Result<_, _>>()?,
+            )),
+            ExpressionKind::TupleIndexing(tuple_indexing) => self.check_field(
+                &tuple_indexing.expression,
+                &FieldName::Index(tuple_indexing.index),
+            ),
+            ExpressionKind::Struct(struct_expression) => {
+                let Some(fields) = self.items.structs.get(&struct_expression.path) else {
+                    return Err(type_error(format!(
+                        "cannot find struct `{}` in this scope",
+                        struct_expression.path
+                    )));
+                };
+                self.check_struct_fields(
+                    &struct_expression.path,
+                    fields,
+                    &struct_expression.fields,
+                )?;
+                Ok(Ty::Adt(struct_expression.path.clone()))
+            }
+            ExpressionKind::VariantPath(path) => {
+                let fields = self.items.variant_fields(path)?;
+                self.check_struct_fields(&path.to_string(), fields, &[])?;
+                Ok(Ty::Adt(path.enum_name.clone()))
+            }
+            ExpressionKind::EnumVariant(variant) => {
+                let fields = self.items.variant_fields(&variant.path)?;
+                self.check_struct_fields(&variant.path.to_string(), fields, &variant.fields)?;
+                Ok(Ty::Adt(variant.path.enum_name.clone()))
+            }
+            ExpressionKind::Field(field) => {
+                self.check_field(&field.expression, &FieldName::Named(field.field.clone()))
+            }
+            ExpressionKind::EnumProjection(projection) => {
+                let base = self.check_expression(&projection.expression)?;
+                let base = self.autoderef(&base);
+                if base != Ty::Adt(projection.variant.enum_name.clone()) {
+                    return Err(type_error(format!(
+                        "no variant `{}` on type `{}`",
+                        projection.variant.variant,
+                        self.describe(&base)
+                    )));
+                }
+                self.variant_field_type(&projection.variant, &projection.field)
+            }
+            //@ Discriminants have type `isize`, like the default representation of enums.
+            ExpressionKind::Discriminant(discriminant) => {
+                let base = self.check_expression(&discriminant.expression)?;
+                match self.shallow_resolve(&base) {
+                    Ty::Adt(name) if self.items.enums.contains_key(&name) => {
+                        Ok(Ty::Int(IntType::Isize))
+                    }
+                    _ => Err(type_error(format!(
+                        "`enum#discriminant` expects an enum, found `{}`",
+                        self.describe(&base)
+                    ))),
+                }
+            }
+            ExpressionKind::DiscriminantOf(path) => {
+                self.items.variant_fields(path)?;
+                Ok(Ty::Int(IntType::Isize))
+            }
+            ExpressionKind::Call(call) => self.check_call(call),
+            ExpressionKind::Virtual(
+                VirtualExpression::ValueToPlaceCoercion(inner)
+                | VirtualExpression::PlaceToValueCoercion(inner),
+            ) => self.check_expression(inner),
+            ExpressionKind::Virtual(VirtualExpression::Unreachable) => {
+                Ok(self.fresh(VariableKind::Diverging))
+            }
+        }
+    }
+
+    fn literal_type(&mut self, literal: &LiteralExpression) -> Ty {
+        match literal {
+            LiteralExpression::String(_) => Ty::Ref(Mutability::Immutable, Box::new(Ty::Str)),
+            LiteralExpression::Integer(IntegerLiteral {
+                suffix: Some(ty), ..
+            }) => Ty::Int(*ty),
+            LiteralExpression::Integer(IntegerLiteral { suffix: None, .. }) => {
+                self.fresh(VariableKind::Integer)
+            }
+            LiteralExpression::Bool(_) => Ty::Bool,
+        }
+    }
+
+    fn check_path(&mut self, name: &Identifier) -> Result<Ty, CompilationError> {
+        if let Some(ty) = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.locals.get(name))
+        {
+            return Ok(ty.clone());
+        }
+        if self.items.functions.contains_key(name) {
+            return Err(type_error(format!(
+                "function `{name}` can only be called, we don't support function values"
+            )));
+        }
+        Err(type_error(format!(
+            "cannot find value `{name}` in this scope"
+        )))
+    }
+
+    fn check_operator(&mut self, operator: &OperatorExpression) -> Result<Ty, CompilationError> {
+        match operator {
+            OperatorExpression::Borrow(borrow) => {
+                let ty = self.check_expression(&borrow.expression)?;
+                Ok(Ty::Ref(borrow.mutability, Box::new(ty)))
+            }
+            OperatorExpression::Dereference(dereference) => {
+                let ty = self.check_expression(&dereference.expression)?;
+                match self.shallow_resolve(&ty) {
+                    Ty::Ref(_, pointee) => Ok(*pointee),
+                    _ => Err(type_error(format!(
+                        "type `{}` cannot be dereferenced",
+                        self.describe(&ty)
+                    ))),
+                }
+            }
+            OperatorExpression::Negation(operator, operand) => {
+                let ty = self.check_expression(operand)?;
+                match (operator, self.shallow_resolve(&ty)) {
+                    (NegationOperator::Not, Ty::Bool) => {}
+                    (NegationOperator::Neg, Ty::Int(int_ty)) if !int_ty.is_signed() => {
+                        return Err(type_error(format!(
+                            "cannot apply `-` to type `{}`",
+                            int_ty.name()
+                        )));
+                    }
+                    _ => self.expect_integer(&ty, operator.symbol())?,
+                }
+                Ok(ty)
+            }
+            OperatorExpression::Binary(operator, left, right) => {
+                let left = self.check_expression(left)?;
+                let right = self.check_expression(right)?;
+                self.check_binary_operands(*operator, &left, &right)?;
+                Ok(left)
+            }
+            //@ We only have built-in comparisons, which are defined on `bool` and integers.
+            OperatorExpression::Comparison(operator, left, right) => {
+                let left = self.check_expression(left)?;
+                let right = self.check_expression(right)?;
+                self.unify(&left, &right)?;
+                match self.shallow_resolve(&left) {
+                    Ty::Bool | Ty::Int(_) | Ty::Var(_) => Ok(Ty::Bool),
+                    _ => Err(type_error(format!(
+                        "binary operation `{}` cannot be applied to type `{}`",
+                        operator.symbol(),
+                        self.describe(&left)
+                    ))),
+                }
+            }
+            OperatorExpression::Assignment(place, value) => {
+                let place = self.check_expression(place)?;
+                let value = self.check_expression(value)?;
+                self.unify(&place, &value)?;
+                Ok(Ty::unit())
+            }
+            OperatorExpression::CompoundAssignment(operator, place, value) => {
+                let place = self.check_expression(place)?;
+                let value = self.check_expression(value)?;
+                self.check_binary_operands(*operator, &place, &value)?;
+                Ok(Ty::unit())
+            }
+            OperatorExpression::LazyAnd(left, right) | OperatorExpression::LazyOr(left, right) => {
+                for operand in [left, right] {
+                    let ty = self.check_expression(operand)?;
+                    self.unify(&Ty::Bool, &ty)?;
+                }
+                Ok(Ty::Bool)
+            }
+        }
+    }
+
+    //@ Arithmetic operators take two integers of the same type, and bitwise operators additionally
+    //@ work on `bool`s. The two operands of a shift can have different integer types.
+    fn check_binary_operands(
+        &mut self,
+        operator: BinaryOperator,
+        left: &Ty,
+        right: &Ty,
+    ) -> Result<(), CompilationError> {
+        if operator.is_shift() {
+            self.expect_integer(left, operator.symbol())?;
+            return self.expect_integer(right, operator.symbol());
+        }
+        self.unify(left, right)?;
+        let is_bitwise = matches!(
+            operator,
+            BinaryOperator::BitAnd | BinaryOperator::BitOr | BinaryOperator::BitXor
+        );
+        if is_bitwise && self.shallow_resolve(left) == Ty::Bool {
+            return Ok(());
+        }
+        self.expect_integer(left, operator.symbol())
+    }
+
+    fn expect_integer(&mut self, ty: &Ty, operator: &str) -> Result<(), CompilationError> {
+        let integer = self.fresh(VariableKind::Integer);
+        if self.try_unify(&integer, ty) {
+            Ok(())
+        } else {
+            Err(type_error(format!(
+                "cannot apply `{operator}` to type `{}`",
+                self.describe(ty)
+            )))
+        }
+    }
+
+    //@ A `loop` has the type of the values it is exited with, and diverges if it is never exited.
+    //@ A `while` loop has type `()`.
+    fn check_loop(&mut self, loop_expression: &LoopExpression) -> Result<Ty, CompilationError> {
+        match &loop_expression.kind {
+            LoopKind::Infinite(body) => {
+                let ty = self.fresh(VariableKind::Diverging);
+                self.breakables.push(Breakable {
+                    label: loop_expression.label.clone(),
+                    is_loop: true,
+                    ty: ty.clone(),
+                });
+                let body = self.check_block(body)?;
+                self.unify(&Ty::unit(), &body)?;
+                self.breakables.pop();
+                Ok(ty)
+            }
+            LoopKind::While { condition, body } => {
+                self.scopes.push(Scope::default());
+                let condition = self.check_expression(condition)?;
+                self.unify(&Ty::Bool, &condition)?;
+                self.breakables.push(Breakable {
+                    label: loop_expression.label.clone(),
+                    is_loop: true,
+                    ty: Ty::unit(),
+                });
+                let body = self.check_block(body)?;
+                self.unify(&Ty::unit(), &body)?;
+                self.breakables.pop();
+                self.scopes.pop();
+                Ok(Ty::unit())
+            }
+        }
+    }
+
+    fn labeled_breakable(&self, label: &str) -> Result<usize, CompilationError> {
+        self.breakables
+            .iter()
+            .rposition(|breakable| breakable.label.as_deref() == Some(label))
+            .ok_or_else(|| type_error(format!("use of undeclared label `{label}`")))
+    }
+
+    fn innermost_loop(&self, keyword: &str) -> Result<usize, CompilationError> {
+        self.breakables
+            .iter()
+            .rposition(|breakable| breakable.is_loop)
+            .ok_or_else(|| type_error(format!("`{keyword}` outside of a loop")))
+    }
+
+    /// Check the fields of a struct expression against the fields of the struct or variant
+    /// `name`. All the fields must be provided, exactly once.
+    fn check_struct_fields(
+        &mut self,
+        name: &str,
+        declared: &StructFields,
+        fields: &[StructExprField],
+    ) -> Result<(), CompilationError> {
+        let declared = declared.field_list();
+        let mut provided = vec![];
+        for field in fields {
+            let Some((_, ty)) = declared
+                .iter()
+                .find(|(declared_name, _)| *declared_name == field.name)
+            else {
+                return Err(type_error(format!(
+                    "`{name}` has no field named `{}`",
+                    field.name
+                )));
+            };
+            if provided.contains(&&field.name) {
+                return Err(type_error(format!(
+                    "field `{}` specified more than once",
+                    field.name
+                )));
+            }
+            provided.push(&field.name);
+            let expected = self.items.lower_type(ty)?;
+            let value = self.check_expression(&field.value)?;
+            self.unify(&expected, &value)?;
+        }
+        if let Some((missing, _)) = declared
+            .iter()
+            .find(|(field, _)| !provided.contains(&field))
+        {
+            return Err(type_error(format!(
+                "missing field `{missing}` in initializer of `{name}`"
+            )));
+        }
+        Ok(())
+    }
+
+    //@ Until we've resolved them, enum projections look like two nested field accesses, see [Enum
+    //@ Expressions](../language/expressions/enum-exprs.md.rs): `$place.$name.$field` is an enum
+    //@ projection if the type of `$place` is an enum with a `$name` variant.
+    fn check_field(
+        &mut self,
+        base: &Expression,
+        field: &FieldName,
+    ) -> Result<Ty, CompilationError> {
+        let ExpressionKind::Field(variant_access) = &base.kind else {
+            let base = self.check_expression(base)?;
+            return self.field_type(&base, field);
+        };
+        let place = self.check_expression(&variant_access.expression)?;
+        if let Ty::Adt(enum_name) = self.autoderef(&place)
+            && base.attrs.is_empty()
+            && let Some(adt) = self.items.enums.get(&enum_name)
+            && adt.variant(&variant_access.field).is_some()
+        {
+            let path = VariantPath {
+                enum_name,
+                variant: variant_access.field.clone(),
+            };
+            return self.variant_field_type(&path, field);
+        }
+        let ty = self
+            .field_type(&place, &FieldName::Named(variant_access.field.clone()))
+            .map_err(|error| error.with_span(base.span))?;
+        self.typed_nodes.push((base.origin.id, ty.clone()));
+        self.field_type(&ty, field)
+    }
+
+    fn variant_field_type(
+        &mut self,
+        path: &VariantPath,
+        field: &FieldName,
+    ) -> Result<Ty, CompilationError> {
+        let fields = self.items.variant_fields(path)?;
+        match fields
+            .field_list()
+            .into_iter()
+            .find(|(name, _)| name == field)
+        {
+            Some((_, ty)) => self.items.lower_type(ty),
+            None => Err(type_error(format!(
+                "no field `{field}` on variant `{path}`"
+            ))),
+        }
+    }
+
+    //@ Field accesses see through references, like the built-in autoderef does
+    //@ [ref:expr.field.autoderef].
+    fn field_type(&mut self, base: &Ty, field: &FieldName) -> Result<Ty, CompilationError> {
+        let base = self.autoderef(base);
+        if let (Ty::Tuple(elements), FieldName::Index(index)) = (&base, field)
+            && let Some(element) = elements.get(*index)
+        {
+            return Ok(element.clone());
+        }
+        if let Ty::Adt(name) = &base
+            && let Some(fields) = self.items.structs.get(name)
+            && let Some((_, ty)) = fields
+                .field_list()
+                .into_iter()
+                .find(|(name, _)| name == field)
+        {
+            return self.items.lower_type(ty);
+        }
+        Err(type_error(format!(
+            "no field `{field}` on type `{}`",
+            self.describe(&base)
+        )))
+    }
+
+    fn autoderef(&self, ty: &Ty) -> Ty {
+        let mut ty = self.shallow_resolve(ty);
+        while let Ty::Ref(_, pointee) = ty {
+            ty = self.shallow_resolve(&pointee);
+        }
+        ty
+    }
+
+    //@ Only named functions can be called. `print` is a built-in function that takes one argument of
+    //@ any type.
+    fn check_call(&mut self, call: &CallExpression) -> Result<Ty, CompilationError> {
+        let ExpressionKind::Path(name) = &call.callee.kind else {
+            return Err(type_error(format!(
+                "expected function, found `{}`",
+                call.callee
+            )));
+        };
+        let Some(signature) = self.items.functions.get(name) else {
+            if name == "print" {
+                if call.args.len() != 1 {
+                    return Err(type_error(format!(
+                        "`print` takes 1 argument but {} arguments were supplied",
+                        call.args.len()
+                    )));
+                }
+                self.check_expression(&call.args[0])?;
+                return Ok(Ty::unit());
+            }
+            return Err(type_error(format!(
+                "cannot find function `{name}` in this scope"
+            )));
+        };
+        let expected = signature.parameters.len();
+        if call.args.len() < expected || (!signature.variadic && call.args.len() > expected) {
+            return Err(type_error(format!(
+                "function `{name}` takes {expected} arguments but {} arguments were supplied",
+                call.args.len()
+            )));
+        }
+        for (index, arg) in call.args.iter().enumerate() {
+            let arg = self.check_expression(arg)?;
+            if let Some(parameter) = signature.parameters.get(index) {
+                let parameter = self.items.lower_type(parameter)?;
+                self.unify(&parameter, &arg)?;
+            }
+        }
+        self.items.lower_type(&signature.return_type)
+    }
+
+    //@ ## Unification
+    fn fresh(&mut self, kind: VariableKind) -> Ty {
+        self.variables.push(Variable::Unknown(kind));
+        Ty::Var(self.variables.len() - 1)
+    }
+
+    /// Follow the known variables until we reach a type constructor or an unknown variable.
+    fn shallow_resolve(&self, ty: &Ty) -> Ty {
+        let mut ty = ty.clone();
+        while let Ty::Var(var) = ty
+            && let Variable::Known(known) = &self.variables[var]
+        {
+            ty = known.clone();
+        }
+        ty
+    }
+
+    fn variable_kind(&self, var: usize) -> VariableKind {
+        match &self.variables[var] {
+            Variable::Unknown(kind) => *kind,
+            Variable::Known(_) => unreachable!("variable should be resolved"),
+        }
+    }
+
+    fn is_diverging(&self, ty: &Ty) -> bool {
+        matches!(self.shallow_resolve(ty), Ty::Var(var) if self.variable_kind(var) == VariableKind::Diverging)
+    }
+
+    fn unify(&mut self, expected: &Ty, found: &Ty) -> Result<(), CompilationError> {
+        if self.try_unify(expected, found) {
+            Ok(())
+        } else {
+            Err(type_error(format!(
+                "mismatched types: expected `{}`, found `{}`",
+                self.describe(expected),
+                self.describe(found)
+            )))
+        }
+    }
+
+    fn try_unify(&mut self, left: &Ty, right: &Ty) -> bool {
+        let left = self.shallow_resolve(left);
+        let right = self.shallow_resolve(right);
+        match (&left, &right) {
+            (Ty::Var(left), Ty::Var(right)) if left == right => true,
+            (Ty::Var(left), Ty::Var(right)) => {
+                let kind = self.variable_kind(*left).merge(self.variable_kind(*right));
+                self.variables[*right] = Variable::Unknown(kind);
+                self.variables[*left] = Variable::Known(Ty::Var(*right));
+                true
+            }
+            (Ty::Var(var), ty) | (ty, Ty::Var(var)) => {
+                let fits = match self.variable_kind(*var) {
+                    VariableKind::Integer => matches!(ty, Ty::Int(_)),
+                    VariableKind::General | VariableKind::Diverging => !self.occurs(*var, ty),
+                };
+                if fits {
+                    self.variables[*var] = Variable::Known(ty.clone());
+                }
+                fits
+            }
+            (Ty::Bool, Ty::Bool) | (Ty::Str, Ty::Str) | (Ty::SelfTy, Ty::SelfTy) => true,
+            (Ty::Int(left), Ty::Int(right)) => left == right,
+            (Ty::Tuple(left), Ty::Tuple(right)) => {
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .zip(right)
+                        .all(|(left, right)| self.try_unify(left, right))
+            }
+            (Ty::Ref(left_mutability, left), Ty::Ref(right_mutability, right)) => {
+                left_mutability == right_mutability && self.try_unify(left, right)
+            }
+            (Ty::Adt(left), Ty::Adt(right)) => left == right,
+            _ => false,
+        }
+    }
+
+    /// Whether the variable appears in this type, in which case binding it would make an infinite
+    /// type.
+    fn occurs(&self, var: usize, ty: &Ty) -> bool {
+        match self.shallow_resolve(ty) {
+            Ty::Var(other) => other == var,
+            Ty::Tuple(elements) => elements.iter().any(|element| self.occurs(var, element)),
+            Ty::Ref(_, pointee) => self.occurs(var, &pointee),
+            Ty::Bool | Ty::Str | Ty::Int(_) | Ty::SelfTy | Ty::Adt(_) => false,
+        }
+    }
+
+    /// Convert back to a language type, if the type is fully known.
+    fn to_type(&self, ty: &Ty) -> Option<Type> {
+        let kind = match self.shallow_resolve(ty) {
+            Ty::Bool => TypeKind::Bool,
+            Ty::Str => TypeKind::Str,
+            Ty::Int(ty) => TypeKind::Int(ty),
+            Ty::Tuple(elements) => TypeKind::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.to_type(element))
+                    .collect::<Option<_>>()?,
+            ),
+            Ty::SelfTy => TypeKind::TraitSelf,
+            Ty::Ref(mutability, pointee) => {
+                TypeKind::Ref(None, mutability, Box::new(self.to_type(&pointee)?))
+            }
+            Ty::Adt(name) => TypeKind::Path(name, Span::dummy()),
+            Ty::Var(_) => return None,
+        };
+        Some(Type::new(kind))
+    }
+
+    /// Print the type for error messages.
+    fn describe(&self, ty: &Ty) -> String {
+        match self.shallow_resolve(ty) {
+            Ty::Bool => "bool".to_owned(),
+            Ty::Str => "str".to_owned(),
+            Ty::Int(ty) => ty.name().to_owned(),
+            Ty::Tuple(elements) if elements.len() == 1 => {
+                format!("({},)", self.describe(&elements[0]))
+            }
+            Ty::Tuple(elements) => format!(
+                "({})",
+                elements
+                    .iter()
+                    .map(|element| self.describe(element))
+                    .join(", ")
+            ),
+            Ty::SelfTy => "Self".to_owned(),
+            Ty::Ref(mutability, pointee) => format!("&{mutability}{}", self.describe(&pointee)),
+            Ty::Adt(name) => name.to_string(),
+            Ty::Var(var) => match self.variable_kind(var) {
+                VariableKind::Integer => "{integer}".to_owned(),
+                VariableKind::General | VariableKind::Diverging => "_".to_owned(),
+            },
+        }
+    }
+}
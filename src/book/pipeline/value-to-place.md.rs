@@ -107,11 +107,509 @@
 //@ After this step, all place contexts contain place expressions.
 //@
 //@ > The rest of this section is a work-in-progress experiment about making the book executable.
+//@
+//@ We implement the edition 2024 rules for the subset of the language we support:
+//@ - Temporaries created in an expression statement or a `let` initializer live until the end of
+//@   that statement. We wrap the statement in a block that declares them. The bindings of a `let`
+//@   must outlive that block, so we declare them right before it and assign them inside: a
+//@   destructuring `let` binds fresh names in the block, which we then move into the outer
+//@   bindings;
+//@ - Temporaries created in the tail expression of a block are dropped before the locals of that
+//@   block [ref:destructors.scope.temporary.edition2024]. We declare them right before the tail
+//@   expression;
+//@ - Temporaries created in the condition of an `if`, a match guard or a match arm are dropped at
+//@   the end of that expression [ref:destructors.scope.temporary.enclosing]. We turn the expression
+//@   into a block that declares them;
+//@ - Temporaries created in the scrutinee of a `let` condition live until the end of the `if`
+//@   branch [ref:destructors.scope.temporary.edition2024]. We bind them with extra `let` conditions
+//@   just before it, as in the example above;
+//@ - Temporaries that undergo lifetime extension [ref:destructors.scope.lifetime-extension] live
+//@   until the end of the block that contains the `let` statement. We declare them right before
+//@   that statement and assign them where the expression was.
 use crate::desugarings::*; //#
+use crate::interactive_example; //#
+use std::collections::HashMap; //#
 
+interactive_example! {
+    expr_unnesting::desugar_nested_exprs,
+    fn main() {
+        let x = &true;
+        if *&false {
+            print(1);
+        }
+        *&true;
+        print(*x);
+    }
+}
 pub fn desugar_value_to_place(program: &mut Program) -> Result<(), CompilationError> {
-    program.visit_all_mut(|_block: &mut BlockExpression| {
-        // TODO: look for all the `VirtualExpression::ValueToPlaceCoercion`
-        Ok(())
+    let mut temporaries = Temporaries {
+        names: NameSupply::new(program),
+        types: type_checking::node_types(program)?,
+    };
+    program.visit_all_mut_infallible(|function: &mut Function| {
+        if let FunctionBody::Block(body) = &mut function.body {
+            temporaries.block(body, None);
+        }
+    });
+    Ok(())
+}
+
+/// Materializes the temporaries of function bodies.
+struct Temporaries {
+    names: NameSupply,
+    /// The types of the nodes of the program, to write the types of the new `let` statements.
+    types: HashMap<NodeId, Type>,
+}
+
+/// A temporary that holds `value`. Unless it is an extended temporary declared elsewhere, it gets
+/// materialized as `let $name; $name = $value;`.
+struct Temporary {
+    name: Identifier,
+    value: Expression,
+    ty: Option<Type>,
+    declared_here: bool,
+    /// The origin of the coercion that the temporary replaces.
+    origin: Origin,
+}
+
+impl Temporaries {
+    /// Materialize the temporaries of this block. If the block is an extending expression,
+    /// `extended` collects the declarations of the temporaries whose lifetime gets extended past
+    /// the end of the block.
+    fn block(&mut self, block: &mut BlockExpression, extended: Option<&mut Vec<Statement>>) {
+        let statements = std::mem::take(&mut block.statements);
+        for statement in statements {
+            self.statement(statement, &mut block.statements);
+        }
+        if let Some(tail) = &mut block.tail {
+            let mut temporaries = Vec::new();
+            self.expression(tail, extended, &mut temporaries);
+            block.statements.extend(materialize(temporaries));
+        }
+    }
+
+    /// Materialize the temporaries of this statement and push the result to `statements`.
+    fn statement(&mut self, statement: Statement, statements: &mut Vec<Statement>) {
+        match statement {
+            Statement::Let {
+                attrs,
+                scope,
+                pattern,
+                ty,
+                initial_value: Some(mut value),
+                mut else_branch,
+                span,
+                origin,
+            } => {
+                let mut extended = Vec::new();
+                let mut temporaries = Vec::new();
+                // The initializer of a `let` that binds by-value is an extending expression
+                // [ref:destructors.scope.lifetime-extension.exprs].
+                let extending =
+                    matches!(pattern.kind, PatternKind::Identifier(_)).then_some(&mut extended);
+                self.expression(&mut value, extending, &mut temporaries);
+                if let Some(else_branch) = &mut else_branch {
+                    self.block(else_branch, None);
+                }
+                statements.append(&mut extended);
+                match &pattern.kind {
+                    PatternKind::Identifier(name) if declares_temporaries(&temporaries) => {
+                        let mut scoped_statements = materialize(temporaries);
+                        scoped_statements.push(Statement::Expr(assignment(
+                            name.clone(),
+                            value,
+                            &origin,
+                        )));
+                        let scoped = scoped(scoped_statements, &origin);
+                        // The binding must outlive the temporaries, so we declare it outside of
+                        // their scope.
+                        statements.push(Statement::Let {
+                            attrs,
+                            scope,
+                            pattern,
+                            ty,
+                            initial_value: None,
+                            else_branch,
+                            span,
+                            origin,
+                        });
+                        statements.push(scoped);
+                    }
+                    _ if binds_variables(&pattern) && declares_temporaries(&temporaries) => {
+                        // The bindings must outlive the temporaries, so we declare them outside of
+                        // their scope. Inside it, the pattern binds fresh names that we then move
+                        // out of.
+                        let mut pattern = pattern;
+                        let mut renamed = Vec::new();
+                        pattern.visit_all_mut_infallible(|pattern: &mut Pattern| {
+                            if let PatternKind::Identifier(name) = &mut pattern.kind {
+                                let fresh = self.names.fresh(&name.name);
+                                let name = std::mem::replace(name, fresh.clone());
+                                renamed.push((name, fresh, pattern.origin.clone()));
+                            }
+                        });
+                        let mut scoped_statements = materialize(temporaries);
+                        scoped_statements.push(Statement::Let {
+                            attrs,
+                            scope: None,
+                            pattern,
+                            ty,
+                            initial_value: Some(value),
+                            else_branch,
+                            span,
+                            origin: origin.clone(),
+                        });
+                        for (name, fresh, source) in renamed {
+                            statements.push(Statement::Let {
+                                attrs: vec![],
+                                scope: scope.clone(),
+                                pattern: Pattern::derived_from(
+                                    &source,
+                                    PatternKind::Identifier(name.clone()),
+                                ),
+                                ty: derived_type(self.types.get(&source.id).cloned(), &source),
+                                initial_value: None,
+                                else_branch: None,
+                                span,
+                                origin: source.derive(),
+                            });
+                            let fresh = Expression {
+                                origin: source.derive(),
+                                ..Expression::new(ExpressionKind::Path(fresh))
+                            };
+                            let value = Expression {
+                                origin: source.derive(),
+                                ..Expression::new(ExpressionKind::Virtual(
+                                    VirtualExpression::PlaceToValueCoercion(Box::new(fresh)),
+                                ))
+                            };
+                            scoped_statements
+                                .push(Statement::Expr(assignment(name, value, &source)));
+                        }
+                        statements.push(scoped(scoped_statements, &origin));
+                    }
+                    _ => {
+                        let statement = Statement::Let {
+                            attrs,
+                            scope,
+                            pattern,
+                            ty,
+                            initial_value: Some(value),
+                            else_branch,
+                            span,
+                            origin,
+                        };
+                        push_scoped(statement, temporaries, statements);
+                    }
+                }
+            }
+            Statement::Expr(mut expression) => {
+                let mut temporaries = Vec::new();
+                self.expression(&mut expression, None, &mut temporaries);
+                push_scoped(Statement::Expr(expression), temporaries, statements);
+            }
+            Statement::Let { .. } | Statement::Empty | Statement::Item(_) => {
+                statements.push(statement)
+            }
+        }
+    }
+
+    /// Replace the value-to-place coercions in this expression with temporaries, in evaluation
+    /// order. The temporaries are pushed to `scope`; the declarations of extended temporaries are
+    /// pushed to `extended` instead. `extended` is `Some` iff this expression is an extending
+    /// expression.
+    fn expression(
+        &mut self,
+        expression: &mut Expression,
+        mut extended: Option<&mut Vec<Statement>>,
+        scope: &mut Vec<Temporary>,
+    ) {
+        match &mut expression.kind {
+            ExpressionKind::Literal(_)
+            | ExpressionKind::Path(_)
+            | ExpressionKind::VariantPath(_)
+            | ExpressionKind::DiscriminantOf(_) => {}
+            ExpressionKind::Virtual(VirtualExpression::ValueToPlaceCoercion(value)) => {
+                self.expression(value, extended.as_deref_mut(), scope);
+                let name = self.names.fresh("tmp");
+                let value = std::mem::replace(&mut **value, unit_expression());
+                let ty = self.types.get(&expression.origin.id).cloned();
+                let declared_here = match extended {
+                    Some(extended) => {
+                        extended.push(declaration(name.clone(), ty.clone(), &expression.origin));
+                        false
+                    }
+                    None => true,
+                };
+                scope.push(Temporary {
+                    name: name.clone(),
+                    value,
+                    ty,
+                    declared_here,
+                    origin: expression.origin.clone(),
+                });
+                expression.kind = ExpressionKind::Path(name);
+                expression.origin = expression.origin.derive();
+            }
+            ExpressionKind::Virtual(VirtualExpression::PlaceToValueCoercion(place)) => {
+                self.expression(place, None, scope);
+            }
+            ExpressionKind::Virtual(VirtualExpression::Unreachable) => {}
+            ExpressionKind::Operator(operator) => match &mut **operator {
+                //@ The operand of an extending borrow expression is extending. If a borrow,
+                //@ dereference, field or tuple indexing expression has an extended temporary
+                //@ scope, then so does its operand [ref:destructors.scope.lifetime-extension.exprs].
+                OperatorExpression::Borrow(borrow) => {
+                    self.expression(&mut borrow.expression, extended, scope)
+                }
+                OperatorExpression::Dereference(dereference) => {
+                    self.expression(&mut dereference.expression, extended, scope)
+                }
+                OperatorExpression::Negation(_, operand) => self.expression(operand, None, scope),
+                OperatorExpression::Binary(_, left, right)
+                | OperatorExpression::Comparison(_, left, right)
+                | OperatorExpression::Assignment(left, right)
+                | OperatorExpression::CompoundAssignment(_, left, right) => {
+                    self.expression(left, None, scope);
+                    self.expression(right, None, scope);
+                }
+                OperatorExpression::LazyAnd(left, right)
+                | OperatorExpression::LazyOr(left, right) => {
+                    self.condition(left);
+                    self.condition(right);
+                }
+            },
+            ExpressionKind::Grouped(inner) => self.expression(inner, extended, scope),
+            //@ The operands of an extending tuple or struct expression are extending
+            //@ [ref:destructors.scope.lifetime-extension.exprs].
+            ExpressionKind::Tuple(elements) => {
+                for element in elements {
+                    self.expression(element, extended.as_deref_mut(), scope);
+                }
+            }
+            ExpressionKind::Struct(adt) => {
+                for field in &mut adt.fields {
+                    self.expression(&mut field.value, extended.as_deref_mut(), scope);
+                }
+            }
+            ExpressionKind::EnumVariant(variant) => {
+                for field in &mut variant.fields {
+                    self.expression(&mut field.value, extended.as_deref_mut(), scope);
+                }
+            }
+            ExpressionKind::TupleIndexing(tuple_indexing) => {
+                self.expression(&mut tuple_indexing.expression, extended, scope)
+            }
+            ExpressionKind::Field(field) => self.expression(&mut field.expression, extended, scope),
+            ExpressionKind::EnumProjection(projection) => {
+                self.expression(&mut projection.expression, extended, scope)
+            }
+            ExpressionKind::Discriminant(discriminant) => {
+                self.expression(&mut discriminant.expression, None, scope)
+            }
+            ExpressionKind::Call(call) => {
+                self.expression(&mut call.callee, None, scope);
+                for argument in &mut call.args {
+                    self.expression(argument, None, scope);
+                }
+            }
+            ExpressionKind::Block(block) => self.block(block, extended),
+            ExpressionKind::If(if_expression) => {
+                self.condition(&mut if_expression.condition);
+                self.expression(
+                    &mut if_expression.then_branch,
+                    extended.as_deref_mut(),
+                    scope,
+                );
+                if let Some(else_branch) = &mut if_expression.else_branch {
+                    self.expression(else_branch, extended, scope);
+                }
+            }
+            ExpressionKind::Let(let_expression) => {
+                self.expression(&mut let_expression.scrutinee, None, scope)
+            }
+            //@ The arms of an extending `match` expression are extending
+            //@ [ref:destructors.scope.lifetime-extension.exprs].
+            ExpressionKind::Match(match_expression) => {
+                self.expression(&mut match_expression.scrutinee, None, scope);
+                for arm in &mut match_expression.arms {
+                    if let Some(guard) = &mut arm.guard {
+                        self.condition(guard);
+                    }
+                    self.scoped_expression(&mut arm.body, extended.as_deref_mut());
+                }
+            }
+            ExpressionKind::Loop(loop_expression) => match &mut loop_expression.kind {
+                LoopKind::Infinite(body) => self.block(body, None),
+                LoopKind::While { condition, body } => {
+                    self.condition(condition);
+                    self.block(body, None);
+                }
+            },
+            ExpressionKind::Break(break_expression) => {
+                if let Some(value) = &mut break_expression.value {
+                    self.expression(value, None, scope);
+                }
+            }
+            ExpressionKind::Continue(_) => {}
+        }
+    }
+
+    /// Materialize the temporaries of an `if` or `while` condition or of a match guard. Each
+    /// operand of a let chain is handled separately.
+    fn condition(&mut self, condition: &mut Expression) {
+        match &mut condition.kind {
+            ExpressionKind::Operator(OperatorExpression::LazyAnd(left, right)) => {
+                self.condition(left);
+                self.condition(right);
+            }
+            ExpressionKind::Let(_) => {
+                let mut temporaries = Vec::new();
+                self.expression(condition, None, &mut temporaries);
+                if !temporaries.is_empty() {
+                    let condition_value = std::mem::replace(condition, unit_expression());
+                    let origin = condition_value.origin.clone();
+                    *condition = temporaries
+                        .into_iter()
+                        .map(|temporary| Expression {
+                            origin: temporary.origin.derive(),
+                            ..Expression::new(ExpressionKind::Let(LetExpression {
+                                pattern: Pattern::derived_from(
+                                    &temporary.origin,
+                                    PatternKind::Identifier(temporary.name),
+                                ),
+                                scrutinee: Box::new(temporary.value),
+                            }))
+                        })
+                        .chain([condition_value])
+                        .reduce(|left, right| Expression {
+                            origin: origin.derive(),
+                            ..LetExpression::and(left, right)
+                        })
+                        .unwrap();
+                }
+            }
+            _ => self.scoped_expression(condition, None),
+        }
+    }
+
+    /// Materialize the temporaries of an expression that is its own temporary scope, by turning
+    /// it into a block that declares them.
+    fn scoped_expression(
+        &mut self,
+        expression: &mut Expression,
+        extended: Option<&mut Vec<Statement>>,
+    ) {
+        let (span, origin) = (expression.span, expression.origin.clone());
+        let mut temporaries = Vec::new();
+        self.expression(expression, extended, &mut temporaries);
+        if !temporaries.is_empty() {
+            let value = std::mem::replace(expression, unit_expression());
+            *expression = Expression {
+                span,
+                origin: origin.derive(),
+                ..BlockExpression::derived_from(&origin, materialize(temporaries), Some(value))
+                    .into()
+            };
+        }
+    }
+}
+
+/// The statements that declare and assign these temporaries.
+fn materialize(temporaries: Vec<Temporary>) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    for temporary in temporaries {
+        if temporary.declared_here {
+            statements.push(declaration(
+                temporary.name.clone(),
+                temporary.ty,
+                &temporary.origin,
+            ));
+        }
+        statements.push(Statement::Expr(assignment(
+            temporary.name,
+            temporary.value,
+            &temporary.origin,
+        )));
+    }
+    statements
+}
+
+/// Whether these temporaries need to be declared, as opposed to only assigning extended
+/// temporaries declared elsewhere.
+fn declares_temporaries(temporaries: &[Temporary]) -> bool {
+    temporaries.iter().any(|temporary| temporary.declared_here)
+}
+
+fn binds_variables(pattern: &Pattern) -> bool {
+    let mut binds_variables = false;
+    pattern.visit_all_infallible(|pattern: &Pattern| {
+        binds_variables |= matches!(pattern.kind, PatternKind::Identifier(_));
+    });
+    binds_variables
+}
+
+/// Push `statement` to `statements`, preceded by the statements that materialize its temporaries.
+/// If these declare new temporaries, we wrap everything in a block so that they get dropped at the
+/// end of the statement.
+fn push_scoped(statement: Statement, temporaries: Vec<Temporary>, statements: &mut Vec<Statement>) {
+    if declares_temporaries(&temporaries) {
+        let origin = match &statement {
+            Statement::Let { origin, .. } => origin.clone(),
+            Statement::Expr(expression) => expression.origin.clone(),
+            Statement::Empty | Statement::Item(_) => unreachable!("no temporaries here"),
+        };
+        let mut scoped_statements = materialize(temporaries);
+        scoped_statements.push(statement);
+        statements.push(scoped(scoped_statements, &origin));
+    } else {
+        statements.extend(materialize(temporaries));
+        statements.push(statement);
+    }
+}
+
+/// A block of these statements, made from the node with origin `source`.
+fn scoped(statements: Vec<Statement>, source: &Origin) -> Statement {
+    Statement::Expr(Expression {
+        origin: source.derive(),
+        ..BlockExpression::derived_from(source, statements, None).into()
     })
 }
+
+/// The declaration of a temporary of type `ty`, made from the node with origin `source`.
+fn declaration(name: Identifier, ty: Option<Type>, source: &Origin) -> Statement {
+    Statement::Let {
+        attrs: vec![],
+        scope: None,
+        pattern: Pattern::derived_from(source, PatternKind::Identifier(name)),
+        ty: derived_type(ty, source),
+        initial_value: None,
+        else_branch: None,
+        span: Span::dummy(),
+        origin: source.derive(),
+    }
+}
+
+/// This type, made from the node with origin `source`.
+fn derived_type(mut ty: Option<Type>, source: &Origin) -> Option<Type> {
+    ty.visit_all_mut_infallible(|ty: &mut Type| ty.origin = source.derive());
+    ty
+}
+
+/// `$name = $value`, made from the node with origin `source`.
+fn assignment(name: Identifier, value: Expression, source: &Origin) -> Expression {
+    let place = Expression {
+        origin: source.derive(),
+        ..Expression::new(ExpressionKind::Path(name))
+    };
+    Expression {
+        origin: source.derive(),
+        ..Expression::new(ExpressionKind::Operator(Box::new(
+            OperatorExpression::Assignment(place, value),
+        )))
+    }
+}
+
+fn unit_expression() -> Expression {
+    Expression::new(ExpressionKind::Tuple(vec![]))
+}
@@ -95,7 +95,7 @@
             }
 
             try {
-                const rendered = wasmModule.interactive_desugar_example(exampleId, editor.value);
+                const rendered = wasmModule.interactive_desugar_example(exampleId, editor.value, "rust", "rust");
                 if (currentRun === runId) {
                     setOutput(desugarOutput, rendered, false);
                 }
@@ -9,78 +9,467 @@
 // Therefore the rust project root must be a parent of the mdbook root.
 #[path = "book/language/overview.md.rs"]
 pub mod language;
-pub use language::{Program, print_program};
+pub use language::{Program, Span, print_program, print_program_as_rust};
 
 #[path = "book/pipeline/overview.md.rs"]
 pub mod desugarings;
 pub use desugarings::desugar;
 #[cfg(not(target_arch = "wasm32"))]
 pub use desugarings::formality::{check_with_formality, translate_to_formality};
+pub use desugarings::interpreter::interpret;
 #[cfg(not(target_arch = "wasm32"))]
 pub use desugarings::minirust::{run_in_minirust, translate_to_minirust};
 
 pub mod parser {
     use crate::{
-        CompilationError,
-        language::{Program, Token},
+        CompilationError, Diagnostic,
+        language::{Identifier, Program, Span, Token},
     };
     use logos::Logos;
 
     include!(concat!(env!("OUT_DIR"), "/parser.rs"));
+    include!(concat!(env!("OUT_DIR"), "/terminals.rs"));
 
-    impl From<ParseError> for CompilationError {
-        fn from(value: ParseError) -> Self {
-            CompilationError::Parse(format!("{value:?}"))
+    /// The span of a production covers the spans of its symbols. The symbols that don't come from
+    /// the source, such as empty lists, don't count.
+    impl rusty_lr::Location for Span {
+        fn new<'a>(stack: impl Iterator<Item = &'a Self> + Clone, len: usize) -> Self
+        where
+            Self: 'a,
+        {
+            // `stack` yields the spans of the symbols of the production from last to first.
+            let mut spans = stack.take(len).filter(|span| !span.is_dummy());
+            let Some(last) = spans.next() else {
+                return Span::dummy();
+            };
+            let first = spans.last().unwrap_or(last);
+            Span::new(first.start, last.end)
         }
     }
 
+    /// Parse `input`, reporting all the syntax errors we find rather than the first one. After an
+    /// error we skip the rest of the statement or item it is in, see [`recovery_point`], and parse
+    /// on from there.
     pub fn parse_program(input: &str) -> Result<Program, CompilationError> {
-        let mut lexer = Token::lexer(input);
-        let mut context = ProgramContext::with_default_userdata();
-        for token in lexer.by_ref() {
-            match token {
-                Ok(token) => context.feed(token)?,
-                Err(()) => {
-                    let offset = lexer.span().start;
-                    let ch = lexer.slice().chars().next().unwrap_or('\0');
-                    return Err(CompilationError::Parse(format!(
-                        "unexpected character {ch:?} at byte {offset}"
-                    )));
-                }
+        parse(input, false)
+    }
+
+    /// Parse a program printed by [`print_program`](crate::print_program), whose names may have
+    /// hygiene markers such as `tmp#1`. Tests use it to check that printing round-trips.
+    pub fn parse_printed_program(input: &str) -> Result<Program, CompilationError> {
+        parse(input, true)
+    }
+
+    fn parse(input: &str, allow_markers: bool) -> Result<Program, CompilationError> {
+        let (tokens, mut errors) = lex(input, allow_markers);
+        // The tokens `context` accepted, as indices into `tokens`. After an error we rebuild the
+        // context by feeding it a prefix of these again.
+        let mut fed = vec![];
+        let mut context = replay(&tokens, &fed);
+        let mut next = 0;
+        let mut last_error = None;
+        while let Some((token, span)) = tokens.get(next) {
+            if context.feed_location(token.clone(), *span).is_ok() {
+                fed.push(next);
+                next += 1;
+                continue;
+            }
+            if last_error == Some(next) {
+                // The token we resumed at doesn't fit either: drop it.
+                next += 1;
+                continue;
             }
+            last_error = Some(next);
+            let found = format!("`{}`", &input[span.start..span.end]);
+            errors.push(expected_error(&tokens, &fed, found, *span));
+            let top_level = depth(&tokens, &fed) == 0;
+            fed.truncate(statement_start(&tokens, &fed));
+            next = recovery_point(&tokens, next, top_level);
+            context = replay(&tokens, &fed);
         }
-        let parses: Vec<_> = context.accept_all()?.collect();
+        let end = Span::new(input.len(), input.len());
+        let parses = match context.accept_all() {
+            Ok(parses) if errors.is_empty() => parses,
+            Ok(_) => return Err(parse_errors(errors)),
+            Err(_) => {
+                let found = "end of input".to_owned();
+                errors.push(expected_error(&tokens, &fed, found, end));
+                return Err(parse_errors(errors));
+            }
+        };
+        // See the "Struct expressions in conditions" section of the struct expressions chapter.
+        let parses: Vec<_> = parses
+            .filter(|(program, _data)| !program.has_struct_expression_in_condition())
+            .collect();
         match parses.as_slice() {
-            [(program, _data)] => Ok(program.clone()),
-            [] => Err(CompilationError::Parse("no valid parse".to_owned())),
-            parses => Err(CompilationError::Parse(format!(
+            [(program, _data)] => {
+                let mut program = program.clone();
+                program.assign_node_ids();
+                Ok(program)
+            }
+            [] => Err(CompilationError::Parse(Diagnostic::new("no valid parse"))),
+            parses => Err(CompilationError::Parse(Diagnostic::new(format!(
                 "ambiguous parse: found {} valid parses, such as:\nparse 1:\n{}\n\nparse 2:\n{}",
                 parses.len(),
                 parses[0].0,
                 parses[1].0,
+            )))),
+        }
+    }
+
+    /// Split `input` into tokens. Characters that don't start a token are reported and skipped, as
+    /// are hygiene markers unless `allow_markers`.
+    fn lex(input: &str, allow_markers: bool) -> (Vec<(Token, Span)>, Vec<Diagnostic>) {
+        let mut lexer = Token::lexer(input);
+        let mut tokens = vec![];
+        let mut errors = vec![];
+        while let Some(token) = lexer.next() {
+            let span = Span::new(lexer.span().start, lexer.span().end);
+            match token {
+                Ok(Token::Identifier(identifier)) if identifier.is_hygienic() && !allow_markers => {
+                    let message = format!("unexpected hygiene marker in `{identifier}`");
+                    errors.push(Diagnostic::new(message).with_span(span));
+                    let identifier = Identifier::new(identifier.name);
+                    tokens.push((Token::Identifier(identifier), span));
+                }
+                Ok(token) => tokens.push((token, span)),
+                Err(()) => {
+                    let ch = lexer.slice().chars().next().unwrap_or('\0');
+                    let message = format!("unexpected character {ch:?}");
+                    errors.push(Diagnostic::new(message).with_span(span));
+                }
+            }
+        }
+        (tokens, errors)
+    }
+
+    /// A parser that was fed the tokens at the indices `fed`, which it accepted before.
+    fn replay(tokens: &[(Token, Span)], fed: &[usize]) -> ProgramContext {
+        let mut context = ProgramContext::with_default_userdata();
+        for &index in fed {
+            let (token, span) = &tokens[index];
+            let accepted = context.feed_location(token.clone(), *span).is_ok();
+            assert!(accepted, "the parser accepted these tokens before");
+        }
+        context
+    }
+
+    /// The error for finding `found` where the parser could only accept the tokens listed by
+    /// [`expected_tokens`].
+    fn expected_error(
+        tokens: &[(Token, Span)],
+        fed: &[usize],
+        found: String,
+        span: Span,
+    ) -> Diagnostic {
+        let message = match expected_tokens(tokens, fed).as_slice() {
+            [] => format!("unexpected {found}"),
+            [expected] => format!("expected {expected}, found {found}"),
+            [first, second] => format!("expected one of {first} or {second}, found {found}"),
+            [expected @ .., last] => format!(
+                "expected one of {}, or {last}, found {found}",
+                expected.join(", ")
+            ),
+        };
+        Diagnostic::new(message).with_span(span)
+    }
+
+    /// The tokens the parser would accept after `fed`, in the order of the lexer chapter. We find
+    /// them by feeding a token of each kind to a parser rebuilt with [`replay`].
+    fn expected_tokens(tokens: &[(Token, Span)], fed: &[usize]) -> Vec<&'static str> {
+        terminals()
+            .into_iter()
+            // `__unsupported__` stands for the syntax we don't support, there's no point in
+            // suggesting it.
+            .filter(|(_, token)| *token != Token::Unsupported)
+            .filter(|(_, token)| {
+                let mut context = replay(tokens, fed);
+                context.feed_location(token.clone(), Span::dummy()).is_ok()
+            })
+            .map(|(description, _)| description)
+            .collect()
+    }
+
+    /// How many braces the tokens at the indices `fed` leave open.
+    fn depth(tokens: &[(Token, Span)], fed: &[usize]) -> usize {
+        fed.iter().fold(0, |depth, &index| match tokens[index].0 {
+            Token::LBrace => depth + 1,
+            Token::RBrace => depth - 1,
+            _ => depth,
+        })
+    }
+
+    /// Where in `fed` the statement or item that the parser was in the middle of starts, i.e.
+    /// after the last `;`, `{` or `}`.
+    fn statement_start(tokens: &[(Token, Span)], fed: &[usize]) -> usize {
+        fed.iter()
+            .rposition(|&index| {
+                matches!(
+                    tokens[index].0,
+                    Token::Semicolon | Token::LBrace | Token::RBrace
+                )
+            })
+            .map_or(0, |position| position + 1)
+    }
+
+    /// Where to resume parsing after an error at `tokens[error]`. Inside a block, that's after the
+    /// `;` ending the statement, or before the `}` closing the block if there is no such `;`. At
+    /// the top level, that's at the start of the next item. Parsing resumes with the parser as it
+    /// was at the start of the statement or item, see [`statement_start`].
+    fn recovery_point(tokens: &[(Token, Span)], error: usize, top_level: bool) -> usize {
+        let mut depth = 0;
+        for (index, (token, _)) in tokens.iter().enumerate().skip(error) {
+            match token {
+                Token::LBrace => depth += 1,
+                // A stray `}` at the top level is skipped like any other token.
+                Token::RBrace if depth == 0 && top_level => {}
+                Token::RBrace if depth == 0 => return index,
+                Token::RBrace => depth -= 1,
+                Token::Semicolon if depth == 0 && !top_level => return index + 1,
+                token if depth == 0 && top_level && starts_item(token) => return index,
+                _ => {}
+            }
+        }
+        tokens.len()
+    }
+
+    fn starts_item(token: &Token) -> bool {
+        matches!(
+            token,
+            Token::Pub
+                | Token::Const
+                | Token::Async
+                | Token::Safe
+                | Token::Unsafe
+                | Token::Extern
+                | Token::Fn
+                | Token::Struct
+                | Token::Enum
+        )
+    }
+
+    /// The error reporting all of `errors`, which must not be empty, in the order of the source.
+    fn parse_errors(mut errors: Vec<Diagnostic>) -> CompilationError {
+        errors.sort_by_key(|error| error.span.start);
+        let mut first = errors.remove(0);
+        first.others = errors;
+        CompilationError::Parse(first)
+    }
+}
+
+/// Programs as JSON, for tools that would rather consume the AST than reparse printed source. The
+/// JSON follows serde's default representation of the `language` types. Spans may be omitted, and
+/// so may origins: the nodes without an id get one after the ids of the program.
+pub mod json {
+    use crate::{CompilationError, Diagnostic, Program, Span};
+
+    pub fn program_to_json(program: &Program) -> String {
+        serde_json::to_string_pretty(program).expect("the AST has no maps with non-string keys")
+    }
+
+    pub fn program_from_json(input: &str) -> Result<Program, CompilationError> {
+        let mut program: Program = serde_json::from_str(input).map_err(|error| {
+            // serde_json reports 1-based lines and columns, and line 0 for I/O errors.
+            let span = match error.line() {
+                0 => Span::dummy(),
+                line => {
+                    let line_start: usize = input
+                        .split_inclusive('\n')
+                        .take(line - 1)
+                        .map(str::len)
+                        .sum();
+                    let mut start =
+                        (line_start + error.column().saturating_sub(1)).min(input.len());
+                    while !input.is_char_boundary(start) {
+                        start -= 1;
+                    }
+                    Span::new(start, start + 1)
+                }
+            };
+            CompilationError::Parse(Diagnostic::new(error.to_string()).with_span(span))
+        })?;
+        program.assign_node_ids();
+        Ok(program)
+    }
+}
+
+/// The syntaxes programs can be read from and written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Rust source, as accepted by the parser and produced by the printer.
+    Rust,
+    /// The AST as JSON, see [`json`].
+    Json,
+    /// Self-contained Rust that rustc can compile, see [`print_program_as_rust`]. Programs can
+    /// only be written in this format.
+    Rustc,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "rust" => Some(Format::Rust),
+            "json" => Some(Format::Json),
+            "rustc" => Some(Format::Rustc),
+            _ => None,
+        }
+    }
+
+    pub fn read_program(self, input: &str) -> Result<Program, CompilationError> {
+        match self {
+            Format::Rust => parser::parse_program(input),
+            Format::Json => json::program_from_json(input),
+            Format::Rustc => Err(CompilationError::Parse(Diagnostic::new(
+                "programs can't be read in the `rustc` format",
             ))),
         }
     }
+
+    pub fn write_program(self, program: &Program) -> String {
+        match self {
+            Format::Rust => print_program(program),
+            Format::Json => json::program_to_json(program) + "\n",
+            Format::Rustc => print_program_as_rust(program),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum CompilationError {
-    Parse(String),
-    Desugaring(String),
-    Formality(String),
-    Internal(String),
-    MiniRust(String),
+    Parse(Diagnostic),
+    Desugaring(Diagnostic),
+    Type(Diagnostic),
+    Formality(Diagnostic),
+    Internal(Diagnostic),
+    MiniRust(Diagnostic),
+    Runtime(Diagnostic),
+}
+
+/// An error message, and the piece of source it is about if we know it.
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub message: String,
+    pub span: Span,
+    /// Errors reported along with this one, such as the other syntax errors of the same file.
+    pub others: Vec<Diagnostic>,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>) -> Self {
+        Diagnostic {
+            message: message.into(),
+            span: Span::dummy(),
+            others: vec![],
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Render `message` the way rustc does, pointing at the line of `source` that `self.span` is
+    /// on. See [`CompilationError::render`].
+    fn render(&self, message: &str, file_name: &str, source: &str) -> String {
+        let mut rendered = format!("error: {message}\n");
+        let span = self.span;
+        if span.is_dummy() || span.start > source.len() {
+            return rendered;
+        }
+        let line_start = source[..span.start]
+            .rfind('\n')
+            .map_or(0, |index| index + 1);
+        let line_end = source[span.start..]
+            .find('\n')
+            .map_or(source.len(), |index| span.start + index);
+        let line = &source[line_start..line_end];
+        let line_number = source[..line_start].matches('\n').count() + 1;
+        let column = source[line_start..span.start].chars().count() + 1;
+        // Multi-line spans are only underlined up to the end of their first line.
+        let underlined = source[span.start..span.end.min(line_end)].chars().count();
+        let gutter = " ".repeat(line_number.to_string().len());
+        rendered += &format!("{gutter}--> {file_name}:{line_number}:{column}\n");
+        rendered += &format!("{gutter} |\n");
+        rendered += &format!("{line_number} | {line}\n");
+        rendered += &format!(
+            "{gutter} | {}{}\n",
+            " ".repeat(column - 1),
+            "^".repeat(underlined.max(1))
+        );
+        rendered
+    }
+}
+
+impl CompilationError {
+    pub fn diagnostic(&self) -> &Diagnostic {
+        match self {
+            CompilationError::Parse(diagnostic)
+            | CompilationError::Desugaring(diagnostic)
+            | CompilationError::Type(diagnostic)
+            | CompilationError::Formality(diagnostic)
+            | CompilationError::Internal(diagnostic)
+            | CompilationError::MiniRust(diagnostic)
+            | CompilationError::Runtime(diagnostic) => diagnostic,
+        }
+    }
+
+    fn diagnostic_mut(&mut self) -> &mut Diagnostic {
+        match self {
+            CompilationError::Parse(diagnostic)
+            | CompilationError::Desugaring(diagnostic)
+            | CompilationError::Type(diagnostic)
+            | CompilationError::Formality(diagnostic)
+            | CompilationError::Internal(diagnostic)
+            | CompilationError::MiniRust(diagnostic)
+            | CompilationError::Runtime(diagnostic) => diagnostic,
+        }
+    }
+
+    /// Point at `span`, unless the error already points at something more precise. Passes call
+    /// this on the errors coming out of each node they have a span for, so that the innermost one
+    /// wins.
+    pub fn with_span(mut self, span: Span) -> Self {
+        let diagnostic = self.diagnostic_mut();
+        if diagnostic.span.is_dummy() {
+            diagnostic.span = span;
+        }
+        self
+    }
+
+    /// Render the error the way rustc does, pointing at the line of `source` it is about:
+    ///
+    /// ```text
+    /// error: mismatched types: expected `bool`, found `{integer}`
+    ///  --> bad_literal.rs:3:5
+    ///   |
+    /// 3 |     0
+    ///   |     ^
+    /// ```
+    ///
+    /// The errors reported along with this one follow, separated by blank lines.
+    pub fn render(&self, file_name: &str, source: &str) -> String {
+        let diagnostic = self.diagnostic();
+        let mut rendered = diagnostic.render(&self.to_string(), file_name, source);
+        for other in &diagnostic.others {
+            rendered += "\n";
+            rendered += &other.render(&other.message, file_name, source);
+        }
+        rendered
+    }
 }
 
 impl std::error::Error for CompilationError {}
 impl std::fmt::Display for CompilationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = &self.diagnostic().message;
         match self {
-            CompilationError::Parse(msg)
-            | CompilationError::Desugaring(msg)
-            | CompilationError::Formality(msg)
-            | CompilationError::MiniRust(msg) => write!(f, "{msg}"),
-            CompilationError::Internal(msg) => write!(f, "internal error: {msg}"),
+            CompilationError::Parse(_)
+            | CompilationError::Desugaring(_)
+            | CompilationError::Type(_)
+            | CompilationError::Formality(_)
+            | CompilationError::MiniRust(_)
+            | CompilationError::Runtime(_) => write!(f, "{message}"),
+            CompilationError::Internal(_) => write!(f, "internal error: {message}"),
         }
     }
 }
@@ -146,21 +535,23 @@ pub mod interactive_examples {
             .map(|example| example.id)
             .sorted()
             .format(", ");
-        Err(CompilationError::Internal(format!(
+        Err(CompilationError::Internal(Diagnostic::new(format!(
             "unknown interactive example `{example_id}`; expected one of: {}",
             supported,
-        )))
+        ))))
     }
 
     #[allow(unused)] // Used in wasm
-    fn parse_apply_interactive_example_and_print_program(
+    fn apply_interactive_example(
         example_id: &str,
         input: &str,
+        input_format: Format,
+        output_format: Format,
     ) -> Result<String, CompilationError> {
         let example = interactive_example_by_id(example_id)?;
-        let mut program = parser::parse_program(input)?;
+        let mut program = input_format.read_program(input)?;
         (example.step)(&mut program)?;
-        Ok(print_program(&program))
+        Ok(output_format.write_program(&program))
     }
 
     // Hack to make sure `inventory` works reliably on wasm.
@@ -177,12 +568,25 @@ pub mod interactive_examples {
 
     #[cfg(target_arch = "wasm32")]
     #[wasm_bindgen::prelude::wasm_bindgen]
+    /// `input_format` and `output_format` are `"rust"` or `"json"`, and `output_format` can also be
+    /// `"rustc"`, see [`Format`].
     pub fn interactive_desugar_example(
         example_id: &str,
         input: &str,
+        input_format: &str,
+        output_format: &str,
     ) -> Result<String, wasm_bindgen::JsValue> {
         ensure_wasm_ctors();
-        parse_apply_interactive_example_and_print_program(example_id, input)
+        let parse_format = |name: &str| {
+            Format::from_name(name).ok_or_else(|| {
+                CompilationError::Internal(Diagnostic::new(format!("unknown format `{name}`")))
+            })
+        };
+        parse_format(input_format)
+            .and_then(|input_format| {
+                let output_format = parse_format(output_format)?;
+                apply_interactive_example(example_id, input, input_format, output_format)
+            })
             .map_err(|error| wasm_bindgen::JsValue::from_str(&error.to_string()))
     }
 }
@@ -1,14 +1,290 @@
 use std::io::{self, Read};
 
-fn main() {
+use rust_via_desugarings::desugarings::{PASSES, pass_by_name, run_passes};
+use rust_via_desugarings::{
+    CompilationError, Format, check_and_run, check_with_formality, desugar, interpret,
+    translate_to_formality, translate_to_minirust,
+};
+
+const USAGE: &str = "\
+usage: rust-via-desugarings <command> [<options>] [<file>...]
+
+Reads each file in turn, or stdin if no file is given or the file is `-`.
+
+options:
+    --input-format rust|json      read Rust source (the default) or the AST as JSON
+    --format rust|json|rustc      for `parse` and `desugar`, print the program as Rust source, as
+                                  JSON, or as self-contained Rust that rustc can compile instead
+                                  of the default
+
+commands:
+    parse                         print the AST of the program
+    desugar                       print the desugared program
+        --stop-after <pass>       stop after the given pass
+        --emit final|all|diff     print the program after the last pass (the default), after
+                                  every pass, or only the lines each pass changed
+    check                         desugar, then check the program with a-mir-formality
+    run                           desugar, check, then run the program in MiniRust
+    translate --to <backend>      desugar, then print the program in the `formality` or
+                                  `minirust` backend
+    interpret                     run the program in the reference interpreter
+        --stop-after <pass>       run the program as it is after the given pass
+
+exit codes:
+    1    the input could not be read
+    2    bad usage
+    3    parse error
+    4    desugaring error
+    5    type error
+    6    a-mir-formality error
+    7    MiniRust error
+    8    runtime error in the reference interpreter
+    101  internal error";
+
+enum Command {
+    Parse,
+    Desugar {
+        stop_after: Option<String>,
+        emit: Emit,
+    },
+    Check,
+    Run,
+    Translate(Backend),
+    Interpret {
+        stop_after: Option<String>,
+    },
+}
+
+/// What to print while desugaring.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Emit {
+    /// The program after the last pass.
+    Final,
+    /// The program after every pass.
+    All,
+    /// The lines each pass changed.
+    Diff,
+}
+
+enum Backend {
+    Formality,
+    MiniRust,
+}
+
+struct Options {
+    input_format: Format,
+    /// `None` prints the AST for `parse` and Rust source for `desugar`.
+    output_format: Option<Format>,
+}
+
+fn parse_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<(Command, Options, Vec<String>), String> {
+    let mut command = match args.next().as_deref() {
+        Some("parse") => Command::Parse,
+        Some("desugar") => Command::Desugar {
+            stop_after: None,
+            emit: Emit::Final,
+        },
+        Some("check") => Command::Check,
+        Some("run") => Command::Run,
+        Some("translate") => Command::Translate(Backend::Formality),
+        Some("interpret") => Command::Interpret { stop_after: None },
+        Some(other) => return Err(format!("unknown command `{other}`")),
+        None => return Err("missing command".to_owned()),
+    };
+    let mut options = Options {
+        input_format: Format::Rust,
+        output_format: None,
+    };
+    let mut has_backend = false;
+    let mut files = vec![];
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("missing value for `{arg}`"))
+        };
+        let parse_format = |name: String| {
+            Format::from_name(&name).ok_or_else(|| format!("unknown format `{name}`"))
+        };
+        match (&mut command, arg.as_str()) {
+            (_, "--input-format") => {
+                options.input_format = parse_format(value()?)?;
+                if options.input_format == Format::Rustc {
+                    return Err("`rustc` is only an output format".to_owned());
+                }
+            }
+            (Command::Parse | Command::Desugar { .. }, "--format") => {
+                options.output_format = Some(parse_format(value()?)?)
+            }
+            (
+                Command::Desugar { stop_after, .. } | Command::Interpret { stop_after },
+                "--stop-after",
+            ) => {
+                let pass = value()?;
+                if pass_by_name(&pass).is_none() {
+                    let passes: Vec<_> = PASSES.iter().map(|pass| pass.name).collect();
+                    return Err(format!(
+                        "unknown pass `{pass}`; expected one of: {}",
+                        passes.join(", ")
+                    ));
+                }
+                *stop_after = Some(pass);
+            }
+            (Command::Desugar { emit, .. }, "--emit") => {
+                *emit = match value()?.as_str() {
+                    "final" => Emit::Final,
+                    "all" => Emit::All,
+                    "diff" => Emit::Diff,
+                    other => return Err(format!("unknown `--emit` kind `{other}`")),
+                }
+            }
+            (Command::Translate(backend), "--to") => {
+                *backend = match value()?.as_str() {
+                    "formality" => Backend::Formality,
+                    "minirust" => Backend::MiniRust,
+                    other => return Err(format!("unknown backend `{other}`")),
+                };
+                has_backend = true;
+            }
+            (_, "-") => files.push(arg),
+            (_, flag) if flag.starts_with('-') => {
+                return Err(format!("unexpected option `{flag}`"));
+            }
+            _ => files.push(arg),
+        }
+    }
+    if matches!(command, Command::Translate(_)) && !has_backend {
+        return Err("`translate` needs a `--to <backend>`".to_owned());
+    }
+    if files.is_empty() {
+        files.push("-".to_owned());
+    }
+    Ok((command, options, files))
+}
+
+fn execute(command: &Command, options: &Options, input: &str) -> Result<String, CompilationError> {
+    let program = options.input_format.read_program(input)?;
+    Ok(match command {
+        Command::Parse => match options.output_format {
+            Some(format) => format.write_program(&program),
+            None => format!("{program:#?}\n"),
+        },
+        Command::Desugar { stop_after, emit } => {
+            let output_format = options.output_format.unwrap_or(Format::Rust);
+            let mut output = String::new();
+            let mut previous = output_format.write_program(&program);
+            let program = run_passes(program, stop_after.as_deref(), |pass, program| {
+                let printed = output_format.write_program(program);
+                match emit {
+                    Emit::Final => {}
+                    Emit::All => output += &format!("// after `{}`\n{printed}", pass.name),
+                    Emit::Diff => {
+                        output += &format!("// `{}`\n{}", pass.name, line_diff(&previous, &printed))
+                    }
+                }
+                previous = printed;
+            })?;
+            if *emit == Emit::Final {
+                output = output_format.write_program(&program);
+            }
+            output
+        }
+        Command::Check => {
+            check_with_formality(&desugar(program)?)?;
+            String::new()
+        }
+        Command::Run => check_and_run(&desugar(program)?)?,
+        Command::Translate(Backend::Formality) => {
+            format!("{:#?}\n", translate_to_formality(&desugar(program)?)?)
+        }
+        Command::Translate(Backend::MiniRust) => {
+            format!("{:#?}\n", translate_to_minirust(&desugar(program)?)?)
+        }
+        Command::Interpret { stop_after: None } => interpret(&program)?,
+        Command::Interpret {
+            stop_after: Some(pass),
+        } => interpret(&run_passes(program, Some(pass.as_str()), |_, _| {})?)?,
+    })
+}
+
+fn exit_code(error: &CompilationError) -> i32 {
+    match error {
+        CompilationError::Parse(_) => 3,
+        CompilationError::Desugaring(_) => 4,
+        CompilationError::Type(_) => 5,
+        CompilationError::Formality(_) => 6,
+        CompilationError::MiniRust(_) => 7,
+        CompilationError::Runtime(_) => 8,
+        CompilationError::Internal(_) => 101,
+    }
+}
+
+fn read_input(file: &str) -> io::Result<String> {
     let mut input = String::new();
-    io::stdin().read_to_string(&mut input).unwrap();
+    if file == "-" {
+        io::stdin().read_to_string(&mut input)?;
+    } else {
+        input = std::fs::read_to_string(file)?;
+    }
+    Ok(input)
+}
 
-    match rust_via_desugarings::parse_desugar_and_print_program(&input) {
-        Ok(output) => print!("{output}"),
-        Err(error) => {
-            eprintln!("{error}");
+fn main() {
+    let (command, options, files) = parse_args(std::env::args().skip(1)).unwrap_or_else(|error| {
+        eprintln!("error: {error}\n\n{USAGE}");
+        std::process::exit(2);
+    });
+    for file in files {
+        let input = read_input(&file).unwrap_or_else(|error| {
+            eprintln!("error: cannot read `{file}`: {error}");
             std::process::exit(1);
+        });
+        match execute(&command, &options, &input) {
+            Ok(output) => print!("{output}"),
+            Err(error) => {
+                let name = if file == "-" { "<stdin>" } else { &file };
+                // The spans of a program read from JSON point into the Rust source it came from,
+                // which we don't have.
+                let located = options.input_format == Format::Rust
+                    || matches!(error, CompilationError::Parse(_));
+                let source = if located { &input } else { "" };
+                eprint!("{}", error.render(name, source));
+                std::process::exit(exit_code(&error));
+            }
+        }
+    }
+}
+
+/// The lines removed from `before` and added in `after`, prefixed with `-` and `+` respectively.
+fn line_diff(before: &str, after: &str) -> String {
+    let before: Vec<&str> = before.lines().collect();
+    let after: Vec<&str> = after.lines().collect();
+    // `common[i][j]` is the length of the longest common subsequence of `before[i..]` and
+    // `after[j..]`.
+    let mut common = vec![vec![0; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            common[i][j] = if before[i] == after[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            i += 1;
+            j += 1;
+        } else if i < before.len() && (j == after.len() || common[i + 1][j] >= common[i][j + 1]) {
+            diff.push_str(&format!("-{}\n", before[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+{}\n", after[j]));
+            j += 1;
         }
     }
+    diff
 }
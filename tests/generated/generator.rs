@@ -0,0 +1,1209 @@
+//! A generator of random well-typed programs.
+//!
+//! Programs are built directly as syntax trees, in the shape the parser gives to their printed
+//! form. In particular we add the parentheses that the printer would add as `Grouped` expressions,
+//! and we parenthesize expressions with a block wherever the grammar doesn't take them as they
+//! are.
+use rust_via_desugarings::Program;
+use rust_via_desugarings::language::*;
+
+/// The names of locals. There are few of them so that bindings get shadowed a lot.
+const NAMES: &[&str] = &["a", "b", "c", "x", "y"];
+const FIELD_NAMES: &[&str] = &["p", "q", "r"];
+const VARIANT_NAMES: &[&str] = &["A", "B", "C"];
+const INT_TYPES: &[IntType] = &[IntType::U8, IntType::I32, IntType::U64, IntType::Isize];
+const BINARY_OPERATORS: &[BinaryOperator] = &[
+    BinaryOperator::Add,
+    BinaryOperator::Sub,
+    BinaryOperator::Mul,
+    BinaryOperator::Div,
+    BinaryOperator::Rem,
+    BinaryOperator::BitAnd,
+    BinaryOperator::BitOr,
+    BinaryOperator::BitXor,
+    BinaryOperator::Shl,
+    BinaryOperator::Shr,
+];
+const BITWISE_OPERATORS: &[BinaryOperator] = &[
+    BinaryOperator::BitAnd,
+    BinaryOperator::BitOr,
+    BinaryOperator::BitXor,
+];
+const COMPARISON_OPERATORS: &[ComparisonOperator] = &[
+    ComparisonOperator::Eq,
+    ComparisonOperator::Ne,
+    ComparisonOperator::Lt,
+    ComparisonOperator::Gt,
+    ComparisonOperator::Le,
+    ComparisonOperator::Ge,
+];
+/// How deeply expressions nest, counting from the body of a function.
+const MAX_DEPTH: usize = 4;
+
+// Precedences, as in `OperatorExpression::precedence`.
+const ASSIGNMENT: u8 = 1;
+const LAZY_OR: u8 = 2;
+const LAZY_AND: u8 = 3;
+const COMPARISON: u8 = 4;
+const PREFIX: u8 = 11;
+
+/// The program generated from `seed`. The same seed always gives the same program.
+pub fn generate_program(seed: u64) -> Program {
+    Generator {
+        rng: Rng(seed),
+        structs: vec![],
+        enums: vec![],
+        functions: vec![],
+        scopes: vec![],
+        breakables: vec![],
+        labels: 0,
+        fuel: MAX_DEPTH,
+        in_condition: false,
+    }
+    .program()
+}
+
+/// SplitMix64, which is plenty random for picking syntax.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+/// A loop or labeled block that a `break` can exit.
+struct Breakable {
+    label: Option<String>,
+    /// The type of the values it is exited with.
+    ty: Type,
+    is_loop: bool,
+}
+
+struct Generator {
+    rng: Rng,
+    /// The structs declared so far, with their fields.
+    structs: Vec<(Identifier, Vec<(Identifier, Type)>)>,
+    /// The enums declared so far, with their variants.
+    enums: Vec<(Identifier, Vec<EnumVariant>)>,
+    /// The functions declared so far, with their parameter and return types. A function only
+    /// calls the ones declared before it, so that programs don't recurse.
+    functions: Vec<(Identifier, Vec<Type>, Type)>,
+    /// The locals in scope, innermost scope last.
+    scopes: Vec<Vec<(Identifier, Type)>>,
+    /// The loops and labeled blocks around the current expression, innermost last.
+    breakables: Vec<Breakable>,
+    labels: usize,
+    /// How much deeper expressions may nest.
+    fuel: usize,
+    /// Whether we're in the condition of an `if` or `while` or in the scrutinee of a `match`,
+    /// where a struct expression would be taken for the block that follows.
+    in_condition: bool,
+}
+
+impl Generator {
+    fn program(mut self) -> Program {
+        let mut items = vec![];
+        // The fields of a struct or variant only have the types declared before it, so that types
+        // aren't recursive.
+        for index in 0..self.rng.below(5) {
+            items.push(if self.rng.chance(50) {
+                self.struct_item(Identifier::new(format!("S{index}")))
+            } else {
+                self.enum_item(Identifier::new(format!("E{index}")))
+            });
+        }
+        for index in 0..self.rng.below(4) {
+            items.push(self.function(Identifier::new(format!("f{index}"))));
+        }
+        items.push(self.function(Identifier::new("main")));
+        Program { items }
+    }
+
+    fn struct_item(&mut self, name: Identifier) -> Item {
+        let fields: Vec<(Identifier, Type)> = FIELD_NAMES[..1 + self.rng.below(FIELD_NAMES.len())]
+            .iter()
+            .map(|field| (Identifier::new(*field), self.value_type()))
+            .collect();
+        self.structs.push((name.clone(), fields.clone()));
+        item(ItemKind::Struct(Struct {
+            name,
+            generic_params: GenericParams::default(),
+            where_clauses: WhereClauses::default(),
+            fields: StructFields::Named(
+                fields
+                    .into_iter()
+                    .map(|(name, ty)| StructField {
+                        attrs: vec![],
+                        visibility: None,
+                        name,
+                        ty,
+                    })
+                    .collect(),
+            ),
+        }))
+    }
+
+    fn enum_item(&mut self, name: Identifier) -> Item {
+        let variants: Vec<EnumVariant> = VARIANT_NAMES[..1 + self.rng.below(VARIANT_NAMES.len())]
+            .iter()
+            .map(|variant| EnumVariant {
+                attrs: vec![],
+                name: Identifier::new(*variant),
+                fields: self.variant_fields(),
+            })
+            .collect();
+        self.enums.push((name.clone(), variants.clone()));
+        item(ItemKind::Enum(Enum {
+            name,
+            generic_params: GenericParams::default(),
+            where_clauses: WhereClauses::default(),
+            variants,
+        }))
+    }
+
+    /// The fields of a unit, tuple or struct-like variant.
+    fn variant_fields(&mut self) -> StructFields {
+        match self.rng.below(3) {
+            0 => StructFields::Unit,
+            1 => StructFields::Tuple(
+                (0..1 + self.rng.below(2))
+                    .map(|_| TupleField {
+                        attrs: vec![],
+                        visibility: None,
+                        ty: self.value_type(),
+                    })
+                    .collect(),
+            ),
+            _ => StructFields::Named(
+                FIELD_NAMES[..1 + self.rng.below(FIELD_NAMES.len())]
+                    .iter()
+                    .map(|field| StructField {
+                        attrs: vec![],
+                        visibility: None,
+                        name: Identifier::new(*field),
+                        ty: self.value_type(),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn function(&mut self, name: Identifier) -> Item {
+        let is_main = name == "main";
+        let mut bound = vec![];
+        let mut parameters = vec![];
+        if !is_main {
+            for _ in 0..self.rng.below(3) {
+                let ty = self.ty();
+                parameters.push((self.pattern(&ty, false, &mut bound), ty));
+            }
+        }
+        let return_type = if is_main || self.rng.chance(20) {
+            Type::mk_unit()
+        } else {
+            self.value_type()
+        };
+
+        self.scopes = vec![bound];
+        self.breakables.clear();
+        let body = self.block(&return_type, None);
+        self.scopes.clear();
+
+        if !is_main {
+            let parameter_types = parameters.iter().map(|(_, ty)| ty.clone()).collect();
+            self.functions
+                .push((name.clone(), parameter_types, return_type.clone()));
+        }
+        item(ItemKind::Function(Function {
+            qualifiers: FunctionQualifiers::default(),
+            name,
+            generic_params: GenericParams::default(),
+            parameters: parameters
+                .into_iter()
+                .map(|(pattern, ty)| FunctionParam {
+                    attrs: vec![],
+                    kind: FunctionParamKind::Regular {
+                        pattern: Some(pattern),
+                        ty: FunctionParamType::Type(ty),
+                    },
+                })
+                .collect(),
+            return_type: (return_type != Type::mk_unit()).then_some(return_type),
+            where_clauses: WhereClauses::default(),
+            body: FunctionBody::Block(body),
+        }))
+    }
+
+    fn scalar_type(&mut self) -> Type {
+        if self.rng.chance(40) {
+            Type::new(TypeKind::Bool)
+        } else {
+            Type::new(TypeKind::Int(*self.rng.pick(INT_TYPES)))
+        }
+    }
+
+    /// A type that can be matched on with literal or variant patterns.
+    fn simple_type(&mut self) -> Type {
+        if !self.enums.is_empty() && self.rng.chance(25) {
+            let name = self.rng.pick(&self.enums).0.clone();
+            Type::new(TypeKind::Path(name, Span::dummy()))
+        } else if self.rng.chance(25) {
+            Type::new(TypeKind::Tuple(vec![
+                self.scalar_type(),
+                self.scalar_type(),
+            ]))
+        } else {
+            self.scalar_type()
+        }
+    }
+
+    /// A type without references, that can be the type of a field or be returned without
+    /// lifetimes.
+    fn value_type(&mut self) -> Type {
+        let adts: Vec<&Identifier> = self
+            .structs
+            .iter()
+            .map(|(name, _)| name)
+            .chain(self.enums.iter().map(|(name, _)| name))
+            .collect();
+        if !adts.is_empty() && self.rng.chance(15) {
+            let name = (*self.rng.pick(&adts)).clone();
+            Type::new(TypeKind::Path(name, Span::dummy()))
+        } else {
+            self.simple_type()
+        }
+    }
+
+    fn ty(&mut self) -> Type {
+        if self.rng.chance(15) {
+            let mutability = if self.rng.chance(50) {
+                Mutability::Mutable
+            } else {
+                Mutability::Immutable
+            };
+            Type::new(TypeKind::Ref(None, mutability, Box::new(self.value_type())))
+        } else {
+            self.value_type()
+        }
+    }
+
+    /// A block of type `ty`. With `exit`, the block ends with a `break` out of that breakable
+    /// instead of a tail expression.
+    fn block(&mut self, ty: &Type, exit: Option<usize>) -> BlockExpression {
+        let in_condition = std::mem::replace(&mut self.in_condition, false);
+        self.scopes.push(vec![]);
+        let mut statements = vec![];
+        let count = if self.fuel == 0 { 0 } else { self.rng.below(4) };
+        for _ in 0..count {
+            let statement = self.statement();
+            // After a statement with a block, the parser takes something like `*x = 1` or `(a, b)`
+            // for the continuation of that expression.
+            if let Statement::Expr(expression) = &statement
+                && follows_block(&statements)
+                && starts_like_an_operator(expression)
+            {
+                continue;
+            }
+            statements.push(statement);
+        }
+        let mut tail = None;
+        if let Some(exit) = exit {
+            let exit = self
+                .exit(exit, false)
+                .expect("the innermost loop can be exited");
+            statements.push(Statement::Expr(exit));
+        } else if *ty != Type::mk_unit() || self.rng.chance(15) {
+            let mut expression = operand(self.expression(ty), 0);
+            if follows_block(&statements) && starts_like_an_operator(&expression) {
+                let name = Identifier::new(self.rng.pick(NAMES).to_string());
+                statements.push(let_with(
+                    Pattern::new(PatternKind::Identifier(name.clone())),
+                    None,
+                    expression,
+                    None,
+                ));
+                expression = path(&name);
+            }
+            tail = Some(Box::new(expression));
+        }
+        self.scopes.pop();
+        self.in_condition = in_condition;
+        BlockExpression {
+            label: None,
+            inner_attrs: vec![],
+            statements,
+            tail,
+            origin: Origin::new(),
+        }
+    }
+
+    fn statement(&mut self) -> Statement {
+        match self.rng.below(10) {
+            0..=3 => return self.let_statement(),
+            4 => {
+                if let Some(exit) = self.exit_statement() {
+                    return exit;
+                }
+            }
+            _ => {}
+        }
+        let ty = if self.rng.chance(70) {
+            Type::mk_unit()
+        } else {
+            self.ty()
+        };
+        let expression = self.expression(&ty);
+        // Only unit expressions with a block can be statements as they are.
+        if ty != Type::mk_unit() && is_with_block(&expression) {
+            Statement::Expr(grouped(expression))
+        } else {
+            Statement::Expr(expression)
+        }
+    }
+
+    fn let_statement(&mut self) -> Statement {
+        let ty = self.ty();
+        let mut initial_value = self.expression(&ty);
+        let annotation = self.rng.chance(60).then(|| ty.clone());
+        let mut bound = vec![];
+        let refutable = self.rng.chance(10);
+        let pattern = self.pattern(&ty, refutable, &mut bound);
+        let mut else_branch = None;
+        if refutable {
+            // The initializer of a `let`-`else` can't end with a `}`.
+            if ends_with_brace(&initial_value) {
+                initial_value = grouped(initial_value);
+            }
+            let diverge = Expression::new(ExpressionKind::Loop(LoopExpression {
+                label: None,
+                kind: LoopKind::Infinite(plain_block(vec![])),
+            }));
+            else_branch = Some(plain_block(vec![Statement::Expr(diverge)]));
+        }
+        self.scopes.last_mut().unwrap().extend(bound);
+        let_with(pattern, annotation, initial_value, else_branch)
+    }
+
+    /// `if condition { break; }` or the like, if there is something to exit.
+    fn exit_statement(&mut self) -> Option<Statement> {
+        let target = self.rng.below(self.breakables.len().max(1));
+        self.breakables.get(target)?;
+        let exit = self.exit(target, true)?;
+        self.scopes.push(vec![]);
+        let condition = self.condition();
+        self.scopes.pop();
+        Some(Statement::Expr(Expression::new(ExpressionKind::If(
+            IfExpression {
+                condition: Box::new(condition),
+                then_branch: Box::new(plain_block(vec![Statement::Expr(exit)]).into()),
+                else_branch: None,
+            },
+        ))))
+    }
+
+    /// A `break`, or a `continue` if `may_continue`, to the breakable at index `target` if it can
+    /// be named from here: an unlabeled one only exits the innermost loop, and can't cross a
+    /// labeled block.
+    fn exit(&mut self, target: usize, may_continue: bool) -> Option<Expression> {
+        let is_innermost = target + 1 == self.breakables.len();
+        let breakable = &self.breakables[target];
+        let label = match &breakable.label {
+            Some(label) if !(is_innermost && breakable.is_loop) || self.rng.chance(50) => {
+                Some(label.clone())
+            }
+            _ if is_innermost && breakable.is_loop => None,
+            _ => return None,
+        };
+        if may_continue && breakable.is_loop && self.rng.chance(25) {
+            return Some(Expression::new(ExpressionKind::Continue(
+                ContinueExpression { label },
+            )));
+        }
+        let ty = breakable.ty.clone();
+        let value = (ty != Type::mk_unit()).then(|| Box::new(operand(self.expression(&ty), 0)));
+        Some(Expression::new(ExpressionKind::Break(BreakExpression {
+            label,
+            value,
+        })))
+    }
+
+    /// A pattern for a value of type `ty`. Its bindings are added to `bound`, and are distinct
+    /// from the ones already there.
+    fn pattern(
+        &mut self,
+        ty: &Type,
+        refutable: bool,
+        bound: &mut Vec<(Identifier, Type)>,
+    ) -> Pattern {
+        if let TypeKind::Tuple(elements) = &ty.kind
+            && self.rng.chance(60)
+        {
+            return Pattern::new(PatternKind::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.pattern(element, refutable, bound))
+                    .collect(),
+            ));
+        }
+        if refutable
+            && let TypeKind::Path(name, _) = &ty.kind
+            && let Some(variants) = self.variants(name)
+            && self.rng.chance(60)
+        {
+            let variant = self.rng.pick(&variants).clone();
+            let fields = variant
+                .fields
+                .field_list()
+                .into_iter()
+                .map(|(field, ty)| StructPatternField {
+                    name: field,
+                    pattern: self.pattern(ty, refutable, bound),
+                })
+                .collect();
+            return Pattern::new(PatternKind::Variant(VariantPattern {
+                path: VariantPath {
+                    enum_name: name.clone(),
+                    variant: variant.name,
+                },
+                fields,
+            }));
+        }
+        if refutable && self.rng.chance(40) {
+            let literal = match &ty.kind {
+                TypeKind::Bool => Some(LiteralExpression::Bool(self.rng.chance(50))),
+                TypeKind::Int(int_ty) => {
+                    Some(LiteralExpression::Integer(self.integer_literal(*int_ty)))
+                }
+                _ => None,
+            };
+            if let Some(literal) = literal {
+                return Pattern::new(PatternKind::Literal(literal));
+            }
+        }
+        let free: Vec<&str> = NAMES
+            .iter()
+            .copied()
+            .filter(|name| !bound.iter().any(|(bound, _)| *bound == *name))
+            .collect();
+        if free.is_empty() || self.rng.chance(20) {
+            return Pattern::new(PatternKind::Wildcard);
+        }
+        let name = Identifier::new(self.rng.pick(&free).to_string());
+        bound.push((name.clone(), ty.clone()));
+        Pattern::new(PatternKind::Identifier(name))
+    }
+
+    fn expression(&mut self, ty: &Type) -> Expression {
+        if self.fuel == 0 || self.rng.chance(30) {
+            return self.leaf(ty);
+        }
+        self.fuel -= 1;
+        let expression = loop {
+            let expression = match self.rng.below(12) {
+                0 => self.place(ty, false),
+                1 => Some(self.block_expression(ty)),
+                2 => Some(self.if_expression(ty)),
+                3 => Some(self.match_expression(ty)),
+                4 => Some(self.loop_expression(ty)),
+                5 => self.call(ty),
+                6 => Some(grouped(self.expression(ty))),
+                _ => Some(self.operation(ty)),
+            };
+            if let Some(expression) = expression {
+                break expression;
+            }
+        };
+        self.fuel += 1;
+        expression
+    }
+
+    /// A small expression: a local, a literal, or a constructor of literals.
+    fn leaf(&mut self, ty: &Type) -> Expression {
+        if self.rng.chance(60)
+            && let Some(place) = self.place(ty, false)
+        {
+            return place;
+        }
+        match &ty.kind {
+            TypeKind::Bool => literal(LiteralExpression::Bool(self.rng.chance(50))),
+            TypeKind::Int(int_ty) => {
+                literal(LiteralExpression::Integer(self.integer_literal(*int_ty)))
+            }
+            TypeKind::Tuple(elements) => Expression::new(ExpressionKind::Tuple(
+                elements.iter().map(|element| self.leaf(element)).collect(),
+            )),
+            TypeKind::Ref(_, mutability, pointee) => self.borrow(*mutability, pointee),
+            TypeKind::Path(name, _) => self.adt_expression(name),
+            _ => unreachable!("we don't generate values of type `{ty}`"),
+        }
+    }
+
+    fn integer_literal(&mut self, ty: IntType) -> IntegerLiteral {
+        IntegerLiteral {
+            value: self.rng.below(100) as u128,
+            suffix: self.rng.chance(50).then_some(ty),
+        }
+    }
+
+    /// An expression specific to `ty`, like an arithmetic operation for integers.
+    fn operation(&mut self, ty: &Type) -> Expression {
+        match &ty.kind {
+            TypeKind::Bool => self.boolean_operation(),
+            TypeKind::Int(int_ty) => self.integer_operation(*int_ty),
+            TypeKind::Tuple(elements) if elements.is_empty() => self.unit_operation(),
+            TypeKind::Tuple(elements) => Expression::new(ExpressionKind::Tuple(
+                elements
+                    .iter()
+                    .map(|element| self.expression(element))
+                    .collect(),
+            )),
+            TypeKind::Ref(_, mutability, pointee) => self.borrow(*mutability, pointee),
+            TypeKind::Path(name, _) => self.adt_expression(name),
+            _ => unreachable!("we don't generate values of type `{ty}`"),
+        }
+    }
+
+    fn boolean_operation(&mut self) -> Expression {
+        match self.rng.below(4) {
+            0 => {
+                let operand = self.operand(&bool_type(), PREFIX);
+                operator(OperatorExpression::Negation(NegationOperator::Not, operand))
+            }
+            1 => {
+                if self.rng.chance(50) {
+                    let left = self.operand(&bool_type(), LAZY_AND);
+                    let right = self.operand(&bool_type(), LAZY_AND + 1);
+                    operator(OperatorExpression::LazyAnd(left, right))
+                } else {
+                    let left = self.operand(&bool_type(), LAZY_OR);
+                    let right = self.operand(&bool_type(), LAZY_OR + 1);
+                    operator(OperatorExpression::LazyOr(left, right))
+                }
+            }
+            2 => {
+                let comparison = *self.rng.pick(COMPARISON_OPERATORS);
+                let ty = self.scalar_type();
+                let left = self.operand(&ty, COMPARISON + 1);
+                let right = self.operand(&ty, COMPARISON + 1);
+                operator(OperatorExpression::Comparison(comparison, left, right))
+            }
+            _ => {
+                let binary = *self.rng.pick(BITWISE_OPERATORS);
+                let left = self.operand(&bool_type(), binary.precedence());
+                let right = self.operand(&bool_type(), binary.precedence() + 1);
+                operator(OperatorExpression::Binary(binary, left, right))
+            }
+        }
+    }
+
+    fn integer_operation(&mut self, int_ty: IntType) -> Expression {
+        let ty = Type::new(TypeKind::Int(int_ty));
+        if self.rng.chance(20) {
+            let negation = if int_ty.is_signed() && self.rng.chance(50) {
+                NegationOperator::Neg
+            } else {
+                NegationOperator::Not
+            };
+            let operand = self.operand(&ty, PREFIX);
+            return operator(OperatorExpression::Negation(negation, operand));
+        }
+        let binary = *self.rng.pick(BINARY_OPERATORS);
+        let left = self.operand(&ty, binary.precedence());
+        // The two operands of a shift can have different types.
+        let right_ty = if binary.is_shift() {
+            Type::new(TypeKind::Int(*self.rng.pick(INT_TYPES)))
+        } else {
+            ty
+        };
+        let right = self.operand(&right_ty, binary.precedence() + 1);
+        operator(OperatorExpression::Binary(binary, left, right))
+    }
+
+    fn unit_operation(&mut self) -> Expression {
+        let expression = match self.rng.below(5) {
+            0 => self.assignment(),
+            1 => self.compound_assignment(),
+            2 => Some(self.while_loop()),
+            3 => Some(self.if_expression(&Type::mk_unit())),
+            _ => None,
+        };
+        expression.unwrap_or_else(|| {
+            let ty = self.scalar_type();
+            let argument = self.expression(&ty);
+            call(&Identifier::new("print"), vec![argument])
+        })
+    }
+
+    fn assignment(&mut self) -> Option<Expression> {
+        let ty = self.ty();
+        let place = self.place(&ty, true)?;
+        let value = self.operand(&ty, ASSIGNMENT);
+        Some(operator(OperatorExpression::Assignment(
+            operand(place, ASSIGNMENT + 1),
+            value,
+        )))
+    }
+
+    fn compound_assignment(&mut self) -> Option<Expression> {
+        let ty = Type::new(TypeKind::Int(*self.rng.pick(INT_TYPES)));
+        let place = self.place(&ty, true)?;
+        let binary = *self.rng.pick(BINARY_OPERATORS);
+        let value_ty = if binary.is_shift() {
+            Type::new(TypeKind::Int(*self.rng.pick(INT_TYPES)))
+        } else {
+            ty
+        };
+        let value = self.operand(&value_ty, ASSIGNMENT);
+        Some(operator(OperatorExpression::CompoundAssignment(
+            binary,
+            operand(place, ASSIGNMENT + 1),
+            value,
+        )))
+    }
+
+    /// An expression of type `ty` as an operand that binds at least as tightly as
+    /// `min_precedence`.
+    fn operand(&mut self, ty: &Type, min_precedence: u8) -> Expression {
+        let expression = self.expression(ty);
+        operand(expression, min_precedence)
+    }
+
+    fn borrow(&mut self, mutability: Mutability, pointee: &Type) -> Expression {
+        let place = self.place(pointee, mutability == Mutability::Mutable);
+        let borrowed = match place {
+            Some(place) if self.rng.chance(70) => place,
+            _ => self.expression(pointee),
+        };
+        operator(OperatorExpression::Borrow(BorrowExpression {
+            mutability,
+            expression: Box::new(operand(borrowed, PREFIX)),
+        }))
+    }
+
+    /// A value of the struct or enum `name`.
+    fn adt_expression(&mut self, name: &Identifier) -> Expression {
+        match self.variants(name) {
+            Some(variants) => self.variant_expression(name, &variants),
+            None => self.struct_expression(name),
+        }
+    }
+
+    /// The variants of `name`, if it is an enum.
+    fn variants(&self, name: &Identifier) -> Option<Vec<EnumVariant>> {
+        self.enums
+            .iter()
+            .find(|(declared, _)| declared == name)
+            .map(|(_, variants)| variants.clone())
+    }
+
+    /// A value of one of `variants`, written with the constructor of the variant or with a
+    /// struct expression.
+    fn variant_expression(&mut self, name: &Identifier, variants: &[EnumVariant]) -> Expression {
+        let variant = self.rng.pick(variants);
+        let path = VariantPath {
+            enum_name: name.clone(),
+            variant: variant.name.clone(),
+        };
+        let fields = variant.fields.field_list();
+        let braced = matches!(variant.fields, StructFields::Named(_)) || self.rng.chance(30);
+        if !braced {
+            let constructor = Expression::new(ExpressionKind::VariantPath(path));
+            if fields.is_empty() {
+                return constructor;
+            }
+            return Expression::new(ExpressionKind::Call(CallExpression {
+                callee: Box::new(constructor),
+                args: fields.iter().map(|(_, ty)| self.expression(ty)).collect(),
+            }));
+        }
+        let fields = fields
+            .into_iter()
+            .map(|(field, ty)| StructExprField {
+                attrs: vec![],
+                name: field,
+                value: self.expression(ty),
+            })
+            .collect();
+        let expression = Expression::new(ExpressionKind::EnumVariant(EnumVariantExpression {
+            path,
+            fields,
+        }));
+        if self.in_condition {
+            grouped(expression)
+        } else {
+            expression
+        }
+    }
+
+    fn struct_expression(&mut self, name: &Identifier) -> Expression {
+        let (_, fields) = self
+            .structs
+            .iter()
+            .find(|(declared, _)| declared == name)
+            .cloned()
+            .expect("generated types only name declared structs");
+        let fields = fields
+            .into_iter()
+            .map(|(field, ty)| StructExprField {
+                attrs: vec![],
+                name: FieldName::Named(field),
+                value: self.expression(&ty),
+            })
+            .collect();
+        let expression = Expression::new(ExpressionKind::Struct(StructExpression {
+            path: name.clone(),
+            fields,
+        }));
+        if self.in_condition {
+            grouped(expression)
+        } else {
+            expression
+        }
+    }
+
+    fn call(&mut self, ty: &Type) -> Option<Expression> {
+        let candidates: Vec<_> = self
+            .functions
+            .iter()
+            .filter(|(_, _, return_type)| return_type == ty)
+            .cloned()
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let (name, parameters, _) = self.rng.pick(&candidates).clone();
+        let args = parameters
+            .iter()
+            .map(|parameter| self.expression(parameter))
+            .collect();
+        Some(call(&name, args))
+    }
+
+    /// A place expression of type `ty` made from the locals in scope, like `x`, `x.0.p` or `*x`.
+    /// For a `mutable` place, we don't go through shared references.
+    fn place(&mut self, ty: &Type, mutable: bool) -> Option<Expression> {
+        let mut places = vec![];
+        for (name, local_ty) in self.locals() {
+            self.collect_places(path(&name), &local_ty, ty, mutable, 3, &mut places);
+        }
+        if places.is_empty() {
+            return None;
+        }
+        let index = self.rng.below(places.len());
+        Some(places.swap_remove(index))
+    }
+
+    /// The locals in scope, without the shadowed ones.
+    fn locals(&self) -> Vec<(Identifier, Type)> {
+        let mut locals: Vec<(Identifier, Type)> = vec![];
+        for (name, ty) in self
+            .scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+        {
+            if !locals.iter().any(|(local, _)| local == name) {
+                locals.push((name.clone(), ty.clone()));
+            }
+        }
+        locals
+    }
+
+    fn collect_places(
+        &self,
+        place: Expression,
+        place_ty: &Type,
+        wanted: &Type,
+        mutable: bool,
+        depth: usize,
+        places: &mut Vec<Expression>,
+    ) {
+        if place_ty == wanted {
+            places.push(place.clone());
+        }
+        if depth == 0 {
+            return;
+        }
+        match &place_ty.kind {
+            TypeKind::Ref(_, mutability, pointee) => {
+                if mutable && *mutability == Mutability::Immutable {
+                    return;
+                }
+                let dereference =
+                    operator(OperatorExpression::Dereference(DereferenceExpression {
+                        expression: Box::new(operand(place.clone(), PREFIX)),
+                    }));
+                self.collect_places(dereference, pointee, wanted, mutable, depth - 1, places);
+                // Field accesses see through references.
+                self.collect_projections(place, pointee, wanted, mutable, depth - 1, places);
+            }
+            _ => self.collect_projections(place, place_ty, wanted, mutable, depth - 1, places),
+        }
+    }
+
+    fn collect_projections(
+        &self,
+        base: Expression,
+        base_ty: &Type,
+        wanted: &Type,
+        mutable: bool,
+        depth: usize,
+        places: &mut Vec<Expression>,
+    ) {
+        match &base_ty.kind {
+            TypeKind::Tuple(elements) => {
+                for (index, element) in elements.iter().enumerate() {
+                    let projection =
+                        Expression::new(ExpressionKind::TupleIndexing(TupleIndexingExpression {
+                            expression: Box::new(projection_base(base.clone())),
+                            index,
+                        }));
+                    self.collect_places(projection, element, wanted, mutable, depth, places);
+                }
+            }
+            TypeKind::Path(name, _) => {
+                let Some((_, fields)) = self.structs.iter().find(|(declared, _)| declared == name)
+                else {
+                    return;
+                };
+                for (field, ty) in fields {
+                    let projection = Expression::new(ExpressionKind::Field(FieldExpression {
+                        expression: Box::new(projection_base(base.clone())),
+                        field: field.clone(),
+                    }));
+                    self.collect_places(projection, ty, wanted, mutable, depth, places);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn block_expression(&mut self, ty: &Type) -> Expression {
+        let label = self.rng.chance(30).then(|| self.label());
+        if label.is_some() {
+            self.breakables.push(Breakable {
+                label: label.clone(),
+                ty: ty.clone(),
+                is_loop: false,
+            });
+        }
+        let block = self.block(ty, None);
+        if label.is_some() {
+            self.breakables.pop();
+        }
+        block.with_label(label).into()
+    }
+
+    fn if_expression(&mut self, ty: &Type) -> Expression {
+        // The bindings of the condition are only in scope in the `then` branch.
+        self.scopes.push(vec![]);
+        let condition = self.condition();
+        let then_branch = self.block(ty, None);
+        self.scopes.pop();
+        let else_branch = if *ty == Type::mk_unit() && self.rng.chance(50) {
+            None
+        } else if self.fuel > 0 && self.rng.chance(30) {
+            self.fuel -= 1;
+            let else_if = self.if_expression(ty);
+            self.fuel += 1;
+            Some(else_if)
+        } else {
+            Some(self.block(ty, None).into())
+        };
+        Expression::new(ExpressionKind::If(IfExpression {
+            condition: Box::new(condition),
+            then_branch: Box::new(then_branch.into()),
+            else_branch: else_branch.map(Box::new),
+        }))
+    }
+
+    /// The condition of an `if` or `while`: a boolean, or a chain of `let`s and booleans. The
+    /// bindings of the `let`s go in the current scope.
+    fn condition(&mut self) -> Expression {
+        let in_condition = std::mem::replace(&mut self.in_condition, true);
+        let condition = if self.rng.chance(30) {
+            let mut chain = self.let_condition();
+            while self.rng.chance(30) {
+                let next = if self.rng.chance(50) {
+                    self.let_condition()
+                } else {
+                    self.operand(&bool_type(), LAZY_AND + 1)
+                };
+                chain = LetExpression::and(chain, next);
+            }
+            chain
+        } else {
+            self.operand(&bool_type(), 0)
+        };
+        self.in_condition = in_condition;
+        condition
+    }
+
+    fn let_condition(&mut self) -> Expression {
+        let ty = self.simple_type();
+        // A scrutinee ends before the `&&` that continues the chain.
+        let scrutinee = self.operand(&ty, COMPARISON);
+        let mut bound = vec![];
+        let pattern = self.pattern(&ty, true, &mut bound);
+        self.scopes.last_mut().unwrap().extend(bound);
+        Expression::new(ExpressionKind::Let(LetExpression {
+            pattern,
+            scrutinee: Box::new(scrutinee),
+        }))
+    }
+
+    fn match_expression(&mut self, ty: &Type) -> Expression {
+        let scrutinee_ty = self.simple_type();
+        let in_condition = std::mem::replace(&mut self.in_condition, true);
+        let scrutinee = self.operand(&scrutinee_ty, 0);
+        self.in_condition = in_condition;
+
+        // The last arm matches everything, so that the `match` is exhaustive.
+        let count = 1 + self.rng.below(3);
+        let patterns: Vec<_> = (0..count)
+            .map(|index| {
+                let mut bound = vec![];
+                let pattern = self.pattern(&scrutinee_ty, index + 1 < count, &mut bound);
+                (pattern, bound)
+            })
+            .collect();
+        let mut arms = vec![];
+        for (index, (pattern, bound)) in patterns.iter().enumerate() {
+            self.scopes.push(bound.clone());
+            let guard = (index + 1 < count && self.rng.chance(20))
+                .then(|| Box::new(self.operand(&bool_type(), 0)));
+            let mut body = self.expression(ty);
+            // Like after a statement with a block, a tuple pattern would be taken for arguments.
+            if is_with_block(&body)
+                && patterns
+                    .get(index + 1)
+                    .is_some_and(|(pattern, _)| matches!(pattern.kind, PatternKind::Tuple(_)))
+            {
+                body = grouped(body);
+            }
+            self.scopes.pop();
+            arms.push(MatchArm {
+                attrs: vec![],
+                pattern: pattern.clone(),
+                guard,
+                body: Box::new(body),
+            });
+        }
+        Expression::new(ExpressionKind::Match(MatchExpression {
+            scrutinee: Box::new(scrutinee),
+            arms,
+        }))
+    }
+
+    /// A `loop` whose body ends with a `break` carrying a value of type `ty`.
+    fn loop_expression(&mut self, ty: &Type) -> Expression {
+        let label = self.rng.chance(40).then(|| self.label());
+        self.breakables.push(Breakable {
+            label: label.clone(),
+            ty: ty.clone(),
+            is_loop: true,
+        });
+        let body = self.block(&Type::mk_unit(), Some(self.breakables.len() - 1));
+        self.breakables.pop();
+        Expression::new(ExpressionKind::Loop(LoopExpression {
+            label,
+            kind: LoopKind::Infinite(body),
+        }))
+    }
+
+    fn while_loop(&mut self) -> Expression {
+        self.scopes.push(vec![]);
+        // A `while` condition can't exit the loops around it.
+        let breakables = std::mem::take(&mut self.breakables);
+        let condition = self.condition();
+        self.breakables = breakables;
+        let label = self.rng.chance(40).then(|| self.label());
+        self.breakables.push(Breakable {
+            label: label.clone(),
+            ty: Type::mk_unit(),
+            is_loop: true,
+        });
+        let body = self.block(&Type::mk_unit(), None);
+        self.breakables.pop();
+        self.scopes.pop();
+        Expression::new(ExpressionKind::Loop(LoopExpression {
+            label,
+            kind: LoopKind::While {
+                condition: Box::new(condition),
+                body,
+            },
+        }))
+    }
+
+    fn label(&mut self) -> String {
+        self.labels += 1;
+        format!("'l{}", self.labels)
+    }
+}
+
+fn item(kind: ItemKind) -> Item {
+    Item {
+        attrs: vec![],
+        visibility: None,
+        kind,
+        span: Span::dummy(),
+        origin: Origin::new(),
+    }
+}
+
+fn bool_type() -> Type {
+    Type::new(TypeKind::Bool)
+}
+
+fn plain_block(statements: Vec<Statement>) -> BlockExpression {
+    BlockExpression {
+        label: None,
+        inner_attrs: vec![],
+        statements,
+        tail: None,
+        origin: Origin::new(),
+    }
+}
+
+fn let_with(
+    pattern: Pattern,
+    ty: Option<Type>,
+    initial_value: Expression,
+    else_branch: Option<BlockExpression>,
+) -> Statement {
+    Statement::Let {
+        attrs: vec![],
+        scope: None,
+        pattern,
+        ty,
+        initial_value: Some(initial_value),
+        else_branch,
+        span: Span::dummy(),
+        origin: Origin::new(),
+    }
+}
+
+fn literal(literal: LiteralExpression) -> Expression {
+    Expression::new(ExpressionKind::Literal(literal))
+}
+
+fn path(name: &Identifier) -> Expression {
+    Expression::new(ExpressionKind::Path(name.clone()))
+}
+
+fn call(name: &Identifier, args: Vec<Expression>) -> Expression {
+    Expression::new(ExpressionKind::Call(CallExpression {
+        callee: Box::new(path(name)),
+        args,
+    }))
+}
+
+fn operator(operator: OperatorExpression) -> Expression {
+    Expression::new(ExpressionKind::Operator(Box::new(operator)))
+}
+
+fn grouped(expression: Expression) -> Expression {
+    Expression::new(ExpressionKind::Grouped(Box::new(expression)))
+}
+
+fn is_with_block(expression: &Expression) -> bool {
+    matches!(
+        expression.kind,
+        ExpressionKind::Block(_)
+            | ExpressionKind::If(_)
+            | ExpressionKind::Loop(_)
+            | ExpressionKind::Match(_)
+    )
+}
+
+/// `expression` as an operand that binds at least as tightly as `min_precedence`. The printer
+/// parenthesizes looser operators, so we do too. Expressions with a block get parenthesized as
+/// well.
+fn operand(expression: Expression, min_precedence: u8) -> Expression {
+    let needs_parentheses = match &expression.kind {
+        ExpressionKind::Operator(operator) => operator.precedence() < min_precedence,
+        _ => is_with_block(&expression),
+    };
+    if needs_parentheses {
+        grouped(expression)
+    } else {
+        expression
+    }
+}
+
+/// `expression` as the base of a field access or tuple indexing, parenthesized like the printer
+/// does.
+fn projection_base(expression: Expression) -> Expression {
+    if let ExpressionKind::Operator(_) = expression.kind {
+        grouped(expression)
+    } else {
+        operand(expression, 0)
+    }
+}
+
+fn follows_block(statements: &[Statement]) -> bool {
+    matches!(statements.last(), Some(Statement::Expr(expression)) if is_with_block(expression))
+}
+
+/// Whether the first token of `expression` can also continue an expression, like `(` or `*`.
+fn starts_like_an_operator(expression: &Expression) -> bool {
+    match &expression.kind {
+        ExpressionKind::Grouped(_) | ExpressionKind::Tuple(_) => true,
+        ExpressionKind::Operator(operator) => match &**operator {
+            OperatorExpression::Borrow(_)
+            | OperatorExpression::Dereference(_)
+            | OperatorExpression::Negation(NegationOperator::Neg, _) => true,
+            OperatorExpression::Negation(NegationOperator::Not, _) => false,
+            OperatorExpression::Binary(_, left, _)
+            | OperatorExpression::Comparison(_, left, _)
+            | OperatorExpression::Assignment(left, _)
+            | OperatorExpression::CompoundAssignment(_, left, _)
+            | OperatorExpression::LazyAnd(left, _)
+            | OperatorExpression::LazyOr(left, _) => starts_like_an_operator(left),
+        },
+        ExpressionKind::Field(field) => starts_like_an_operator(&field.expression),
+        ExpressionKind::TupleIndexing(tuple_indexing) => {
+            starts_like_an_operator(&tuple_indexing.expression)
+        }
+        ExpressionKind::Call(call) => starts_like_an_operator(&call.callee),
+        _ => false,
+    }
+}
+
+/// Whether the last token of `expression` is a `}`.
+fn ends_with_brace(expression: &Expression) -> bool {
+    match &expression.kind {
+        ExpressionKind::Struct(_) | ExpressionKind::EnumVariant(_) => true,
+        ExpressionKind::Operator(operator) => match &**operator {
+            OperatorExpression::Borrow(BorrowExpression { expression, .. })
+            | OperatorExpression::Dereference(DereferenceExpression { expression }) => {
+                ends_with_brace(expression)
+            }
+            OperatorExpression::Negation(_, last)
+            | OperatorExpression::Binary(_, _, last)
+            | OperatorExpression::Comparison(_, _, last)
+            | OperatorExpression::Assignment(_, last)
+            | OperatorExpression::CompoundAssignment(_, _, last)
+            | OperatorExpression::LazyAnd(_, last)
+            | OperatorExpression::LazyOr(_, last) => ends_with_brace(last),
+        },
+        _ => is_with_block(expression),
+    }
+}
@@ -0,0 +1,135 @@
+//! Properties of the printer, the parser and the desugarings, checked on randomly generated
+//! programs. Each program comes from a seed; failures report the seed along with the program, and
+//! `GENERATED_SEED=<seed>` checks only that one.
+mod generator;
+
+use rust_via_desugarings::desugarings::final_language::check_final_language;
+use rust_via_desugarings::{CompilationError, Program, desugar, parser, print_program};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Check a single program instead of the usual range.
+const SEED_ENV_VAR: &str = "GENERATED_SEED";
+/// How many programs to check, `DEFAULT_PROGRAMS` if unset.
+const PROGRAMS_ENV_VAR: &str = "GENERATED_PROGRAMS";
+const DEFAULT_PROGRAMS: u64 = 200;
+/// How many failures to show, so that a broken pass doesn't print hundreds of programs.
+const SHOWN_FAILURES: usize = 3;
+
+#[test]
+fn printed_programs_parse_back() {
+    check(|program| {
+        let printed = print_program(program);
+        let parsed = parser::parse_printed_program(&printed).map_err(|error| {
+            format!(
+                "the printed program doesn't parse:\n{}",
+                error.render("generated.rs", &printed)
+            )
+        })?;
+        if parsed != *program {
+            return Err(format!(
+                "the printed program parses back to a different program:\n{}",
+                print_program(&parsed)
+            ));
+        }
+        Ok(())
+    });
+}
+
+#[test]
+fn desugaring_does_not_fail_internally() {
+    check(|program| desugar_checked(program).map(|_| ()));
+}
+
+#[test]
+fn desugared_programs_are_in_the_final_language() {
+    check(|program| {
+        let Ok(desugared) = desugar_checked(program)? else {
+            return Ok(());
+        };
+        check_final_language(&desugared).map_err(|error| {
+            format!(
+                "the desugared program is not in the final language: {error}\n{}",
+                print_program(&desugared)
+            )
+        })
+    });
+}
+
+/// A program in the final language has nothing left to desugar.
+#[test]
+fn desugaring_is_idempotent() {
+    check(|program| {
+        let Ok(desugared) = desugar_checked(program)? else {
+            return Ok(());
+        };
+        let again = desugar_checked(&desugared)?.map_err(|error| {
+            format!(
+                "desugaring the desugared program fails: {error}\n{}",
+                print_program(&desugared)
+            )
+        })?;
+        if again != desugared {
+            return Err(format!(
+                "desugaring the desugared program changes it:\n{}\nbecomes\n{}",
+                print_program(&desugared),
+                print_program(&again)
+            ));
+        }
+        Ok(())
+    });
+}
+
+/// Check `property` on the generated programs, and fail with the ones it doesn't hold for.
+fn check(property: impl Fn(&Program) -> Result<(), String>) {
+    let seeds = match env_number(SEED_ENV_VAR) {
+        Some(seed) => seed..seed + 1,
+        None => 0..env_number(PROGRAMS_ENV_VAR).unwrap_or(DEFAULT_PROGRAMS),
+    };
+    let mut failures = vec![];
+    for seed in seeds {
+        let program = generator::generate_program(seed);
+        if let Err(message) = property(&program) {
+            failures.push(format!(
+                "seed {seed}: {message}\n\nfor the program:\n{}",
+                print_program(&program)
+            ));
+        }
+    }
+    if !failures.is_empty() {
+        let count = failures.len();
+        failures.truncate(SHOWN_FAILURES);
+        panic!(
+            "failed on {count} generated programs, rerun one with `{SEED_ENV_VAR}=<seed>`:\n\n{}",
+            failures.join("\n\n")
+        );
+    }
+}
+
+fn env_number(env_var: &str) -> Option<u64> {
+    let value = std::env::var(env_var).ok()?;
+    Some(
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("`{env_var}` should be a number, not `{value}`")),
+    )
+}
+
+/// Desugar `program`. Internal errors and panics are failures; other errors mean the program
+/// was rejected, which can happen since the generator doesn't know all the rules.
+fn desugar_checked(program: &Program) -> Result<Result<Program, CompilationError>, String> {
+    let program = program.clone();
+    match panic::catch_unwind(AssertUnwindSafe(|| desugar(program))) {
+        Ok(Err(CompilationError::Internal(diagnostic))) => {
+            Err(format!("internal error: {}", diagnostic.message))
+        }
+        Ok(result) => Ok(result),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            Err(format!("desugaring panicked: {message}"))
+        }
+    }
+}
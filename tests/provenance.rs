@@ -0,0 +1,174 @@
+//! The node ids and origins that the parser and the desugarings record, see the Provenance chapter.
+use std::collections::HashSet;
+
+use rust_via_desugarings::json::{program_from_json, program_to_json};
+use rust_via_desugarings::language::*;
+use rust_via_desugarings::{desugar, parser::parse_program};
+
+const SOURCE: &str = "
+fn main() {
+    let x = (1, 2);
+    let (a, b) = x;
+    let y = 0;
+    while y < x.0 {
+        y = y + 1;
+    }
+    let z = match y {
+        0 => true,
+        1 => false,
+        _ => false,
+    };
+}
+";
+
+/// The origins of the nodes of `program`, in the order `NodeIds::assign` visits them.
+fn node_origins(program: &Program) -> Vec<Origin> {
+    let mut origins = vec![];
+    program.visit_all_infallible(|item: &Item| origins.push(item.origin.clone()));
+    program.visit_all_infallible(|statement: &Statement| {
+        if let Statement::Let { origin, .. } = statement {
+            origins.push(origin.clone());
+        }
+    });
+    program.visit_all_infallible(|expression: &Expression| origins.push(expression.origin.clone()));
+    program.visit_all_infallible(|block: &BlockExpression| origins.push(block.origin.clone()));
+    program.visit_all_infallible(|pattern: &Pattern| origins.push(pattern.origin.clone()));
+    program.visit_all_infallible(|ty: &Type| origins.push(ty.origin.clone()));
+    origins
+}
+
+fn node_ids(program: &Program) -> Vec<NodeId> {
+    node_origins(program)
+        .into_iter()
+        .map(|origin| origin.id)
+        .collect()
+}
+
+/// The origins of the expressions of `program` for which `filter` holds.
+fn expression_origins(program: &Program, filter: impl Fn(&Expression) -> bool) -> Vec<Origin> {
+    let mut origins = vec![];
+    program.visit_all_infallible(|expression: &Expression| {
+        if filter(expression) {
+            origins.push(expression.origin.clone());
+        }
+    });
+    origins
+}
+
+fn assert_distinct(ids: &[NodeId]) {
+    let mut seen = HashSet::new();
+    for id in ids {
+        assert_ne!(*id, NodeId::UNASSIGNED, "a node has no id");
+        assert!(seen.insert(*id), "two nodes have the id {id:?}");
+    }
+}
+
+#[test]
+fn ids_are_deterministic() {
+    let first = desugar(parse_program(SOURCE).unwrap()).unwrap();
+    let second = desugar(parse_program(SOURCE).unwrap()).unwrap();
+    assert_eq!(node_ids(&first), node_ids(&second));
+}
+
+#[test]
+fn desugared_nodes_have_distinct_ids() {
+    let program = parse_program(SOURCE).unwrap();
+    assert_distinct(&node_ids(&program));
+    let desugared = desugar(program).unwrap();
+    assert_distinct(&node_ids(&desugared));
+}
+
+/// A program read from JSON keeps its ids, and the nodes that have none, or that share the id of
+/// another node, get ids that no node of the program had.
+#[test]
+fn ids_read_from_json_do_not_collide() {
+    let mut program = parse_program(SOURCE).unwrap();
+    let old_ids: HashSet<NodeId> = node_ids(&program).into_iter().collect();
+    let ItemKind::Function(main) = &mut program.items[0].kind else {
+        panic!("expected a function");
+    };
+    let FunctionBody::Block(body) = &mut main.body else {
+        panic!("expected a body");
+    };
+    let copy = body.statements[0].clone();
+    body.statements.push(copy);
+    let unit = Expression::new(ExpressionKind::Tuple(vec![]));
+    body.statements.push(Statement::Expr(unit));
+
+    let read = program_from_json(&program_to_json(&program)).unwrap();
+    let ids = node_ids(&read);
+    assert_distinct(&ids);
+    let new_ids: Vec<_> = ids.iter().filter(|id| !old_ids.contains(id)).collect();
+    // The copied `let`, its pattern, its initializer and the two elements of the tuple, and the
+    // unit expression.
+    assert_eq!(new_ids.len(), 6, "new ids: {new_ids:?}");
+}
+
+/// Following the origins of the desugared nodes leads to nodes of the source program, through
+/// nodes that each know the pass that made them.
+#[test]
+fn desugared_nodes_come_from_the_source() {
+    let program = parse_program(SOURCE).unwrap();
+    let source_ids: HashSet<NodeId> = node_ids(&program).into_iter().collect();
+    let desugared = desugar(program).unwrap();
+    for origin in node_origins(&desugared) {
+        let chain = origin.chain();
+        let (made, source) = chain.split_at(chain.len() - 1);
+        assert!(
+            made.iter()
+                .all(|(id, pass)| *id != NodeId::UNASSIGNED && pass.is_some()),
+            "{chain:?}"
+        );
+        assert!(source_ids.contains(&source[0].0), "{chain:?}");
+    }
+}
+
+#[test]
+fn loop_comes_from_the_while_loop() {
+    let is_loop = |expression: &Expression| matches!(expression.kind, ExpressionKind::Loop(_));
+    let program = parse_program(SOURCE).unwrap();
+    let loops = expression_origins(&program, is_loop);
+    let [while_loop] = &loops[..] else {
+        panic!("expected one loop");
+    };
+    let desugared = desugar(program).unwrap();
+    let loops = expression_origins(&desugared, is_loop);
+    let [desugared_loop] = &loops[..] else {
+        panic!("expected one loop");
+    };
+    assert_eq!(
+        desugared_loop.chain(),
+        [(desugared_loop.id, Some("loops")), (while_loop.id, None)]
+    );
+}
+
+/// The `if let`s a `match` desugars to each mention a copy of the scrutinee, made from it. Pattern
+/// unnesting then reads a copy of that copy.
+#[test]
+fn scrutinee_copies_come_from_the_scrutinee() {
+    let program = parse_program(SOURCE).unwrap();
+    let mut scrutinees = vec![];
+    program.visit_all_infallible(|expression: &Expression| {
+        if let ExpressionKind::Match(match_expression) = &expression.kind {
+            scrutinees.push(match_expression.scrutinee.origin.id);
+        }
+    });
+    let [scrutinee] = scrutinees[..] else {
+        panic!("expected one `match`");
+    };
+    let desugared = desugar(program).unwrap();
+    let copies = expression_origins(&desugared, |expression| {
+        expression.origin.source() == scrutinee
+    });
+    // One per arm, except the `_` arm which doesn't read the scrutinee.
+    let [first, second] = &copies[..] else {
+        panic!("expected two copies: {copies:?}");
+    };
+    let (first, second) = (first.chain(), second.chain());
+    assert_ne!(first[1].0, second[1].0);
+    for chain in [first, second] {
+        let passes: Vec<_> = chain.iter().map(|(_, pass)| *pass).collect();
+        assert_eq!(passes, [Some("patterns"), Some("matches"), None]);
+        assert_eq!(chain[2].0, scrutinee);
+    }
+}
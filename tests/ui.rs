@@ -1,11 +1,41 @@
 use libtest_mimic::{Failed, Trial};
-use std::{error::Error, fs, path::Path};
+use rust_via_desugarings::language::{Item, ItemKind};
+use rust_via_desugarings::{CompilationError, Program};
+use std::{error::Error, fmt, fs, io, path::Path, process::Command};
 use walkdir::WalkDir;
 
 const TESTS_DIR: &str = "tests/ui";
 const DESUGARED_SUFFIX: &str = "desugared.rs";
+/// Setting this environment variable, or passing `--bless`, overwrites the snapshots with the
+/// current outputs instead of comparing them.
+const BLESS_ENV_VAR: &str = "BLESS";
+/// Setting this environment variable, or passing `--rustc`, also compiles each program and its
+/// desugaring with rustc and checks that they agree.
+const RUSTC_ENV_VAR: &str = "RUSTC_DIFFERENTIAL";
+const EDITIONS: &[&str] = &["2015", "2018", "2021", "2024"];
+/// The edition whose rules the pipeline implements. Tests for other editions are rejected.
+const SUPPORTED_EDITION: &str = "2024";
+
+/// How to run the tests, from the command line and the environment.
+#[derive(Clone, Copy)]
+struct Options {
+    bless: bool,
+    rustc: bool,
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
+    // libtest-mimic doesn't know about our flags.
+    let (our_args, args): (Vec<String>, Vec<String>) =
+        std::env::args().partition(|arg| arg == "--bless" || arg == "--rustc");
+    let enabled = |flag: &str, env_var: &str| {
+        our_args.iter().any(|arg| arg == flag)
+            || std::env::var_os(env_var).is_some_and(|value| !value.is_empty())
+    };
+    let options = Options {
+        bless: enabled("--bless", BLESS_ENV_VAR),
+        rustc: enabled("--rustc", RUSTC_ENV_VAR),
+    };
+
     let mut tests = Vec::new();
     for entry in WalkDir::new(TESTS_DIR).sort_by_file_name() {
         let path = entry?.into_path();
@@ -19,96 +49,554 @@ fn main() -> Result<(), Box<dyn Error>> {
                 .expect("ui test path should be under the tests/ui directory")
                 .display()
                 .to_string();
-            tests.push(Trial::test(name, move || run_case(&path)));
+            tests.push(Trial::test(name.clone(), move || {
+                run_case(&path, &name, options)
+            }));
         }
     }
 
-    let args = libtest_mimic::Arguments::from_args();
+    let args = libtest_mimic::Arguments::from_iter(args);
     libtest_mimic::run(&args, tests).exit();
 }
 
+/// The stages a test goes through, in order.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Parse,
+    Desugar,
+    /// Checking the desugared program with a-mir-formality, which includes borrow checking.
+    Check,
+    /// Running the desugared program in MiniRust.
+    Run,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Stage::Parse => "parse",
+            Stage::Desugar => "desugar",
+            Stage::Check => "check",
+            Stage::Run => "run",
+        })
+    }
+}
+
+/// The `//@` lines at the start of a test.
 struct Directives {
-    known_failure: bool,
+    /// `run`: check and run the desugared program, and check that each pass preserves its
+    /// behavior.
     run: bool,
+    /// `parse-fail`, `desugar-fail`, `check-fail` or `run-fail`: the stage at which the test is
+    /// expected to fail.
+    fail: Option<Stage>,
+    /// `error-pattern: <text>`: the error must contain each of these.
+    error_patterns: Vec<String>,
+    /// `stop-after: <pass>`: snapshot the program after that pass instead of the final program.
+    stop_after: Option<String>,
+    /// `edition: <year>`. Only [`SUPPORTED_EDITION`] is accepted for now.
+    edition: String,
 }
 
-fn parse_directives(input: &str) -> Directives {
+fn parse_directives(input: &str) -> Result<Directives, String> {
     let mut directives = Directives {
-        known_failure: false,
         run: false,
+        fail: None,
+        error_patterns: vec![],
+        stop_after: None,
+        edition: SUPPORTED_EDITION.to_owned(),
     };
 
     for line in input.lines() {
         let Some(directive) = line.strip_prefix("//@") else {
             break;
         };
-        match directive.trim() {
-            "known-failure" => directives.known_failure = true,
-            "run" => directives.run = true,
-            _ => {}
+        let (name, value) = match directive.split_once(':') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (directive.trim(), None),
+        };
+        let fail = match (name, value) {
+            ("run", None) => {
+                directives.run = true;
+                None
+            }
+            ("parse-fail", None) => Some(Stage::Parse),
+            ("desugar-fail", None) => Some(Stage::Desugar),
+            ("check-fail", None) => Some(Stage::Check),
+            ("run-fail", None) => Some(Stage::Run),
+            ("error-pattern", Some(pattern)) => {
+                directives.error_patterns.push(pattern.to_owned());
+                None
+            }
+            ("stop-after", Some(pass)) => {
+                if rust_via_desugarings::desugarings::pass_by_name(pass).is_none() {
+                    return Err(format!("`stop-after`: unknown pass `{pass}`"));
+                }
+                directives.stop_after = Some(pass.to_owned());
+                None
+            }
+            ("edition", Some(edition)) => {
+                if !EDITIONS.contains(&edition) {
+                    return Err(format!("`edition`: unknown edition `{edition}`"));
+                }
+                if edition != SUPPORTED_EDITION {
+                    return Err(format!(
+                        "`edition`: the pipeline only implements edition {SUPPORTED_EDITION}, not {edition}"
+                    ));
+                }
+                directives.edition = edition.to_owned();
+                None
+            }
+            _ => return Err(format!("unknown directive `{}`", directive.trim())),
+        };
+        if let Some(fail) = fail {
+            if directives.fail.is_some() {
+                return Err("a test can only fail at one stage".to_owned());
+            }
+            directives.fail = Some(fail);
         }
     }
 
-    directives
+    if !directives.error_patterns.is_empty() && directives.fail.is_none() {
+        return Err(
+            "`error-pattern` needs a directive saying at which stage the test fails".to_owned(),
+        );
+    }
+    if directives.stop_after.is_some()
+        && (directives.run || matches!(directives.fail, Some(Stage::Check | Stage::Run)))
+    {
+        return Err(
+            "`stop-after` stops before the desugared program can be checked or run".to_owned(),
+        );
+    }
+    Ok(directives)
 }
 
-fn run_case(input_path: &Path) -> Result<(), Failed> {
+impl Directives {
+    /// Check that the error that happened at `stage` is the expected one.
+    fn expect_failure(&self, stage: Stage, error: &CompilationError) -> Result<(), String> {
+        match self.fail {
+            Some(expected) if expected == stage => {}
+            Some(expected) => Err(format!(
+                "expected {expected} failure, got {stage} error:\n{error}"
+            ))?,
+            None => Err(format!("expected success, got {stage} error:\n{error}"))?,
+        }
+        let message = error.to_string();
+        for pattern in &self.error_patterns {
+            if !message.contains(pattern.as_str()) {
+                Err(format!(
+                    "expected the error to contain `{pattern}`, got:\n{message}"
+                ))?
+            }
+        }
+        Ok(())
+    }
+
+    fn expect_success(&self) -> Result<(), String> {
+        match self.fail {
+            Some(expected) => Err(format!("expected {expected} failure, but the test passed")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// What a test case outputs. Each output is compared with the snapshot file of the same extension
+/// next to the input; `None` means that there should be no such file.
+#[derive(Default)]
+struct Outputs {
+    desugared: Option<String>,
+    stdout: Option<String>,
+    stderr: Option<String>,
+}
+
+fn run_case(input_path: &Path, name: &str, options: Options) -> Result<(), Failed> {
     let input = fs::read_to_string(input_path)
         .map_err(|error| format!("failed to read input file: {error}"))?;
-    let directives = parse_directives(&input);
-    let desugared_path = input_path.with_extension(DESUGARED_SUFFIX);
-    let stdout_path = input_path.with_extension("out");
-    let stderr_path = input_path.with_extension("stderr");
+    let mut outputs = Outputs::default();
+    let result = check_case(&input, name, options, &mut outputs);
 
-    let result = rust_via_desugarings::parser::parse_program(&input);
+    let snapshots = [
+        (DESUGARED_SUFFIX, outputs.desugared),
+        ("out", outputs.stdout),
+        ("stderr", outputs.stderr),
+    ];
+    let mut mismatches = String::new();
+    for (extension, actual) in snapshots {
+        let path = input_path.with_extension(extension);
+        if options.bless {
+            bless_snapshot(&path, actual.as_deref())?;
+        } else if let Some(mismatch) = compare_snapshot(&path, actual.as_deref()) {
+            mismatches += &mismatch;
+        }
+    }
+    result?;
+    if !mismatches.is_empty() {
+        Err(format!(
+            "outputs do not match the snapshots; run with `--bless` or `{BLESS_ENV_VAR}=1` to update them:\n{mismatches}"
+        ))?
+    }
+    Ok(())
+}
+
+fn check_case(
+    input: &str,
+    name: &str,
+    options: Options,
+    outputs: &mut Outputs,
+) -> Result<(), Failed> {
+    let directives = parse_directives(input)?;
+    let result = rust_via_desugarings::parser::parse_program(input);
     if let Ok(program) = &result {
         // Roundtrip the printer.
         let roundtrip = rust_via_desugarings::print_program(program);
-        let reparsed = rust_via_desugarings::parser::parse_program(&roundtrip)
+        let reparsed = rust_via_desugarings::parser::parse_printed_program(&roundtrip)
             .map_err(|error| format!("failed to re-parse printed source:\n{error}"))?;
         if &reparsed != program {
             Err(format!(
                 "printed source did not round-trip:\nprinted source:\n{roundtrip}\noriginal AST:\n{program:#?}\nreparsed AST:\n{reparsed:#?}"
             ))?
         }
+
+        // Roundtrip through JSON.
+        let json = rust_via_desugarings::json::program_to_json(program);
+        let deserialized = rust_via_desugarings::json::program_from_json(&json)
+            .map_err(|error| format!("failed to read back the JSON of the AST:\n{error}"))?;
+        if &deserialized != program {
+            Err(format!(
+                "JSON did not round-trip:\nJSON:\n{json}\noriginal AST:\n{program:#?}\ndeserialized AST:\n{deserialized:#?}"
+            ))?
+        }
     }
 
-    let result = result.and_then(rust_via_desugarings::desugar);
-
-    let _ = fs::remove_file(&desugared_path);
-    let _ = fs::remove_file(&stdout_path);
-    let _ = fs::remove_file(&stderr_path);
-    match result {
-        Ok(program) => {
-            let output = rust_via_desugarings::print_program(&program);
-            write_output(&desugared_path, output)?;
-            if directives.run {
-                match rust_via_desugarings::check_and_run(&program) {
-                    Ok(stdout) => write_output(&stdout_path, stdout)?,
-                    Err(error) => {
-                        write_output(&stderr_path, format!("{error}\n"))?;
-                        if !directives.known_failure {
-                            Err(format!("expected run success, got error:\n{error}"))?
-                        }
-                        return Ok(());
-                    }
-                }
-            }
-            if directives.known_failure {
-                Err("expected failure, but parsing and printing succeeded".to_owned())?
-            }
+    let program = match result {
+        Ok(program) => program,
+        Err(error) => {
+            outputs.stderr = Some(error.render(name, input));
+            return Ok(directives.expect_failure(Stage::Parse, &error)?);
         }
+    };
+
+    // The program before any pass, then after each pass.
+    let mut stages = vec![("source", program.clone())];
+    let stop_after = directives.stop_after.as_deref();
+    let result =
+        rust_via_desugarings::desugarings::run_passes(program, stop_after, |pass, program| {
+            stages.push((pass.name, program.clone()))
+        });
+    let program = match result {
+        Ok(program) => program,
         Err(error) => {
-            write_output(&stderr_path, format!("{error}\n"))?;
-            if !directives.known_failure {
-                Err(format!("expected success, got parse error:\n{error}"))?
+            outputs.stderr = Some(error.render(name, input));
+            return Ok(directives.expect_failure(Stage::Desugar, &error)?);
+        }
+    };
+    outputs.desugared = Some(rust_via_desugarings::print_program(&program));
+    let run = directives.run || directives.fail == Some(Stage::Run);
+    check_stages(&stages, run)?;
+    if directives.stop_after.is_some() {
+        return Ok(directives.expect_success()?);
+    }
+    // `desugar` only checks this in debug builds.
+    rust_via_desugarings::desugarings::final_language::check_final_language(&program).map_err(
+        |error| {
+            format!(
+                "desugared program is not in the final language:\n{}",
+                error.render(name, input)
+            )
+        },
+    )?;
+
+    let check = run || directives.fail == Some(Stage::Check);
+    if options.rustc {
+        let (_, source) = &stages[0];
+        compare_with_rustc(name, source, &program, &directives)?;
+    }
+    let mut failure = None;
+    if check && let Err(error) = rust_via_desugarings::check_with_formality(&program) {
+        failure = Some((Stage::Check, error));
+    }
+    if run && failure.is_none() {
+        match rust_via_desugarings::run_in_minirust(&program) {
+            Ok(stdout) => outputs.stdout = Some(stdout),
+            Err(error) => failure = Some((Stage::Run, error)),
+        }
+    }
+    match failure {
+        Some((stage, error)) => {
+            outputs.stderr = Some(error.render(name, input));
+            Ok(directives.expect_failure(stage, &error)?)
+        }
+        None => Ok(directives.expect_success()?),
+    }
+}
+
+/// What running one stage of the program did.
+enum Behavior {
+    Output(String),
+    /// Any two runtime errors count as the same behavior, since their messages change as the
+    /// program gets desugared.
+    RuntimeError(String),
+}
+
+impl Behavior {
+    fn of(result: Result<String, CompilationError>) -> Self {
+        match result {
+            Ok(stdout) => Behavior::Output(stdout),
+            Err(error) => Behavior::RuntimeError(error.to_string()),
+        }
+    }
+
+    fn same_as(&self, other: &Behavior) -> bool {
+        match (self, other) {
+            (Behavior::Output(a), Behavior::Output(b)) => a == b,
+            (Behavior::RuntimeError(_), Behavior::RuntimeError(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Behavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Behavior::Output(stdout) => write!(f, "printed:\n{stdout}"),
+            Behavior::RuntimeError(error) => write!(f, "failed with: {error}\n"),
+        }
+    }
+}
+
+/// Check that desugaring preserves the meaning of the program: the stages that a-mir-formality
+/// can check must all be accepted or all be rejected and, for `run` tests, every stage must behave
+/// like the one before it in the reference interpreter and, for the stages in the final language,
+/// in MiniRust as well. Reports the first pass at which the behavior changes.
+fn check_stages(stages: &[(&str, Program)], run: bool) -> Result<(), String> {
+    let mut previous: Option<(&str, Behavior)> = None;
+    let mut previous_verdict: Option<(&str, bool)> = None;
+    for (stage, program) in stages {
+        let in_final_language =
+            rust_via_desugarings::desugarings::final_language::check_final_language(program)
+                .is_ok();
+        let accepted =
+            in_final_language.then(|| rust_via_desugarings::check_with_formality(program).is_ok());
+        if let Some(accepted) = accepted {
+            if let Some((previous_stage, previous_accepted)) = previous_verdict
+                && previous_accepted != accepted
+            {
+                let verdict = |accepted| if accepted { "accepts" } else { "rejects" };
+                Err(format!(
+                    "behavior diverges at pass `{stage}`: the borrow checker {} the program after `{previous_stage}` but {} it after `{stage}`",
+                    verdict(previous_accepted),
+                    verdict(accepted),
+                ))?
+            }
+            previous_verdict = Some((stage, accepted));
+        }
+        // Other tests need not have a `main`, nor terminate.
+        if !run {
+            continue;
+        }
+        let mut behavior = Behavior::of(rust_via_desugarings::interpret(program));
+        if accepted == Some(true) {
+            let in_minirust = Behavior::of(rust_via_desugarings::run_in_minirust(program));
+            if !in_minirust.same_as(&behavior) {
+                Err(format!(
+                    "after `{stage}`, the reference interpreter {behavior}but MiniRust {in_minirust}"
+                ))?
             }
+            behavior = in_minirust;
+        }
+        if let Some((previous_stage, previous_behavior)) = &previous
+            && !behavior.same_as(previous_behavior)
+        {
+            Err(format!(
+                "behavior diverges at pass `{stage}`: after `{previous_stage}`, the program {previous_behavior}but after `{stage}` it {behavior}"
+            ))?
         }
+        previous = Some((stage, behavior));
     }
     Ok(())
 }
 
-fn write_output(path: &Path, output: impl AsRef<str>) -> Result<(), String> {
-    fs::write(path, output.as_ref())
-        .map_err(|error| format!("failed to write output file {}: {error}", path.display()))
+/// Check that rustc accepts the original program if and only if it accepts the desugared one and,
+/// for `run` tests, that both print the same thing. `original` is the program as parsed from the
+/// input file.
+fn compare_with_rustc(
+    name: &str,
+    original: &Program,
+    desugared: &Program,
+    directives: &Directives,
+) -> Result<(), String> {
+    // Until we know the types of their places, enum projections look like nested field accesses,
+    // which we would print as such.
+    let mut original = original.clone();
+    let types = rust_via_desugarings::desugarings::type_checking::node_types(&original)
+        .map_err(|error| format!("failed to type-check the original program: {error}"))?;
+    original.resolve_enum_projections(&types);
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("ui")
+        .join(Path::new(name).with_extension(""));
+    fs::create_dir_all(&dir)
+        .map_err(|error| format!("failed to create {}: {error}", dir.display()))?;
+    let is_main =
+        |item: &Item| matches!(&item.kind, ItemKind::Function(function) if function.name == "main");
+    let has_main = original.items.iter().any(is_main);
+    let compile = |file: &str, program: &Program| -> Result<Option<Command>, String> {
+        let source_path = dir.join(file).with_extension("rs");
+        let binary_path = dir.join(file);
+        let source = rust_via_desugarings::print_program_as_rust(program);
+        fs::write(&source_path, source)
+            .map_err(|error| format!("failed to write {}: {error}", source_path.display()))?;
+        let mut rustc = Command::new("rustc");
+        rustc
+            .args(["--edition", &directives.edition])
+            .args(["--cap-lints", "allow", "-C", "overflow-checks=off"])
+            .arg("-o")
+            .arg(&binary_path)
+            .arg(&source_path);
+        if !has_main {
+            rustc.args(["--crate-type", "lib", "--emit", "metadata"]);
+        }
+        let output = rustc
+            .output()
+            .map_err(|error| format!("failed to run rustc: {error}"))?;
+        Ok(output.status.success().then(|| Command::new(&binary_path)))
+    };
+
+    match (
+        compile("original", &original)?,
+        compile("desugared", desugared)?,
+    ) {
+        (Some(mut original), Some(mut desugared)) => {
+            let run = directives.run || directives.fail == Some(Stage::Run);
+            if run && has_main {
+                let mut execute = |binary: &mut Command, which: &str| {
+                    binary
+                        .output()
+                        .map(|output| (output.status.success(), output.stdout))
+                        .map_err(|error| format!("failed to run the {which} program: {error}"))
+                };
+                let original = execute(&mut original, "original")?;
+                let desugared = execute(&mut desugared, "desugared")?;
+                if original != desugared {
+                    let describe = |(success, stdout): &(bool, Vec<u8>)| {
+                        let outcome = if *success { "succeeds" } else { "fails" };
+                        format!(
+                            "{outcome} after printing:\n{}",
+                            String::from_utf8_lossy(stdout)
+                        )
+                    };
+                    Err(format!(
+                        "compiled with rustc, the original program {}but the desugared one {}",
+                        describe(&original),
+                        describe(&desugared),
+                    ))?
+                }
+            }
+            Ok(())
+        }
+        (None, None) => Ok(()),
+        (Some(_), None) => Err(format!(
+            "rustc accepts the original program but rejects the desugared one; see {}",
+            dir.display()
+        )),
+        (None, Some(_)) => Err(format!(
+            "rustc rejects the original program but accepts the desugared one; see {}",
+            dir.display()
+        )),
+    }
+}
+
+/// Describe how `actual` differs from the snapshot at `path`, if it does.
+fn compare_snapshot(path: &Path, actual: Option<&str>) -> Option<String> {
+    let expected = fs::read_to_string(path).ok();
+    if expected.as_deref() == actual {
+        return None;
+    }
+    let path = path.display();
+    Some(match (expected.as_deref(), actual) {
+        (Some(_), None) => format!("{path} is not produced anymore\n"),
+        (expected, actual) => format!(
+            "--- {path}\n+++ {path} (actual)\n{}",
+            unified_diff(expected.unwrap_or(""), actual.unwrap_or(""))
+        ),
+    })
+}
+
+fn bless_snapshot(path: &Path, actual: Option<&str>) -> Result<(), String> {
+    match actual {
+        Some(actual) => fs::write(path, actual)
+            .map_err(|error| format!("failed to write output file {}: {error}", path.display())),
+        None => match fs::remove_file(path) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(format!(
+                "failed to remove output file {}: {error}",
+                path.display()
+            )),
+            _ => Ok(()),
+        },
+    }
+}
+
+/// The hunks of a unified diff from `before` to `after`, with three lines of context.
+fn unified_diff(before: &str, after: &str) -> String {
+    const CONTEXT: usize = 3;
+    let before: Vec<&str> = before.lines().collect();
+    let after: Vec<&str> = after.lines().collect();
+    // `common[i][j]` is the length of the longest common subsequence of `before[i..]` and
+    // `after[j..]`.
+    let mut common = vec![vec![0; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            common[i][j] = if before[i] == after[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    // Each line of the diff, with its kind and its index in `before` and `after`.
+    let mut lines = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            lines.push((' ', i, j, before[i]));
+            i += 1;
+            j += 1;
+        } else if i < before.len() && (j == after.len() || common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(('-', i, j, before[i]));
+            i += 1;
+        } else {
+            lines.push(('+', i, j, after[j]));
+            j += 1;
+        }
+    }
+
+    let mut diff = String::new();
+    let mut next = 0;
+    while let Some(first_change) = (next..lines.len()).find(|&index| lines[index].0 != ' ') {
+        // A hunk goes on until there are enough unchanged lines to separate it from the next one.
+        let mut last_change = first_change;
+        for (index, line) in lines.iter().enumerate().skip(first_change) {
+            if index - last_change > 2 * CONTEXT {
+                break;
+            }
+            if line.0 != ' ' {
+                last_change = index;
+            }
+        }
+        let start = first_change.saturating_sub(CONTEXT);
+        next = (last_change + 1 + CONTEXT).min(lines.len());
+        let hunk = &lines[start..next];
+        let before_len = hunk.iter().filter(|line| line.0 != '+').count();
+        let after_len = hunk.iter().filter(|line| line.0 != '-').count();
+        // Empty ranges start at the line before them.
+        let first_line = |index: usize, len: usize| if len == 0 { index } else { index + 1 };
+        diff += &format!(
+            "@@ -{},{before_len} +{},{after_len} @@\n",
+            first_line(hunk[0].1, before_len),
+            first_line(hunk[0].2, after_len)
+        );
+        for (kind, _, _, line) in hunk {
+            diff += &format!("{kind}{line}\n");
+        }
+    }
+    diff
 }
@@ -1,3 +0,0 @@
-fn f() -> bool {
-    0
-}
@@ -1,3 +1,5 @@
+//@ desugar-fail
+//@ error-pattern: mismatched types
 fn f() -> bool {
     0
 }
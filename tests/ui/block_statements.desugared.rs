@@ -1,3 +1,5 @@
+fn a() -> () {}
+fn b() -> () {}
 fn f() -> () {
     a();
     b();
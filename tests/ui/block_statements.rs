@@ -1,3 +1,7 @@
+fn a() {}
+
+fn b() {}
+
 fn f() {
     a();
     b();
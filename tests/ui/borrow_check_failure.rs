@@ -1,4 +1,5 @@
-//@ known-failure
+//@ check-fail
+//@ error-pattern: borrow check failed
 //@ run
 fn main() {
     let x: bool = false;
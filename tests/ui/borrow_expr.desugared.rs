@@ -1,6 +1,14 @@
-fn f() -> () {
+fn f(foo: bool) -> () {
     &foo;
     &mut foo;
-    &value_to_place!(&foo);
-    &value_to_place!(&mut foo);
+    {
+        let tmp#1: &bool;
+        tmp#1 = &foo;
+        &tmp#1;
+    }
+    {
+        let tmp#2: &mut bool;
+        tmp#2 = &mut foo;
+        &tmp#2;
+    }
 }
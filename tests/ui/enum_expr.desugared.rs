@@ -0,0 +1,28 @@
+enum Shape {
+    Empty,
+    Circle(bool),
+    Square { big: bool, filled: bool },
+}
+fn make() -> Shape {
+    Shape::Circle { 0: true }
+}
+fn main() -> () {
+    let x: bool;
+    x = true;
+    let a: Shape;
+    a = Shape::Empty {};
+    let b: Shape;
+    b = Shape::Circle { 0: place_to_value!(x) };
+    let c: Shape;
+    c = Shape::Square { big: place_to_value!(x), filled: false };
+    let y: bool;
+    y = place_to_value!(b.Circle.0);
+    c.Square.big = place_to_value!(y);
+    let tmp#1: Shape;
+    tmp#1 = make();
+    let r: &bool;
+    r = &tmp#1.Circle.0;
+    let d: isize;
+    d = place_to_value!(a.enum#discriminant);
+    a.enum#discriminant = discriminant_of!(Shape, Circle);
+}
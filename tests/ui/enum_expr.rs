@@ -0,0 +1,21 @@
+enum Shape {
+    Empty,
+    Circle(bool),
+    Square { big: bool, filled: bool },
+}
+
+fn make() -> Shape {
+    Shape::Circle(true)
+}
+
+fn main() {
+    let x: bool = true;
+    let a: Shape = Shape::Empty;
+    let b: Shape = Shape::Circle(x);
+    let c: Shape = Shape::Square { big: x, filled: false };
+    let y: bool = b.Circle.0;
+    c.Square.big = y;
+    let r: &bool = &make().Circle.0;
+    let d = a.enum#discriminant;
+    a.enum#discriminant = discriminant_of!(Shape, Circle);
+}
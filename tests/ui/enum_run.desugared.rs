@@ -0,0 +1,22 @@
+enum Shape {
+    Empty,
+    Circle(bool),
+    Square { big: bool, filled: bool },
+}
+fn main() -> () {
+    let a: Shape;
+    a = Shape::Empty {};
+    let b: Shape;
+    b = Shape::Circle { 0: true };
+    let c: Shape;
+    c = Shape::Square { big: false, filled: true };
+    print(place_to_value!(a.enum#discriminant));
+    print(place_to_value!(c.enum#discriminant));
+    c.Square.big = place_to_value!(b.Circle.0);
+    print(place_to_value!(c.Square.big));
+    print(place_to_value!(c.Square.filled));
+    a.Circle.0 = false;
+    a.enum#discriminant = discriminant_of!(Shape, Circle);
+    print(place_to_value!(a.enum#discriminant));
+    print(place_to_value!(a.Circle.0));
+}
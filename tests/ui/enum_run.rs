@@ -0,0 +1,21 @@
+//@ run
+enum Shape {
+    Empty,
+    Circle(bool),
+    Square { big: bool, filled: bool },
+}
+
+fn main() {
+    let a: Shape = Shape::Empty;
+    let b: Shape = Shape::Circle(true);
+    let c: Shape = Shape::Square { big: false, filled: true };
+    print(a.enum#discriminant);
+    print(c.enum#discriminant);
+    c.Square.big = b.Circle.0;
+    print(c.Square.big);
+    print(c.Square.filled);
+    a.Circle.0 = false;
+    a.enum#discriminant = discriminant_of!(Shape, Circle);
+    print(a.enum#discriminant);
+    print(a.Circle.0);
+}
@@ -0,0 +1,21 @@
+enum Light {
+    Off,
+    On(bool),
+}
+enum Dial {
+    Off,
+    On(i32),
+}
+fn main() -> () {
+    let light: Light;
+    light = Light::On { 0: true };
+    let dial: Dial;
+    dial = Dial::On { 0: 7 };
+    print(place_to_value!(light.On.0));
+    print(place_to_value!(dial.On.0));
+    dial.On.0 = 8;
+    print(place_to_value!(dial.On.0));
+    let reference: &Dial;
+    reference = &dial;
+    print(place_to_value!((*reference).On.0));
+}
@@ -0,0 +1,21 @@
+//@ run
+enum Light {
+    Off,
+    On(bool),
+}
+
+enum Dial {
+    Off,
+    On(i32),
+}
+
+fn main() {
+    let light: Light = Light::On(true);
+    let dial: Dial = Dial::On(7);
+    print(light.On.0);
+    print(dial.On.0);
+    dial.On.0 = 8;
+    print(dial.On.0);
+    let reference: &Dial = &dial;
+    print((*reference).On.0);
+}
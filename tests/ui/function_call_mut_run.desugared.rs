@@ -4,6 +4,8 @@ fn foo(x: &mut bool) -> () {
 fn main() -> () {
     let x: bool;
     x = false;
-    foo(&mut x);
+    let tmp#1: &mut bool;
+    tmp#1 = &mut x;
+    foo(place_to_value!(tmp#1));
     print(place_to_value!(x));
 }
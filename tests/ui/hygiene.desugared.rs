@@ -0,0 +1,10 @@
+fn main() -> () {
+    let tmp1: bool;
+    tmp1 = true;
+    let tmp#1: bool;
+    tmp#1 = {
+        place_to_value!(tmp1)
+    };
+    let y: &bool;
+    y = &tmp#1;
+}
@@ -0,0 +1,5 @@
+// The temporary must not be confused with the user's `tmp1`.
+fn main() {
+    let tmp1 = true;
+    let y = &{ tmp1 };
+}
@@ -0,0 +1,6 @@
+//@ parse-fail
+//@ error-pattern: unexpected hygiene marker in `x#1`
+// Only the desugarings can make up names with a hygiene marker.
+fn main() {
+    let x#1 = true;
+}
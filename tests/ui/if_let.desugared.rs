@@ -0,0 +1,38 @@
+fn f() -> (bool, bool) {
+    (true, false)
+}
+fn main() -> () {
+    let tmp#2: bool;
+    let tmp#3: &bool;
+    tmp#2 = true;
+    tmp#3 = &tmp#2;
+    let x: &&bool;
+    x = &tmp#3;
+    if let tmp#4 = f() && let a = place_to_value!(tmp#4.0) && let tmp#10 = place_to_value!(tmp#4.1) == true && place_to_value!(tmp#10) {
+        print(place_to_value!(a));
+    } else {}
+    if let tmp#5 = f() && let a#1 = place_to_value!(tmp#5.0) && let b = place_to_value!(tmp#5.1) && place_to_value!(b) && let tmp#11 = place_to_value!(a#1) == true && place_to_value!(tmp#11) {
+        print(1);
+    } else {
+        print(2);
+    }
+    loop {
+        if let tmp#6 = f() && let tmp#12 = place_to_value!(tmp#6.0) == true && place_to_value!(tmp#12) {
+            break;
+        } else {
+            break;
+        }
+    }
+    let c: bool;
+    let d: bool;
+    {
+        let tmp#7: (bool, bool);
+        tmp#7 = f();
+        let c#8: bool;
+        c#8 = place_to_value!(tmp#7.0);
+        let d#9: bool;
+        d#9 = place_to_value!(tmp#7.1);
+        c = place_to_value!(c#8);
+        d = place_to_value!(d#9);
+    }
+}
@@ -0,0 +1,19 @@
+fn f() -> (bool, bool) {
+    (true, false)
+}
+
+fn main() {
+    let x: &&bool = &&true;
+    if let (a, true) = f() {
+        print(a);
+    }
+    if let (a, b) = f() && b && let true = a {
+        print(1);
+    } else {
+        print(2);
+    }
+    while let (true, _) = f() {
+        break;
+    }
+    let (c, d) = f();
+}
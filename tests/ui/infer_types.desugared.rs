@@ -0,0 +1,25 @@
+fn pair() -> (u8, bool) {
+    (1, true)
+}
+fn main() -> () {
+    let a: u16;
+    a = 1;
+    let b: u16;
+    b = place_to_value!(a) + 2u16;
+    let c: i32;
+    c = 3;
+    let d: u8;
+    let e: bool;
+    {
+        let tmp#1: (u8, bool);
+        tmp#1 = pair();
+        let d#2: u8;
+        d#2 = place_to_value!(tmp#1.0);
+        let e#3: bool;
+        e#3 = place_to_value!(tmp#1.1);
+        d = place_to_value!(d#2);
+        e = place_to_value!(e#3);
+    }
+    let f: i32;
+    f = place_to_value!(c);
+}
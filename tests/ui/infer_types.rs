@@ -0,0 +1,12 @@
+fn pair() -> (u8, bool) {
+    (1, true)
+}
+
+fn main() {
+    let a = 1;
+    let b = a + 2u16;
+    let c = 3;
+    let (d, e) = pair();
+    let f;
+    f = c;
+}
@@ -0,0 +1,18 @@
+fn main() -> () {
+    let a: u8;
+    a = 255;
+    let b: i64;
+    b = 2147483647i64;
+    let c: u32;
+    c = 170;
+    let d: usize;
+    d = 1000000;
+    let e: i16;
+    e = 511i16;
+    print(place_to_value!(a));
+    print(place_to_value!(b));
+    print(place_to_value!(c));
+    print(place_to_value!(d));
+    print(place_to_value!(e));
+    print(18446744073709551615u64);
+}
@@ -0,0 +1,14 @@
+//@ run
+fn main() {
+    let a: u8 = 255;
+    let b: i64 = 0x7fff_ffff_i64;
+    let c: u32 = 0b1010_1010;
+    let d: usize = 1_000_000;
+    let e: i16 = 0o777i16;
+    print(a);
+    print(b);
+    print(c);
+    print(d);
+    print(e);
+    print(18_446_744_073_709_551_615u64);
+}
@@ -0,0 +1,19 @@
+fn id(x: u8) -> u8 {
+    place_to_value!(x)
+}
+fn main() -> () {
+    let a: u8;
+    a = 255u8;
+    let b: i16;
+    b = 127i16;
+    let c: (u16, isize);
+    c = (1u16, 2isize);
+    let d: u128;
+    d = 1000u128;
+    let e: u8;
+    e = id(place_to_value!(a));
+    let tmp#1: i64;
+    tmp#1 = 3i64;
+    let r: &i64;
+    r = &tmp#1;
+}
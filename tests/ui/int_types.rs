@@ -0,0 +1,12 @@
+fn id(x: u8) -> u8 {
+    x
+}
+
+fn main() {
+    let a: u8 = 255u8;
+    let b: i16 = 0x7f_i16;
+    let c: (u16, isize) = (1u16, 2isize);
+    let d: u128 = 1_000u128;
+    let e: u8 = id(a);
+    let r: &i64 = &0b11i64;
+}
@@ -0,0 +1,35 @@
+fn main() -> () {
+    let a: bool;
+    a = true;
+    let b: bool;
+    b = false;
+    let tmp#1: bool;
+    tmp#1 = if place_to_value!(a) {
+        place_to_value!(b)
+    } else {
+        false
+    };
+    let c: bool;
+    c = if place_to_value!(tmp#1) {
+        true
+    } else {
+        !place_to_value!(a)
+    };
+    let d: bool;
+    d = if place_to_value!(a) {
+        true
+    } else {
+        if place_to_value!(b) {
+            place_to_value!(c)
+        } else {
+            false
+        }
+    };
+    if let x = a && let tmp#2 = if place_to_value!(b) {
+        true
+    } else {
+        place_to_value!(c)
+    } && place_to_value!(tmp#2) {
+        print(place_to_value!(x));
+    } else {}
+}
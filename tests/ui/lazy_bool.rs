@@ -0,0 +1,9 @@
+fn main() {
+    let a: bool = true;
+    let b: bool = false;
+    let c: bool = a && b || !a;
+    let d: bool = a || (b && c);
+    if let x = a && (b || c) {
+        print(x);
+    }
+}
@@ -0,0 +1,50 @@
+fn main() -> () {
+    let tmp#1: bool;
+    tmp#1 = {
+        print(1);
+        false
+    };
+    let a: bool;
+    a = if place_to_value!(tmp#1) {
+        print(2);
+        true
+    } else {
+        false
+    };
+    print(place_to_value!(a));
+    let tmp#2: bool;
+    tmp#2 = {
+        print(3);
+        true
+    };
+    let b: bool;
+    b = if place_to_value!(tmp#2) {
+        true
+    } else {
+        print(4);
+        false
+    };
+    print(place_to_value!(b));
+    let tmp#3: bool;
+    tmp#3 = {
+        print(5);
+        true
+    };
+    let tmp#4: bool;
+    tmp#4 = if place_to_value!(tmp#3) {
+        print(6);
+        true
+    } else {
+        false
+    };
+    let tmp#5: bool;
+    tmp#5 = if place_to_value!(tmp#4) {
+        true
+    } else {
+        print(7);
+        false
+    };
+    if place_to_value!(tmp#5) {
+        print(8);
+    } else {}
+}
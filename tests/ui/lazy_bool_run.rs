@@ -0,0 +1,10 @@
+//@ run
+fn main() {
+    let a: bool = { print(1); false } && { print(2); true };
+    print(a);
+    let b: bool = { print(3); true } || { print(4); false };
+    print(b);
+    if { print(5); true } && { print(6); true } || { print(7); false } {
+        print(8);
+    }
+}
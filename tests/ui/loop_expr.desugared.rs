@@ -0,0 +1,30 @@
+fn main() -> () {
+    let x: bool;
+    x = true;
+    loop {
+        break;
+    }
+    'outer: loop {
+        if place_to_value!(x) {
+            loop {
+                if true {
+                    continue 'outer;
+                } else {
+                    break;
+                }
+            }
+            x = false;
+        } else {
+            break;
+        }
+    }
+    let y: bool;
+    y = loop {
+        break true;
+    };
+    'a: loop {
+        loop {
+            break 'a;
+        }
+    }
+}
@@ -0,0 +1,20 @@
+fn main() {
+    let x: bool = true;
+    loop {
+        break;
+    }
+    'outer: while x {
+        while true {
+            continue 'outer;
+        }
+        x = false;
+    }
+    let y: bool = loop {
+        break true;
+    };
+    'a: loop {
+        loop {
+            break 'a;
+        }
+    }
+}
@@ -0,0 +1,44 @@
+enum Shape {
+    Empty,
+    Circle(bool),
+    Square { big: bool, filled: bool },
+}
+fn main() -> () {
+    let a: Shape;
+    a = Shape::Circle { 0: false };
+    let b: Shape;
+    b = Shape::Square { big: true, filled: false };
+    let tmp#1: bool;
+    tmp#1 = place_to_value!(a.enum#discriminant) == discriminant_of!(Shape, Circle);
+    let tmp#2: bool;
+    tmp#2 = if place_to_value!(tmp#1) {
+        place_to_value!(a.Circle.0) == true
+    } else {
+        false
+    };
+    if place_to_value!(tmp#2) {
+        print(1)
+    } else if let tmp#3 = place_to_value!(a.enum#discriminant) == discriminant_of!(Shape, Circle) && place_to_value!(tmp#3) && let inside = place_to_value!(a.Circle.0) {
+        print(place_to_value!(inside))
+    } else if true {
+        print(0)
+    } else {
+        unreachable_unchecked!()
+    }
+    if let tmp#4 = place_to_value!(b.enum#discriminant) == discriminant_of!(Shape, Square) && place_to_value!(tmp#4) && let big = place_to_value!(b.Square.big) && let tmp#5 = place_to_value!(b.Square.filled) == true && place_to_value!(tmp#5) {
+        print(place_to_value!(big))
+    } else if let tmp#6 = place_to_value!(b.enum#discriminant) == discriminant_of!(Shape, Square) && place_to_value!(tmp#6) && let tmp#7 = place_to_value!(b.Square.big) == true && place_to_value!(tmp#7) && let filled = place_to_value!(b.Square.filled) {
+        print(place_to_value!(filled))
+    } else if true {
+        print(0)
+    } else {
+        unreachable_unchecked!()
+    }
+    let tmp#8: bool;
+    tmp#8 = place_to_value!(a.enum#discriminant) == discriminant_of!(Shape, Empty);
+    if place_to_value!(tmp#8) {
+        print(2);
+    } else {
+        print(3);
+    }
+}
@@ -0,0 +1,25 @@
+enum Shape {
+    Empty,
+    Circle(bool),
+    Square { big: bool, filled: bool },
+}
+
+fn main() {
+    let a: Shape = Shape::Circle(false);
+    let b: Shape = Shape::Square { big: true, filled: false };
+    match a {
+        Shape::Circle(true) => print(1),
+        Shape::Circle(inside) => print(inside),
+        _ => print(0),
+    }
+    match b {
+        Shape::Square { big, filled: true } => print(big),
+        Shape::Square { big: true, filled } => print(filled),
+        _ => print(0),
+    }
+    if let Shape::Empty = a {
+        print(2);
+    } else {
+        print(3);
+    }
+}
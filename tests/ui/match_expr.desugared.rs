@@ -0,0 +1,67 @@
+fn f() -> (bool, bool) {
+    (true, false)
+}
+fn main() -> () {
+    let x: bool;
+    x = true;
+    let tmp#4: bool;
+    tmp#4 = place_to_value!(x) == true;
+    let y: bool;
+    y = if place_to_value!(tmp#4) {
+        false
+    } else {
+        let tmp#5: bool;
+        tmp#5 = place_to_value!(x) == false;
+        if place_to_value!(tmp#5) {
+            true
+        } else {
+            unreachable_unchecked!()
+        }
+    };
+    {
+        let tmp#1: (bool, bool);
+        tmp#1 = f();
+        if let tmp#6 = place_to_value!(tmp#1.0) == true && place_to_value!(tmp#6) && let b = place_to_value!(tmp#1.1) && place_to_value!(b) {
+            print(1)
+        } else {
+            let tmp#7: bool;
+            tmp#7 = place_to_value!(tmp#1.1) == false;
+            if place_to_value!(tmp#7) {
+                print(2);
+            } else if true {
+                print(3)
+            } else {
+                unreachable_unchecked!()
+            }
+        }
+    }
+    let tmp#2: (bool, bool);
+    let tmp#3: (bool, bool);
+    let tmp#8: bool;
+    tmp#8 = place_to_value!(x) == true;
+    let z: &(bool, bool);
+    z = if place_to_value!(tmp#8) {
+        tmp#2 = f();
+        &tmp#2
+    } else {
+        let tmp#9: bool;
+        tmp#9 = place_to_value!(x) == false;
+        if place_to_value!(tmp#9) {
+            tmp#3 = f();
+            &tmp#3
+        } else {
+            unreachable_unchecked!()
+        }
+    };
+    let tmp#10: bool;
+    tmp#10 = place_to_value!(y) == true;
+    if place_to_value!(tmp#10) {} else if true {
+        if let a = x {
+            print(place_to_value!(a))
+        } else {
+            unreachable_unchecked!()
+        }
+    } else {
+        unreachable_unchecked!()
+    }
+}
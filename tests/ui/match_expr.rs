@@ -0,0 +1,28 @@
+fn f() -> (bool, bool) {
+    (true, false)
+}
+
+fn main() {
+    let x: bool = true;
+    let y: bool = match x {
+        true => false,
+        false => true,
+    };
+    match f() {
+        (true, b) if b => print(1),
+        (_, false) => {
+            print(2);
+        }
+        _ => print(3),
+    }
+    let z = match x {
+        true => &f(),
+        false => &f(),
+    };
+    match y {
+        true => {}
+        _ => match x {
+            a => print(a)
+        }
+    }
+}
@@ -1,22 +1,26 @@
 fn main() -> () {
-    let value: bool;
-    value = if {
-        let x;
+    let tmp#4: bool;
+    tmp#4 = {
+        let x: bool;
         x = true;
         place_to_value!(x)
-    } {
+    };
+    let value: bool;
+    value = if place_to_value!(tmp#4) {
         false
     } else {
         true
     };
-    let value: &bool;
-    value = {
-        let y;
-        y = &value_to_place!({
-            let x;
-            x = true;
-            place_to_value!(x)
-        });
+    let value#2: &bool;
+    value#2 = {
+        let tmp#3: bool;
+        tmp#3 = {
+            let x#1: bool;
+            x#1 = true;
+            place_to_value!(x#1)
+        };
+        let y: &bool;
+        y = &tmp#3;
         place_to_value!(y)
     };
 }
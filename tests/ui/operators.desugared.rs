@@ -0,0 +1,51 @@
+fn main() -> () {
+    let a: i32;
+    a = 1;
+    let b: i32;
+    b = 2;
+    let tmp#2: i32;
+    tmp#2 = place_to_value!(a);
+    let tmp#1: i32;
+    tmp#1 = place_to_value!(b) * 3;
+    let tmp#3: i32;
+    tmp#3 = place_to_value!(tmp#2) + place_to_value!(tmp#1);
+    let tmp#4: i32;
+    tmp#4 = place_to_value!(a) - place_to_value!(b);
+    let tmp#5: i32;
+    tmp#5 = place_to_value!(tmp#3) - place_to_value!(tmp#4);
+    let c: i32;
+    c = place_to_value!(tmp#5) - 4;
+    let tmp#6: i32;
+    tmp#6 = -place_to_value!(a);
+    let tmp#7: i32;
+    tmp#7 = place_to_value!(b) << 2;
+    let tmp#8: i32;
+    tmp#8 = -place_to_value!(tmp#7);
+    let tmp#9: i32;
+    tmp#9 = place_to_value!(tmp#6) * place_to_value!(tmp#8);
+    let tmp#10: i32;
+    tmp#10 = place_to_value!(tmp#9) >> 1;
+    let tmp#11: i32;
+    tmp#11 = place_to_value!(tmp#10) & 255;
+    let tmp#12: i32;
+    tmp#12 = place_to_value!(a) ^ place_to_value!(b);
+    let d: i32;
+    d = place_to_value!(tmp#11) | place_to_value!(tmp#12);
+    let tmp#13: i32;
+    tmp#13 = place_to_value!(a) + 1;
+    let tmp#14: bool;
+    tmp#14 = place_to_value!(tmp#13) < place_to_value!(b);
+    let tmp#15: bool;
+    tmp#15 = !place_to_value!(tmp#14);
+    let tmp#16: bool;
+    tmp#16 = place_to_value!(b) != 2;
+    let e: bool;
+    e = place_to_value!(tmp#15) == place_to_value!(tmp#16);
+    let f: u8;
+    f = 3;
+    f += 1;
+    f <<= 2;
+    let tmp#17: u8;
+    tmp#17 = place_to_value!(f) % 3;
+    f ^= place_to_value!(tmp#17);
+}
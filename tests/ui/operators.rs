@@ -0,0 +1,11 @@
+fn main() {
+    let a: i32 = 1;
+    let b: i32 = 2;
+    let c: i32 = a + b * 3 - (a - b) - 4;
+    let d: i32 = -a * -(b << 2) >> 1 & 0xff | a ^ b;
+    let e: bool = !(a + 1 < b) == (b != 2);
+    let f: u8 = 3;
+    f += 1;
+    f <<= 2;
+    f ^= f % 3;
+}
@@ -0,0 +1,59 @@
+fn main() -> () {
+    let a: i32;
+    a = 7;
+    let b: i32;
+    b = -3;
+    let tmp#2: i32;
+    tmp#2 = place_to_value!(a);
+    let tmp#1: i32;
+    tmp#1 = place_to_value!(b) * 2;
+    let tmp#3: i32;
+    tmp#3 = place_to_value!(tmp#2) + place_to_value!(tmp#1);
+    print(place_to_value!(tmp#3));
+    let tmp#4: i32;
+    tmp#4 = place_to_value!(a) / place_to_value!(b);
+    print(place_to_value!(tmp#4));
+    let tmp#5: i32;
+    tmp#5 = place_to_value!(a) % place_to_value!(b);
+    print(place_to_value!(tmp#5));
+    let tmp#6: i32;
+    tmp#6 = -place_to_value!(a);
+    let tmp#7: i32;
+    tmp#7 = place_to_value!(tmp#6) >> 1;
+    print(place_to_value!(tmp#7));
+    let tmp#8: i32;
+    tmp#8 = place_to_value!(a) << 2;
+    let tmp#9: i32;
+    tmp#9 = place_to_value!(tmp#8) | 1;
+    print(place_to_value!(tmp#9));
+    let tmp#10: i32;
+    tmp#10 = place_to_value!(a) & 6;
+    let tmp#11: i32;
+    tmp#11 = place_to_value!(tmp#10) ^ 1;
+    print(place_to_value!(tmp#11));
+    let tmp#12: i32;
+    tmp#12 = !place_to_value!(a);
+    print(place_to_value!(tmp#12));
+    let x: u8;
+    x = 250;
+    x += 10;
+    print(place_to_value!(x));
+    x -= 5;
+    print(place_to_value!(x));
+    x *= 2;
+    print(place_to_value!(x));
+    let tmp#13: bool;
+    tmp#13 = place_to_value!(a) < place_to_value!(b);
+    print(place_to_value!(tmp#13));
+    let tmp#14: bool;
+    tmp#14 = place_to_value!(a) != 7;
+    let tmp#15: bool;
+    tmp#15 = !place_to_value!(tmp#14);
+    print(place_to_value!(tmp#15));
+    let tmp#16: bool;
+    tmp#16 = true ^ false;
+    print(place_to_value!(tmp#16));
+    let tmp#17: i8;
+    tmp#17 = -5i8;
+    print(place_to_value!(tmp#17));
+}
@@ -0,0 +1,23 @@
+//@ run
+fn main() {
+    let a: i32 = 7;
+    let b: i32 = -3;
+    print(a + b * 2);
+    print(a / b);
+    print(a % b);
+    print(-a >> 1);
+    print(a << 2 | 1);
+    print(a & 6 ^ 1);
+    print(!a);
+    let x: u8 = 250;
+    x += 10;
+    print(x);
+    x -= 5;
+    print(x);
+    x *= 2;
+    print(x);
+    print(a < b);
+    print(!(a != 7));
+    print(true ^ false);
+    print(-5i8);
+}
@@ -0,0 +1,6 @@
+//@ parse-fail
+//@ error-pattern: expected one of `enum#discriminant`, integer literal, or identifier, found `;`
+fn main() {
+    let x = (1, 2);
+    let y = x.;
+}
@@ -0,0 +1,15 @@
+//@ parse-fail
+//@ error-pattern: expected `(`, found `{`
+fn f {
+    let x = 1;
+}
+
+struct S {
+    a u8,
+}
+
+fn main() {
+    let x = (1, 2);
+    let y = x.;
+    let z = x.;
+}
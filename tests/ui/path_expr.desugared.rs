@@ -1,3 +1,3 @@
-fn f() -> () {
+fn f(foo: bool) -> () {
     foo;
 }
@@ -0,0 +1,12 @@
+fn main() -> () {
+    let x: u32;
+    x = 1;
+    let x#1: u32;
+    x#1 = place_to_value!(x) + 1;
+    {
+        let x#2: bool;
+        x#2 = true;
+        print(place_to_value!(x#2));
+    }
+    print(place_to_value!(x#1));
+}
@@ -0,0 +1,10 @@
+//@ run
+fn main() {
+    let x: u32 = 1;
+    let x: u32 = x + 1;
+    {
+        let x: bool = true;
+        print(x);
+    }
+    print(x);
+}
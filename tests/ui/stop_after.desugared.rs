@@ -0,0 +1,5 @@
+fn main() {
+    let x: u32 = 1;
+    let x#1: u32 = x + 1;
+    print(x#1);
+}
@@ -0,0 +1,6 @@
+//@ stop-after: resolve-names
+fn main() {
+    let x: u32 = 1;
+    let x: u32 = x + 1;
+    print(x);
+}
@@ -0,0 +1,54 @@
+struct Point {
+    x: bool,
+    y: (bool, bool),
+}
+struct Pair(bool, Point);
+struct Unit;
+struct Empty {}
+fn make() -> Point {
+    let tmp#4: (bool, bool);
+    tmp#4 = (false, true);
+    Point { x: true, y: place_to_value!(tmp#4) }
+}
+fn main() -> () {
+    let x: bool;
+    x = true;
+    let tmp#5: (bool, bool);
+    tmp#5 = (place_to_value!(x), place_to_value!(x));
+    let p: Point;
+    p = Point { y: place_to_value!(tmp#5), x: place_to_value!(x) };
+    p.x = place_to_value!(p.y.0);
+    let tmp#1: Point;
+    tmp#1 = make();
+    let r: &bool;
+    r = &tmp#1.x;
+    let tmp#6: Point;
+    tmp#6 = make();
+    let pair: Pair;
+    pair = Pair { 0: false, 1: place_to_value!(tmp#6) };
+    let y: bool;
+    y = place_to_value!(pair.1.y.1);
+    let u: Unit;
+    u = Unit {};
+    let e: Empty;
+    e = Empty {};
+    let tmp#2: Point;
+    let tmp#7: (bool, bool);
+    tmp#7 = (true, true);
+    tmp#2 = Point { x: false, y: place_to_value!(tmp#7) };
+    let q: &Point;
+    q = &tmp#2;
+    let tmp#10: bool;
+    tmp#10 = {
+        let tmp#3: Point;
+        let tmp#9: bool;
+        tmp#9 = place_to_value!(x);
+        let tmp#8: (bool, bool);
+        tmp#8 = (place_to_value!(x), place_to_value!(x));
+        tmp#3 = Point { x: place_to_value!(tmp#9), y: place_to_value!(tmp#8) };
+        place_to_value!(tmp#3.x)
+    };
+    if place_to_value!(tmp#10) {
+        print(place_to_value!(y));
+    } else {}
+}
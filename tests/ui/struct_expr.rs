@@ -0,0 +1,29 @@
+struct Point {
+    x: bool,
+    y: (bool, bool),
+}
+
+struct Pair(bool, Point);
+
+struct Unit;
+
+struct Empty {}
+
+fn make() -> Point {
+    Point { x: true, y: (false, true) }
+}
+
+fn main() {
+    let x: bool = true;
+    let p: Point = Point { y: (x, x), x };
+    p.x = p.y.0;
+    let r: &bool = &make().x;
+    let pair: Pair = Pair(false, make());
+    let y: bool = pair.1.y.1;
+    let u: Unit = Unit;
+    let e: Empty = Empty {};
+    let q: &Point = &Point { x: false, y: (true, true) };
+    if (Point { x, y: (x, x) }).x {
+        print(y);
+    }
+}
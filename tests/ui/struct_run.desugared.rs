@@ -0,0 +1,23 @@
+struct Point {
+    x: bool,
+    y: bool,
+}
+struct Pair(bool, Point);
+fn main() -> () {
+    let p: Point;
+    p = Point { x: true, y: false };
+    p.y = place_to_value!(p.x);
+    let r: &mut Point;
+    r = &mut p;
+    (*r).x = false;
+    print(place_to_value!(p.x));
+    print(place_to_value!(p.y));
+    let tmp#1: Point;
+    tmp#1 = Point { y: false, x: true };
+    let pair: Pair;
+    pair = Pair { 0: true, 1: place_to_value!(tmp#1) };
+    print(place_to_value!(pair.1.y));
+    let s: &Pair;
+    s = &pair;
+    print(place_to_value!((*s).0));
+}
@@ -0,0 +1,20 @@
+//@ run
+struct Point {
+    x: bool,
+    y: bool,
+}
+
+struct Pair(bool, Point);
+
+fn main() {
+    let p: Point = Point { x: true, y: false };
+    p.y = p.x;
+    let r: &mut Point = &mut p;
+    (*r).x = false;
+    print(p.x);
+    print(p.y);
+    let pair: Pair = Pair(true, Point { y: false, x: true });
+    print(pair.1.y);
+    let s: &Pair = &pair;
+    print((*s).0);
+}
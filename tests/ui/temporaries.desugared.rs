@@ -0,0 +1,43 @@
+fn f() -> bool {
+    true
+}
+fn main() -> () {
+    let tmp#1: bool;
+    tmp#1 = f();
+    let x: &bool;
+    x = &tmp#1;
+    let tmp#2: bool;
+    let tmp#3: (&bool,);
+    tmp#2 = f();
+    let tmp#9: &bool;
+    tmp#9 = &tmp#2;
+    tmp#3 = (place_to_value!(tmp#9),);
+    let y: &(&bool,);
+    y = &tmp#3;
+    {
+        let tmp#4: bool;
+        tmp#4 = f();
+        let tmp#5: &bool;
+        tmp#5 = &tmp#4;
+        *tmp#5;
+    }
+    let tmp#10: bool;
+    tmp#10 = {
+        let tmp#6: bool;
+        tmp#6 = f();
+        let tmp#7: &bool;
+        tmp#7 = &tmp#6;
+        place_to_value!(*tmp#7)
+    };
+    if place_to_value!(tmp#10) {
+        print(1);
+    } else {}
+    let z: bool;
+    z = {
+        let tmp#8: bool;
+        tmp#8 = f();
+        let w: &bool;
+        w = &tmp#8;
+        place_to_value!(*w)
+    };
+}
@@ -0,0 +1,16 @@
+fn f() -> bool {
+    true
+}
+
+fn main() {
+    let x = &f();
+    let y = &(&f(),);
+    *&f();
+    if *&f() {
+        print(1);
+    }
+    let z = {
+        let w = &f();
+        *w
+    };
+}
@@ -5,8 +5,10 @@ fn main() -> () {
     let one: (bool,);
     one = (true,);
     one.0 = false;
+    let tmp#1: (bool,);
+    tmp#1 = (true,);
     let nested: ((bool,), bool);
-    nested = ((true,), false);
+    nested = (place_to_value!(tmp#1), false);
     nested.0.0 = false;
     let not_tuple: bool;
     not_tuple = true;
@@ -0,0 +1,6 @@
+//@ desugar-fail
+//@ error-pattern: type annotations needed
+fn main() {
+    let x;
+    let y = &x;
+}
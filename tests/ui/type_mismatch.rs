@@ -0,0 +1,12 @@
+//@ desugar-fail
+//@ error-pattern: mismatched types
+struct Point {
+    x: bool,
+    y: bool,
+}
+
+fn main() {
+    let p = Point { x: true, y: false };
+    let n = p.x;
+    n += 1;
+}
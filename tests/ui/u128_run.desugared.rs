@@ -0,0 +1,37 @@
+fn main() -> () {
+    let max: u128;
+    max = !0u128;
+    print(place_to_value!(max));
+    let zero: u128;
+    zero = 0;
+    let tmp#1: u128;
+    tmp#1 = place_to_value!(zero) - 1;
+    print(place_to_value!(tmp#1));
+    let tmp#2: u128;
+    tmp#2 = place_to_value!(max) >> 127;
+    print(place_to_value!(tmp#2));
+    let tmp#3: u128;
+    tmp#3 = place_to_value!(max) >> 1;
+    print(place_to_value!(tmp#3));
+    let tmp#4: u128;
+    tmp#4 = place_to_value!(max) + 1;
+    print(place_to_value!(tmp#4));
+    let tmp#5: u128;
+    tmp#5 = place_to_value!(max) / 10;
+    print(place_to_value!(tmp#5));
+    let tmp#6: u128;
+    tmp#6 = place_to_value!(max) % 10;
+    print(place_to_value!(tmp#6));
+    let tmp#7: bool;
+    tmp#7 = place_to_value!(max) > 1;
+    print(place_to_value!(tmp#7));
+    let min: i128;
+    min = -170141183460469231731687303715884105728;
+    print(place_to_value!(min));
+    let tmp#8: i128;
+    tmp#8 = place_to_value!(min) >> 126;
+    print(place_to_value!(tmp#8));
+    let tmp#9: i128;
+    tmp#9 = place_to_value!(min) - 1;
+    print(place_to_value!(tmp#9));
+}
@@ -0,0 +1,17 @@
+//@ run
+fn main() {
+    let max: u128 = !0u128;
+    print(max);
+    let zero: u128 = 0;
+    print(zero - 1);
+    print(max >> 127);
+    print(max >> 1);
+    print(max + 1);
+    print(max / 10);
+    print(max % 10);
+    print(max > 1);
+    let min: i128 = -170141183460469231731687303715884105728;
+    print(min);
+    print(min >> 126);
+    print(min - 1);
+}
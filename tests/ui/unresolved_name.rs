@@ -0,0 +1,5 @@
+//@ desugar-fail
+//@ error-pattern: cannot find value `y`
+fn main() {
+    let x = y;
+}
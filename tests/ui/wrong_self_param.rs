@@ -1,2 +1,3 @@
-//@ known-failure
+//@ desugar-fail
+//@ error-pattern: only allowed as the first function argument
 fn foo(x: bool, self) {}
@@ -15,13 +15,24 @@ static IDENT_RE: LazyLock<Regex> =
 pub struct LexerSpec {
     token_type: Option<String>,
     start: Option<String>,
+    location: Option<String>,
     tokens: Vec<TokenSpec>,
     token_by_display: HashMap<String, usize>,
     token_by_symbol: HashMap<String, usize>,
-    precedence: Vec<String>,
+    precedence: Vec<PrecedenceLevel>,
     allow: Vec<String>,
 }
 
+/// One precedence directive, from lowest to highest precedence.
+#[derive(Debug)]
+struct PrecedenceLevel {
+    /// `%left`, `%right` or `%precedence`.
+    directive: &'static str,
+    terminals: Vec<String>,
+}
+
+const PRECEDENCE_DIRECTIVES: [&str; 3] = ["%left", "%right", "%precedence"];
+
 #[derive(Debug)]
 pub struct TokenSpec {
     pub display: String,
@@ -57,9 +68,23 @@ fn parse_lexer_block(input: &str, spec: &mut LexerSpec) -> Result<()> {
             spec.token_type = Some(trim_directive(rest)?.to_string());
         } else if let Some(rest) = line.strip_prefix("%start") {
             spec.start = Some(trim_directive(rest)?.to_string());
-        } else if let Some(rest) = line.strip_prefix("%precedence") {
-            let terminal = trim_directive(rest)?;
-            spec.precedence.push(terminal.to_string());
+        } else if let Some(rest) = line.strip_prefix("%location") {
+            spec.location = Some(trim_directive(rest)?.to_string());
+        } else if let Some((directive, rest)) = PRECEDENCE_DIRECTIVES
+            .iter()
+            .find_map(|directive| Some((*directive, line.strip_prefix(directive)?)))
+        {
+            let terminals = trim_directive(rest)?
+                .split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            if terminals.is_empty() {
+                bail!("expected at least one terminal after `{directive}`");
+            }
+            spec.precedence.push(PrecedenceLevel {
+                directive,
+                terminals,
+            });
         } else if let Some(rest) = line.strip_prefix("%allow") {
             spec.allow.push(trim_directive(rest)?.to_string());
         } else {
@@ -163,6 +188,9 @@ impl LexerSpec {
         let mut out = String::new();
         writeln!(out, "%tokentype {token_type};")?;
         writeln!(out, "%start {start};")?;
+        if let Some(location) = &self.location {
+            writeln!(out, "%location {location};")?;
+        }
         writeln!(out)?;
         for token in &self.tokens {
             writeln!(
@@ -173,9 +201,13 @@ impl LexerSpec {
             )?;
         }
         writeln!(out)?;
-        for terminal in &self.precedence {
-            let symbol = self.resolve_terminal(terminal)?;
-            writeln!(out, "%precedence {symbol};")?;
+        for level in &self.precedence {
+            let symbols = level
+                .terminals
+                .iter()
+                .map(|terminal| self.resolve_terminal(terminal))
+                .collect::<Result<Vec<_>>>()?;
+            writeln!(out, "{} {};", level.directive, symbols.join(" "))?;
         }
         writeln!(out)?;
         for allow in &self.allow {
@@ -184,6 +216,32 @@ impl LexerSpec {
         Ok(out)
     }
 
+    /// A Rust function listing one token of each kind, along with how error messages name it. The
+    /// payloads of the tokens are `Default::default()`.
+    pub fn rust_terminals(&self) -> Result<String> {
+        let token_type = self
+            .token_type
+            .as_deref()
+            .ok_or_else(|| anyhow!("missing `%tokentype` in lexer block"))?;
+        let mut out = String::new();
+        writeln!(
+            out,
+            "fn terminals() -> Vec<(&'static str, {token_type})> {{"
+        )?;
+        writeln!(out, "    vec![")?;
+        for token in &self.tokens {
+            writeln!(
+                out,
+                "        ({:?}, {}),",
+                token.description(),
+                token.rust_example()
+            )?;
+        }
+        writeln!(out, "    ]")?;
+        writeln!(out, "}}")?;
+        Ok(out)
+    }
+
     fn resolve_terminal(&self, terminal: &str) -> Result<&str> {
         let display = terminal
             .strip_prefix('`')
@@ -213,6 +271,23 @@ impl TokenSpec {
         }
     }
 
+    /// Keywords and punctuation are quoted, named tokens are spelled out: `IDENTIFIER` becomes
+    /// "identifier".
+    fn description(&self) -> String {
+        if is_token_name(&self.display) {
+            self.display.to_ascii_lowercase().replace('_', " ")
+        } else {
+            format!("`{}`", self.display)
+        }
+    }
+
+    fn rust_example(&self) -> String {
+        match &self.payload {
+            Some(_) => format!("Token::{}(Default::default())", self.variant),
+            None => format!("Token::{}", self.variant),
+        }
+    }
+
     fn rust_pattern(&self, variable: &str) -> String {
         match &self.payload {
             Some(_) => format!("Token::{}({variable})", self.variant),
@@ -371,6 +446,10 @@ fn render_action(action: &str, bindings: &[TokenBinding], lexer: &LexerSpec) ->
         )?;
         writeln!(out, "        }};")?;
     }
+    // Actions refer to the location of the source matched by the production as `span`.
+    if action_names(action).contains("span") {
+        writeln!(out, "        let span = @$;")?;
+    }
     for line in action.lines() {
         writeln!(out, "        {line}")?;
     }
@@ -759,3 +838,52 @@ fn should_discard(
     };
     !action_names.contains(&token.symbol)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_associativity_directives() {
+        let mut spec = LexerSpec::default();
+        parse_lexer_block(
+            "%tokentype Token;\n%start Expr;\n`+` Plus;\n`-` Minus;\n`=` Eq;\nPREFIX Prefix;\n\
+             %right `=`;\n%left `+` `-`;\n%precedence PREFIX;\n",
+            &mut spec,
+        )
+        .unwrap();
+        let declarations = spec.rustylr_declarations().unwrap();
+        assert!(declarations.contains("%right EQ;\n%left PLUS MINUS;\n%precedence PREFIX;\n"));
+    }
+
+    #[test]
+    fn binds_span_of_production() {
+        let mut spec = LexerSpec::default();
+        parse_lexer_block(
+            "%tokentype Token;\n%start Expr;\n%location Span;\n",
+            &mut spec,
+        )
+        .unwrap();
+        let declarations = spec.rustylr_declarations().unwrap();
+        assert!(declarations.contains("%start Expr;\n%location Span;\n"));
+        let action = render_action("Expr { span }", &[], &spec).unwrap();
+        assert!(action.contains("let span = @$;\n        Expr { span }"));
+    }
+
+    #[test]
+    fn lists_terminals_with_their_description() {
+        let mut spec = LexerSpec::default();
+        parse_lexer_block(
+            "%tokentype Token;\n`fn` Fn;\n`;` Semicolon;\nINTEGER_LITERAL IntegerLiteral(u128);\n",
+            &mut spec,
+        )
+        .unwrap();
+        let terminals = spec.rust_terminals().unwrap();
+        assert!(terminals.contains("(\"`fn`\", Token::Fn),\n"));
+        assert!(terminals.contains("(\"`;`\", Token::Semicolon),\n"));
+        assert!(
+            terminals
+                .contains("(\"integer literal\", Token::IntegerLiteral(Default::default())),\n")
+        );
+    }
+}
@@ -18,21 +18,33 @@ const RULE_PAGE_PREFIXES: &[(&str, &str)] = &[
     ("associated", "items/associated-items.html"),
     ("coerce", "type-coercions.html"),
     ("destructors", "destructors.html"),
+    ("expr.arith-logic", "expressions/operator-expr.html"),
     ("expr.call", "expressions/call-expr.html"),
+    ("expr.cmp", "expressions/operator-expr.html"),
     ("expr.deref", "expressions/operator-expr.html"),
+    ("expr.field", "expressions/field-expr.html"),
     ("expr.if", "expressions/if-expr.html"),
+    ("expr.literal", "expressions/literal-expr.html"),
+    ("expr.loop", "expressions/loop-expr.html"),
+    ("expr.match", "expressions/match-expr.html"),
     ("expr.method", "expressions/method-call-expr.html"),
     ("expr.operator", "expressions/operator-expr.html"),
     ("expr.paren", "expressions/grouped-expr.html"),
     ("expr.place-value", "expressions.html"),
+    ("expr.precedence", "expressions.html"),
     ("expr.struct", "expressions/struct-expr.html"),
     ("items.associated", "items/associated-items.html"),
+    ("items.enum", "items/enumerations.html"),
     ("items.fn", "items/functions.html"),
+    ("items.struct", "items/structs.html"),
     ("items.union", "items/unions.html"),
+    ("lex.token", "tokens.html"),
     ("macro.decl", "macros-by-example.html"),
     ("names", "names.html"),
+    ("patterns", "patterns.html"),
     ("type.closure", "types/closure.html"),
     ("type.fn-item", "types/function-item.html"),
+    ("type.numeric", "types/numeric.html"),
 ];
 
 pub fn handle_preprocessing() -> anyhow::Result<()> {